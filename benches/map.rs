@@ -0,0 +1,67 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use rustyrogue::generator::layout::LayoutKind;
+use rustyrogue::generator::map::MapGenerator;
+use rustyrogue::generator::prefab::{PrefabLibrary, PREFAB_DIRECTORY};
+use rustyrogue::map::types::Point;
+use rustyrogue::map::Map;
+use std::hint::black_box;
+
+// Same size and room sizes the game plays on
+const WIDTH: usize = 400;
+const HEIGHT: usize = 200;
+const MIN_ROOM_SIZE: usize = 10;
+const MAX_ROOM_SIZE: usize = 25;
+const FOV_RADIUS: u32 = 10;
+const SEED: u64 = 7;
+
+fn generator() -> MapGenerator {
+    let mut map_generator = MapGenerator::new(WIDTH, HEIGHT);
+    map_generator
+        .set_prefabs(PrefabLibrary::load(PREFAB_DIRECTORY).unwrap_or_default())
+        .set_layout(LayoutKind::Bsp);
+    map_generator
+}
+
+fn generated_map() -> (Map, Point) {
+    let mut map_generator = generator();
+    map_generator.generate(MIN_ROOM_SIZE, MAX_ROOM_SIZE);
+    let spawn = map_generator
+        .get_spawn_room()
+        .expect("No rooms generated")
+        .center();
+    (Map::from_tiles(map_generator.into_dungeon()), spawn)
+}
+
+fn fov(c: &mut Criterion) {
+    let (mut map, spawn) = generated_map();
+    c.bench_function("fov 400x200", |b| {
+        b.iter(|| map.update_fov(black_box(spawn), FOV_RADIUS))
+    });
+}
+
+fn curses(c: &mut Criterion) {
+    let (mut map, _) = generated_map();
+    c.bench_function("obelisk curses 400x200", |b| {
+        b.iter(|| map.apply_obelisk_curses())
+    });
+}
+
+// The same level with rooms populated in parallel and one at a time
+fn generate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate");
+    group.sample_size(10);
+    for (name, parallel) in [("bsp 400x200", true), ("bsp 400x200 sequential", false)] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let mut map_generator = generator();
+                map_generator.set_seed(SEED).set_parallel(parallel);
+                map_generator.generate(MIN_ROOM_SIZE, MAX_ROOM_SIZE);
+                black_box(map_generator.into_dungeon())
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, fov, curses, generate);
+criterion_main!(benches);
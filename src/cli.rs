@@ -0,0 +1,327 @@
+use crate::game::{Difficulty, GameSetup};
+use crate::generator::layout::LayoutKind;
+use crate::generator::map::DungeonSize;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: rustyrogue [command] [options]
+
+Without a command the title menu opens.
+
+Commands:
+  play [options]        Start a run right away, back to the menu when it's over
+      --seed <number>           Seed for the whole run
+      --difficulty <easy|normal|hard>
+      --layout <bsp|caves|maze|mixed>
+                                Layout of the first level, later ones go by depth
+      --size <width>x<height>   Size of every level (default 400x200)
+      --rooms <min>-<max>       Room sizes (default 10-25)
+      --map <file>              Play a hand made map
+      --endless                 Play an endless world
+      --load <file>             Pick up a saved run where it was left
+  generate [options]    Generate a single level and look at it
+      --seed <number>           Seed for the level
+      --layout <bsp|caves|maze|mixed>
+      --size <width>x<height>   Size of the level (default 300x120)
+      --rooms <min>-<max>       Room sizes (default 5-20)
+      --depth <number>          Depth to generate for (default 1)
+      --out <file>              Write the level to a file instead of viewing it
+  stats [options]       Generate many levels and print statistics about them
+      --count <number>          How many levels (default 100)
+      --seed <number>           Seed of the first level, the rest follow on
+      --layout <bsp|caves|maze|mixed>
+      --size <width>x<height>   Size of the levels (default 300x120)
+      --rooms <min>-<max>       Room sizes (default 5-20)
+      --depth <number>          Depth to generate for (default 1)
+      --csv                     Print CSV instead of a table
+  view <file>           Look around a map file in the viewer
+  replay <file>         Watch a saved run play out again
+  scores                Print the high score table (or --scores)
+  help                  Print this message (or --help)
+";
+
+// What the command line asked for
+#[derive(Debug)]
+pub enum Command {
+    Menu,
+    Play(GameSetup),
+    Load(PathBuf),
+    Generate(GenerateOptions),
+    Stats(StatsOptions),
+    View(PathBuf),
+    Replay(PathBuf),
+    Scores,
+    Help,
+}
+
+#[derive(Debug)]
+pub struct GenerateOptions {
+    pub seed: Option<u64>,
+    pub layout: Option<LayoutKind>,
+    pub size: DungeonSize,
+    pub depth: u32,
+    pub out: Option<PathBuf>,
+}
+
+// Levels are generated with consecutive seeds from `seed`
+#[derive(Debug)]
+pub struct StatsOptions {
+    pub count: usize,
+    pub seed: Option<u64>,
+    pub layout: Option<LayoutKind>,
+    pub size: DungeonSize,
+    pub depth: u32,
+    pub csv: bool,
+}
+
+// The `--name value` pairs after a command, checked against the names it takes
+struct Options {
+    command: &'static str,
+    values: Vec<(String, Option<String>)>,
+}
+
+impl Options {
+    // `flags` take no value, `valued` take exactly one
+    fn parse(
+        command: &'static str,
+        args: &[String],
+        flags: &[&str],
+        valued: &[&str],
+    ) -> Result<Self, String> {
+        let mut values: Vec<(String, Option<String>)> = Vec::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let name = arg.as_str();
+            let value = if flags.contains(&name) {
+                None
+            } else if valued.contains(&name) {
+                let value = args
+                    .next()
+                    .filter(|value| !value.starts_with("--"))
+                    .ok_or_else(|| format!("{} needs a value", name))?;
+                Some(value.clone())
+            } else if name.starts_with("--") {
+                return Err(format!("Unknown option {} for {}", name, command));
+            } else {
+                return Err(format!("Unexpected argument '{}' for {}", name, command));
+            };
+            if values.iter().any(|(seen, _)| seen == name) {
+                return Err(format!("{} is given more than once", name));
+            }
+            values.push((name.to_string(), value));
+        }
+        Ok(Options { command, values })
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.values.iter().any(|(seen, _)| seen == name)
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(seen, _)| seen == name)
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    // Parses the value of `name` if it was given
+    fn parse_value<T>(
+        &self,
+        name: &str,
+        parse: impl Fn(&str) -> Result<T, String>,
+    ) -> Result<Option<T>, String> {
+        self.get(name)
+            .map(|value| parse(value).map_err(|e| format!("Invalid {} '{}': {}", name, value, e)))
+            .transpose()
+    }
+
+    // None of `names` may be combined with `name`
+    fn conflicts(&self, name: &str, names: &[&str], reason: &str) -> Result<(), String> {
+        if !self.has(name) {
+            return Ok(());
+        }
+        match names.iter().find(|other| self.has(other)) {
+            Some(other) => Err(format!(
+                "{} {} can't be combined with {}, {}",
+                self.command, name, other, reason
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn size(&self, default: DungeonSize) -> Result<DungeonSize, String> {
+        let mut size = default;
+        if let Some((width, height)) = self.parse_value("--size", |value| parse_pair(value, 'x'))? {
+            size.width = width;
+            size.height = height;
+        }
+        if let Some((min, max)) = self.parse_value("--rooms", |value| parse_pair(value, '-'))? {
+            size.min_room_size = min;
+            size.max_room_size = max;
+        }
+        size.validate()?;
+        Ok(size)
+    }
+
+    fn depth(&self) -> Result<u32, String> {
+        Ok(self
+            .parse_value("--depth", |value| {
+                match value.parse::<u32>().map_err(|e| e.to_string())? {
+                    0 => Err("the dungeon starts at depth 1".to_string()),
+                    depth => Ok(depth),
+                }
+            })?
+            .unwrap_or(1))
+    }
+}
+
+// `400x200`, `10-25`
+fn parse_pair(value: &str, separator: char) -> Result<(usize, usize), String> {
+    let (first, second) = value
+        .split_once(separator)
+        .ok_or_else(|| format!("expected two numbers separated by '{}'", separator))?;
+    let number = |part: &str| {
+        part.trim()
+            .parse::<usize>()
+            .map_err(|e| format!("'{}' {}", part, e))
+    };
+    Ok((number(first)?, number(second)?))
+}
+
+fn parse_seed(value: &str) -> Result<u64, String> {
+    value.parse().map_err(|e| format!("{}", e))
+}
+
+// A single file argument and nothing else
+fn parse_file(command: &str, args: &[String]) -> Result<PathBuf, String> {
+    match args {
+        [file] if !file.starts_with("--") => Ok(PathBuf::from(file)),
+        [] => Err(format!("{} needs a file", command)),
+        _ => Err(format!("{} takes a single file and no options", command)),
+    }
+}
+
+// `args` without the program name
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Ok(Command::Menu);
+    };
+
+    match command.as_str() {
+        "play" => parse_play(rest),
+        "generate" => parse_generate(rest),
+        "stats" => parse_stats(rest),
+        "view" => parse_file("view", rest).map(Command::View),
+        "replay" => parse_file("replay", rest).map(Command::Replay),
+        "scores" | "--scores" if rest.is_empty() => Ok(Command::Scores),
+        "help" | "--help" | "-h" if rest.is_empty() => Ok(Command::Help),
+        "scores" | "--scores" | "help" | "--help" | "-h" => {
+            Err(format!("{} takes no arguments", command))
+        }
+        other => Err(format!("Unknown command '{}'", other)),
+    }
+}
+
+fn parse_play(args: &[String]) -> Result<Command, String> {
+    let options = Options::parse(
+        "play",
+        args,
+        &["--endless"],
+        &[
+            "--seed",
+            "--difficulty",
+            "--layout",
+            "--size",
+            "--rooms",
+            "--map",
+            "--load",
+        ],
+    )?;
+
+    options.conflicts(
+        "--load",
+        &[
+            "--seed",
+            "--difficulty",
+            "--layout",
+            "--size",
+            "--rooms",
+            "--map",
+            "--endless",
+        ],
+        "a saved run keeps the setup it was started with",
+    )?;
+    options.conflicts(
+        "--map",
+        &["--endless", "--layout", "--size", "--rooms"],
+        "the map is already made",
+    )?;
+    options.conflicts(
+        "--endless",
+        &["--layout", "--size", "--rooms"],
+        "endless worlds are made of fixed size chunks",
+    )?;
+
+    if let Some(file) = options.get("--load") {
+        return Ok(Command::Load(PathBuf::from(file)));
+    }
+
+    Ok(Command::Play(GameSetup {
+        seed: options.parse_value("--seed", parse_seed)?,
+        difficulty: options
+            .parse_value("--difficulty", |value| value.parse::<Difficulty>())?
+            .unwrap_or_default(),
+        layout: options.parse_value("--layout", |value| value.parse::<LayoutKind>())?,
+        size: options.size(DungeonSize::LEVEL)?,
+        endless: options.has("--endless"),
+        map: options.get("--map").map(PathBuf::from),
+    }))
+}
+
+fn parse_generate(args: &[String]) -> Result<Command, String> {
+    let options = Options::parse(
+        "generate",
+        args,
+        &[],
+        &[
+            "--seed", "--layout", "--size", "--rooms", "--depth", "--out",
+        ],
+    )?;
+
+    Ok(Command::Generate(GenerateOptions {
+        seed: options.parse_value("--seed", parse_seed)?,
+        layout: options.parse_value("--layout", |value| value.parse::<LayoutKind>())?,
+        size: options.size(DungeonSize::VIEWER)?,
+        depth: options.depth()?,
+        out: options.get("--out").map(PathBuf::from),
+    }))
+}
+
+fn parse_stats(args: &[String]) -> Result<Command, String> {
+    let options = Options::parse(
+        "stats",
+        args,
+        &["--csv"],
+        &[
+            "--count", "--seed", "--layout", "--size", "--rooms", "--depth",
+        ],
+    )?;
+
+    let count = options
+        .parse_value("--count", |value| {
+            match value.parse::<usize>().map_err(|e| e.to_string())? {
+                0 => Err("at least one level is needed".to_string()),
+                count => Ok(count),
+            }
+        })?
+        .unwrap_or(100);
+
+    Ok(Command::Stats(StatsOptions {
+        count,
+        seed: options.parse_value("--seed", parse_seed)?,
+        layout: options.parse_value("--layout", |value| value.parse::<LayoutKind>())?,
+        size: options.size(DungeonSize::VIEWER)?,
+        depth: options.depth()?,
+        csv: options.has("--csv"),
+    }))
+}
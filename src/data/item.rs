@@ -0,0 +1,208 @@
+use crate::data::{parse_sections, Section};
+use crate::item::{Item, ItemCategory, POTION_APPEARANCES, SCROLL_LABELS};
+use ratatui::style::Color as RatatuiColor;
+
+// What using an item does
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ItemEffect {
+    Heal(u32),
+    Reveal(Option<u32>), // the level around the player, or all of it without a radius
+    CureCurse(u32),      // lifts curses and wards against them for this many turns
+    Teleport,
+    Light { radius: u32, turns: u32 },
+    BanishObelisk,
+    Unlock,  // what keys do, they're used up on their lock
+    Nothing, // artefacts are only carried
+}
+
+impl ItemEffect {
+    // `heal 15`, `reveal`, `reveal 40`, `cure_curse 30`, `teleport`, `light 20 30`,
+    // `banish_obelisk`, `unlock`, `none`
+    fn parse(value: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = value.split_whitespace().collect();
+        let number = |index: usize| -> Result<u32, String> {
+            parts
+                .get(index)
+                .and_then(|part| part.parse().ok())
+                .ok_or_else(|| format!("effect '{}' is missing a number", value))
+        };
+        let arguments = |count: usize| -> Result<(), String> {
+            if parts.len() == count + 1 {
+                Ok(())
+            } else {
+                Err(format!(
+                    "effect '{}' should have {} number(s) after it",
+                    value, count
+                ))
+            }
+        };
+
+        let effect = match parts.first().copied() {
+            Some("heal") => {
+                arguments(1)?;
+                ItemEffect::Heal(number(1)?)
+            }
+            Some("reveal") if parts.len() == 1 => ItemEffect::Reveal(None),
+            Some("reveal") => {
+                arguments(1)?;
+                ItemEffect::Reveal(Some(number(1)?))
+            }
+            Some("cure_curse") => {
+                arguments(1)?;
+                ItemEffect::CureCurse(number(1)?)
+            }
+            Some("teleport") => {
+                arguments(0)?;
+                ItemEffect::Teleport
+            }
+            Some("light") => {
+                arguments(2)?;
+                ItemEffect::Light {
+                    radius: number(1)?,
+                    turns: number(2)?,
+                }
+            }
+            Some("banish_obelisk") => {
+                arguments(0)?;
+                ItemEffect::BanishObelisk
+            }
+            Some("unlock") => {
+                arguments(0)?;
+                ItemEffect::Unlock
+            }
+            Some("none") => {
+                arguments(0)?;
+                ItemEffect::Nothing
+            }
+            _ => return Err(format!("unknown effect '{}'", value)),
+        };
+        Ok(effect)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ItemDef {
+    pub name: String,
+    pub category: ItemCategory,
+    pub glyph: char,
+    pub colour: RatatuiColor,
+    pub effect: ItemEffect,
+    pub rarity: u32, // the least rare secret it can be found in, same orders of magnitude as secrets
+}
+
+impl ItemDef {
+    fn parse(mut section: Section) -> Result<Self, String> {
+        let category = section.required_with("category", |value| match value {
+            "potion" => Ok(ItemCategory::Potion),
+            "scroll" => Ok(ItemCategory::Scroll),
+            "key" => Ok(ItemCategory::Key),
+            "artefact" => Ok(ItemCategory::Artefact),
+            _ => Err(format!(
+                "unknown category '{}', expected potion, scroll, key or artefact",
+                value
+            )),
+        })?;
+        let def = ItemDef {
+            category,
+            glyph: section.glyph()?,
+            colour: section.colour()?,
+            effect: section.required_with("effect", ItemEffect::parse)?,
+            // Keys and artefacts are left by the generator rather than found in secrets
+            rarity: match category {
+                ItemCategory::Key | ItemCategory::Artefact => {
+                    section.optional("rarity")?.unwrap_or(1000)
+                }
+                _ => section.required("rarity")?,
+            },
+            name: section.name.clone(),
+        };
+
+        let line = section.line;
+        section.finish()?;
+
+        // Saved chunks refer to items by name, with underscores for spaces
+        if def.name.contains([':', '_']) {
+            return Err(format!(
+                "line {}: item name '{}' can't contain ':' or '_'",
+                line, def.name
+            ));
+        }
+        if (def.category == ItemCategory::Key) != (def.effect == ItemEffect::Unlock) {
+            return Err(format!(
+                "line {}: '{}' has to be a key to unlock, and keys can only unlock",
+                line, def.name
+            ));
+        }
+        if (def.category == ItemCategory::Artefact) != (def.effect == ItemEffect::Nothing) {
+            return Err(format!(
+                "line {}: '{}' has to be an artefact to do nothing, and artefacts can only do nothing",
+                line, def.name
+            ));
+        }
+        if !matches!(def.rarity, 1 | 10 | 100 | 1000) {
+            return Err(format!(
+                "line {}: rarity of '{}' has to be 1, 10, 100 or 1000",
+                line, def.name
+            ));
+        }
+        Ok(def)
+    }
+}
+
+// Every item the game knows about
+#[derive(Debug)]
+pub struct ItemBook {
+    items: Vec<ItemDef>,
+}
+
+impl ItemBook {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let items = parse_sections(source)?
+            .into_iter()
+            .map(ItemDef::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Even the most common secret has to hold something
+        if !items.iter().any(|item| {
+            item.rarity == 1 && matches!(item.category, ItemCategory::Potion | ItemCategory::Scroll)
+        }) {
+            return Err("at least one item needs rarity 1".to_string());
+        }
+        // Every unidentified item needs an appearance of its own
+        let count = |category| {
+            items
+                .iter()
+                .filter(|item| item.category == category)
+                .count()
+        };
+        if count(ItemCategory::Potion) > POTION_APPEARANCES.len() {
+            return Err(format!(
+                "at most {} potions can be defined",
+                POTION_APPEARANCES.len()
+            ));
+        }
+        if count(ItemCategory::Scroll) > SCROLL_LABELS.len() {
+            return Err(format!(
+                "at most {} scrolls can be defined",
+                SCROLL_LABELS.len()
+            ));
+        }
+        if items.len() > u8::MAX as usize {
+            return Err(format!("at most {} items can be defined", u8::MAX));
+        }
+
+        Ok(ItemBook { items })
+    }
+
+    pub fn get(&self, item: Item) -> &ItemDef {
+        &self.items[item.index()]
+    }
+
+    pub fn all(&self) -> impl Iterator<Item = Item> {
+        (0..self.items.len() as u8).map(Item::from_index)
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Option<Item> {
+        self.all().find(|&item| self.get(item).name == name)
+    }
+}
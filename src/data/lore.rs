@@ -0,0 +1,46 @@
+use crate::data::parse_sections;
+use rand::Rng;
+
+#[derive(Clone, Debug)]
+pub struct Lore {
+    pub title: String,
+    pub text: String,
+}
+
+// Everything the signs in the dungeon can say
+#[derive(Debug)]
+pub struct LoreBook {
+    entries: Vec<Lore>,
+}
+
+impl LoreBook {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let entries = parse_sections(source)?
+            .into_iter()
+            .map(|mut section| {
+                let text = section.required("text")?;
+                let title = section.name.clone();
+                section.finish()?;
+                Ok(Lore { title, text })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        if entries.is_empty() {
+            return Err("no lore defined".to_string());
+        }
+        if entries.len() > u16::MAX as usize {
+            return Err(format!("at most {} lore entries can be defined", u16::MAX));
+        }
+
+        Ok(LoreBook { entries })
+    }
+
+    // Signs keep the index, unknown ones are read as the first entry
+    pub fn get(&self, index: u16) -> &Lore {
+        self.entries.get(index as usize).unwrap_or(&self.entries[0])
+    }
+
+    pub fn random<R: Rng>(&self, rng: &mut R) -> u16 {
+        rng.gen_range(0..self.entries.len()) as u16
+    }
+}
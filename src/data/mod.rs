@@ -0,0 +1,262 @@
+pub mod item;
+pub mod lore;
+pub mod monster;
+pub mod spawn;
+
+use item::ItemBook;
+use lore::LoreBook;
+use monster::Bestiary;
+use ratatui::style::Color as RatatuiColor;
+use sdl2::pixels::Color as SDLColor;
+use spawn::SpawnTables;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+// Monster, item, spawn and lore definitions are read from here at startup so balancing
+// doesn't need a rebuild
+pub const DATA_DIRECTORY: &str = "data";
+const MONSTERS_FILE: &str = "monsters.txt";
+const ITEMS_FILE: &str = "items.txt";
+const SPAWNS_FILE: &str = "spawns.txt";
+const LORE_FILE: &str = "lore.txt";
+
+// The shipped definitions, used when nothing was installed (tests, benchmarks)
+const BUILTIN_MONSTERS: &str = include_str!("../../data/monsters.txt");
+const BUILTIN_ITEMS: &str = include_str!("../../data/items.txt");
+const BUILTIN_SPAWNS: &str = include_str!("../../data/spawns.txt");
+const BUILTIN_LORE: &str = include_str!("../../data/lore.txt");
+
+static GAME_DATA: OnceLock<GameData> = OnceLock::new();
+
+pub struct GameData {
+    pub monsters: Bestiary,
+    pub items: ItemBook,
+    pub spawns: SpawnTables,
+    pub lore: LoreBook,
+}
+
+impl GameData {
+    // Load and check every definition file in `directory`
+    pub fn load<P: AsRef<Path>>(directory: P) -> io::Result<Self> {
+        let directory = directory.as_ref();
+        let read = |file: &str| {
+            let path = directory.join(file);
+            fs::read_to_string(&path).map(|source| (path, source))
+        };
+
+        let (path, source) = read(MONSTERS_FILE)?;
+        let monsters = Bestiary::parse(&source).map_err(|e| invalid(&path, e))?;
+        let (path, source) = read(ITEMS_FILE)?;
+        let items = ItemBook::parse(&source).map_err(|e| invalid(&path, e))?;
+        // Spawn tables can name monsters, so they come last
+        let (path, source) = read(SPAWNS_FILE)?;
+        let spawns =
+            SpawnTables::parse(&source, &monsters, &items).map_err(|e| invalid(&path, e))?;
+        let (path, source) = read(LORE_FILE)?;
+        let lore = LoreBook::parse(&source).map_err(|e| invalid(&path, e))?;
+
+        Ok(GameData {
+            monsters,
+            items,
+            spawns,
+            lore,
+        })
+    }
+
+    fn builtin() -> Self {
+        let monsters = Bestiary::parse(BUILTIN_MONSTERS).expect("Invalid builtin monsters");
+        let items = ItemBook::parse(BUILTIN_ITEMS).expect("Invalid builtin items");
+        GameData {
+            spawns: SpawnTables::parse(BUILTIN_SPAWNS, &monsters, &items)
+                .expect("Invalid builtin spawn tables"),
+            lore: LoreBook::parse(BUILTIN_LORE).expect("Invalid builtin lore"),
+            monsters,
+            items,
+        }
+    }
+
+    // Make `data` the definitions everything reads from. Has to happen before
+    // the first map is built, afterwards the definitions are fixed.
+    pub fn install(self) -> io::Result<()> {
+        GAME_DATA.set(self).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "Game data is already in use and can't be replaced",
+            )
+        })
+    }
+}
+
+pub fn game_data() -> &'static GameData {
+    GAME_DATA.get_or_init(GameData::builtin)
+}
+
+pub fn monsters() -> &'static Bestiary {
+    &game_data().monsters
+}
+
+pub fn items() -> &'static ItemBook {
+    &game_data().items
+}
+
+pub fn spawns() -> &'static SpawnTables {
+    &game_data().spawns
+}
+
+pub fn lore() -> &'static LoreBook {
+    &game_data().lore
+}
+
+fn invalid(path: &Path, error: String) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid definitions in {}: {}", path.display(), error),
+    )
+}
+
+// A `[name]` header and the `key = value` lines under it
+pub struct Section {
+    pub name: String,
+    pub line: usize,
+    fields: Vec<(String, String, usize)>,
+}
+
+// Definition files are a list of sections. Blank lines and lines starting
+// with `#` are skipped.
+pub fn parse_sections(source: &str) -> Result<Vec<Section>, String> {
+    let mut sections: Vec<Section> = Vec::new();
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(format!("line {}: section without a name", line_number));
+            }
+            if sections.iter().any(|section| section.name == name) {
+                return Err(format!("line {}: '{}' is defined twice", line_number, name));
+            }
+            sections.push(Section {
+                name: name.to_string(),
+                line: line_number,
+                fields: Vec::new(),
+            });
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("line {}: expected `key = value`", line_number));
+        };
+        let Some(section) = sections.last_mut() else {
+            return Err(format!("line {}: field outside of a section", line_number));
+        };
+        let key = key.trim();
+        if section
+            .fields
+            .iter()
+            .any(|(existing, _, _)| existing == key)
+        {
+            return Err(format!("line {}: '{}' is set twice", line_number, key));
+        }
+        section
+            .fields
+            .push((key.to_string(), value.trim().to_string(), line_number));
+    }
+
+    Ok(sections)
+}
+
+impl Section {
+    // Takes a field out of the section, so `finish` can spot the ones nobody asked for
+    pub fn take(&mut self, key: &str) -> Option<(String, usize)> {
+        let index = self.fields.iter().position(|(name, _, _)| name == key)?;
+        let (_, value, line) = self.fields.remove(index);
+        Some((value, line))
+    }
+
+    // The next field nobody asked for, for sections whose keys aren't fixed
+    pub fn take_next(&mut self) -> Option<(String, String, usize)> {
+        if self.fields.is_empty() {
+            None
+        } else {
+            Some(self.fields.remove(0))
+        }
+    }
+
+    pub fn required<T: FromStr>(&mut self, key: &str) -> Result<T, String> {
+        self.optional(key)?
+            .ok_or_else(|| format!("line {}: '{}' is missing '{}'", self.line, self.name, key))
+    }
+
+    pub fn optional<T: FromStr>(&mut self, key: &str) -> Result<Option<T>, String> {
+        match self.take(key) {
+            Some((value, line)) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("line {}: bad value '{}' for '{}'", line, value, key)),
+            None => Ok(None),
+        }
+    }
+
+    // Run `parse` on the value, its error gets the line number added
+    pub fn required_with<T, F>(&mut self, key: &str, parse: F) -> Result<T, String>
+    where
+        F: FnOnce(&str) -> Result<T, String>,
+    {
+        let (value, line) = self
+            .take(key)
+            .ok_or_else(|| format!("line {}: '{}' is missing '{}'", self.line, self.name, key))?;
+        parse(&value).map_err(|e| format!("line {}: {}", line, e))
+    }
+
+    pub fn glyph(&mut self) -> Result<char, String> {
+        self.required_with("glyph", |value| {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(glyph), None) => Ok(glyph),
+                _ => Err(format!("glyph '{}' has to be a single character", value)),
+            }
+        })
+    }
+
+    pub fn colour(&mut self) -> Result<RatatuiColor, String> {
+        self.required_with("colour", |value| {
+            RatatuiColor::from_str(value).map_err(|_| format!("unknown colour '{}'", value))
+        })
+    }
+
+    // `r, g, b`
+    pub fn sdl_colour(&mut self) -> Result<SDLColor, String> {
+        self.required_with("sdl_colour", |value| {
+            let channels: Vec<u8> = value
+                .split(',')
+                .map(|channel| channel.trim().parse().ok())
+                .collect::<Option<_>>()
+                .filter(|channels: &Vec<u8>| channels.len() == 3)
+                .ok_or_else(|| format!("sdl_colour '{}' should be `r, g, b`", value))?;
+            Ok(SDLColor::RGB(channels[0], channels[1], channels[2]))
+        })
+    }
+
+    // Every field has to be used up by now
+    pub fn finish(self) -> Result<(), String> {
+        match self.fields.first() {
+            Some((key, _, line)) => Err(format!(
+                "line {}: unknown field '{}' in '{}'",
+                line, key, self.name
+            )),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use crate::data::{parse_sections, Section};
+use crate::effect::Effect;
+use crate::tile::TERRAIN_GLYPHS;
+use rand::Rng;
+use ratatui::style::Color as RatatuiColor;
+use sdl2::pixels::Color as SDLColor;
+
+// Index of a definition in the bestiary, cheap enough to live inside a tile
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct MonsterKind(u8);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Behaviour {
+    Wander, // roams about
+    Hunt,   // comes for the player once it has seen them
+    Guard,  // keeps watch over the treasure in secret rooms
+    Boss,   // rules a boss room on its own
+}
+
+impl Behaviour {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "wander" => Ok(Behaviour::Wander),
+            "hunt" => Ok(Behaviour::Hunt),
+            "guard" => Ok(Behaviour::Guard),
+            "boss" => Ok(Behaviour::Boss),
+            _ => Err(format!(
+                "unknown behaviour '{}', expected wander, hunt, guard or boss",
+                value
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MonsterDef {
+    pub name: String,
+    pub glyph: char,
+    pub colour: RatatuiColor,
+    pub sdl_colour: SDLColor,
+    pub hp: u32,
+    pub damage: u32,
+    pub fov: u32,
+    pub speed: u32, // 100 acts as often as the player
+    pub behaviour: Behaviour,
+    pub min_depth: u32,
+    pub max_depth: Option<u32>,
+    pub weight: u32, // relative chance against the others allowed at the same depth
+    pub exp: u32,    // experience for killing it
+    pub on_hit: Option<(Effect, u32)>, // lasting effect its hits leave, and for how long
+}
+
+impl MonsterDef {
+    pub fn spawns_at(&self, depth: u32) -> bool {
+        depth >= self.min_depth && self.max_depth.is_none_or(|max| depth <= max)
+    }
+
+    fn parse(mut section: Section) -> Result<Self, String> {
+        let name = section.name.clone();
+        if name.contains(|c: char| c.is_whitespace() || c == ':') {
+            return Err(format!(
+                "line {}: monster name '{}' can't contain spaces or ':'",
+                section.line, name
+            ));
+        }
+
+        let def = MonsterDef {
+            glyph: section.glyph()?,
+            colour: section.colour()?,
+            sdl_colour: section.sdl_colour()?,
+            hp: section.required("hp")?,
+            damage: section.required("damage")?,
+            fov: section.required("fov")?,
+            speed: section.optional("speed")?.unwrap_or(100),
+            behaviour: section.required_with("behaviour", Behaviour::parse)?,
+            min_depth: section.optional("min_depth")?.unwrap_or(1),
+            max_depth: section.optional("max_depth")?,
+            weight: section.required("weight")?,
+            exp: section.required("exp")?,
+            on_hit: match section.take("on_hit") {
+                Some((value, line)) => {
+                    Some(parse_on_hit(&value).map_err(|e| format!("line {}: {}", line, e))?)
+                }
+                None => None,
+            },
+            name,
+        };
+
+        let line = section.line;
+        section.finish()?;
+
+        if def.hp == 0 {
+            return Err(format!("line {}: '{}' needs some hp", line, def.name));
+        }
+        if TERRAIN_GLYPHS.contains(def.glyph) || def.glyph.is_whitespace() {
+            return Err(format!(
+                "line {}: glyph '{}' of '{}' is already used by the map",
+                line, def.glyph, def.name
+            ));
+        }
+        if def.max_depth.is_some_and(|max| max < def.min_depth) {
+            return Err(format!(
+                "line {}: '{}' has max_depth below min_depth",
+                line, def.name
+            ));
+        }
+        Ok(def)
+    }
+}
+
+// `poison <damage> <turns>` or `blindness <sight radius> <turns>`
+fn parse_on_hit(value: &str) -> Result<(Effect, u32), String> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let number = |index: usize| -> Result<u32, String> {
+        parts
+            .get(index)
+            .and_then(|part| part.parse().ok())
+            .ok_or_else(|| format!("on_hit '{}' is missing a number", value))
+    };
+    if parts.len() != 3 {
+        return Err(format!(
+            "on_hit '{}' should be `<effect> <amount> <turns>`",
+            value
+        ));
+    }
+
+    let effect = match parts[0] {
+        "poison" => Effect::Poison { damage: number(1)? },
+        "blindness" => Effect::Blindness {
+            fov_radius: number(1)?,
+        },
+        other => {
+            return Err(format!(
+                "unknown on_hit effect '{}', expected poison or blindness",
+                other
+            ))
+        }
+    };
+    Ok((effect, number(2)?))
+}
+
+// Every monster the game knows about
+#[derive(Debug)]
+pub struct Bestiary {
+    monsters: Vec<MonsterDef>,
+}
+
+impl Bestiary {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let monsters = parse_sections(source)?
+            .into_iter()
+            .map(MonsterDef::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if monsters.is_empty() {
+            return Err("no monsters defined".to_string());
+        }
+        if monsters.len() > u8::MAX as usize {
+            return Err(format!("at most {} monsters can be defined", u8::MAX));
+        }
+        for (index, monster) in monsters.iter().enumerate() {
+            if let Some(other) = monsters[..index]
+                .iter()
+                .find(|other| other.glyph == monster.glyph)
+            {
+                return Err(format!(
+                    "'{}' and '{}' share the glyph '{}'",
+                    other.name, monster.name, monster.glyph
+                ));
+            }
+        }
+
+        Ok(Bestiary { monsters })
+    }
+
+    pub fn get(&self, kind: MonsterKind) -> &MonsterDef {
+        &self.monsters[kind.0 as usize]
+    }
+
+    pub fn kinds(&self) -> impl Iterator<Item = MonsterKind> {
+        (0..self.monsters.len() as u8).map(MonsterKind)
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Option<MonsterKind> {
+        self.kinds().find(|&kind| self.get(kind).name == name)
+    }
+
+    pub fn find_by_glyph(&self, glyph: char) -> Option<MonsterKind> {
+        self.kinds().find(|&kind| self.get(kind).glyph == glyph)
+    }
+
+    // A weighted pick among the monsters that live at `depth` and pass `filter`
+    pub fn random<R, F>(&self, depth: u32, rng: &mut R, filter: F) -> Option<MonsterKind>
+    where
+        R: Rng,
+        F: Fn(&MonsterDef) -> bool,
+    {
+        let candidates: Vec<MonsterKind> = self
+            .kinds()
+            .filter(|&kind| {
+                let def = self.get(kind);
+                def.spawns_at(depth) && def.weight > 0 && filter(def)
+            })
+            .collect();
+        let total: u32 = candidates.iter().map(|&kind| self.get(kind).weight).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = rng.gen_range(0..total);
+        for kind in candidates {
+            let weight = self.get(kind).weight;
+            if roll < weight {
+                return Some(kind);
+            }
+            roll -= weight;
+        }
+        None
+    }
+}
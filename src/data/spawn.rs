@@ -0,0 +1,273 @@
+use crate::data::item::ItemBook;
+use crate::data::monster::{Bestiary, MonsterKind};
+use crate::data::{parse_sections, Section};
+use crate::generator::room::RoomType;
+use crate::item::{Item, ItemCategory};
+use rand::Rng;
+
+// Which item lies on the floor
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ItemPick {
+    Named(Item),
+    // Any item of the category that can turn up in secrets of this rarity
+    Random { category: ItemCategory, rarity: u32 },
+}
+
+// Something a spawn table can put in a room
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Spawn {
+    Monster,            // any monster living at the depth that isn't a guard or a boss
+    Guardian,           // a guard from the bestiary
+    Boss,               // a boss from the bestiary, takes the middle of the room
+    Named(MonsterKind), // this monster in particular
+    Secret(u32),        // a secret of the given rarity
+    Item(ItemPick),     // an item lying on the floor
+    Obelisk,            // lands in the middle of the room when it can
+    Shrine,             // likewise
+    Sign,               // tells a random piece of lore
+    Pit,                // a trap, kept away from walls so it never blocks the way
+}
+
+impl Spawn {
+    // `monster`, `guardian`, `boss`, `monster:<name>`, `secret:<rarity>`,
+    // `potion:<rarity>`, `scroll:<rarity>`, `item:<name>`, `obelisk`, `shrine`,
+    // `sign` or `pit`
+    fn parse(key: &str, bestiary: &Bestiary, items: &ItemBook) -> Result<Self, String> {
+        let rarity = |value: &str| match value.parse() {
+            Ok(rarity @ (1 | 10 | 100 | 1000)) => Ok(rarity),
+            _ => Err(format!("rarity '{}' has to be 1, 10, 100 or 1000", value)),
+        };
+
+        let spawn = match key.split_once(':') {
+            None => match key {
+                "monster" => Spawn::Monster,
+                "guardian" => Spawn::Guardian,
+                "boss" => Spawn::Boss,
+                "obelisk" => Spawn::Obelisk,
+                "shrine" => Spawn::Shrine,
+                "sign" => Spawn::Sign,
+                "pit" => Spawn::Pit,
+                _ => return Err(format!("unknown spawn '{}'", key)),
+            },
+            Some(("monster", name)) => Spawn::Named(
+                bestiary
+                    .find_by_name(name)
+                    .ok_or_else(|| format!("no monster called '{}'", name))?,
+            ),
+            Some(("secret", value)) => Spawn::Secret(rarity(value)?),
+            Some(("potion", value)) => Spawn::Item(ItemPick::Random {
+                category: ItemCategory::Potion,
+                rarity: rarity(value)?,
+            }),
+            Some(("scroll", value)) => Spawn::Item(ItemPick::Random {
+                category: ItemCategory::Scroll,
+                rarity: rarity(value)?,
+            }),
+            Some(("item", name)) => Spawn::Item(ItemPick::Named(
+                items
+                    .find_by_name(name)
+                    .ok_or_else(|| format!("no item called '{}'", name))?,
+            )),
+            _ => return Err(format!("unknown spawn '{}'", key)),
+        };
+        Ok(spawn)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SpawnEntry {
+    pub spawn: Spawn,
+    pub weight: u32, // chance to be rolled against the other entries
+    pub min: u32,    // always placed
+    pub max: u32,    // rolls never go past this
+}
+
+// `<weight> <min>-<max>`
+fn parse_entry(spawn: Spawn, value: &str) -> Result<SpawnEntry, String> {
+    let bad_entry = || format!("'{}' should be `<weight> <min>-<max>`", value);
+    let (weight, count) = value.split_once(' ').ok_or_else(bad_entry)?;
+    let weight = weight.trim().parse().map_err(|_| bad_entry())?;
+    let (min, max) = parse_count(count.trim())?;
+    Ok(SpawnEntry {
+        spawn,
+        weight,
+        min,
+        max,
+    })
+}
+
+// `<min>-<max>` or a single number for exactly that many
+fn parse_count(value: &str) -> Result<(u32, u32), String> {
+    let bad_count = || format!("count '{}' should be `<min>-<max>`", value);
+    let (min, max) = match value.split_once('-') {
+        Some((min, max)) => (min.trim(), max.trim()),
+        None => (value, value),
+    };
+    let min: u32 = min.parse().map_err(|_| bad_count())?;
+    let max: u32 = max.parse().map_err(|_| bad_count())?;
+    if max < min {
+        return Err(format!("count '{}' has max below min", value));
+    }
+    Ok((min, max))
+}
+
+// What goes into one kind of room over a range of depths
+#[derive(Clone, Debug)]
+pub struct SpawnTable {
+    pub name: String,
+    pub room: RoomType,
+    pub min_depth: u32,
+    pub max_depth: Option<u32>,
+    pub rolls: (u32, u32), // weighted picks made on top of every entry's minimum
+    pub entries: Vec<SpawnEntry>,
+}
+
+impl SpawnTable {
+    pub fn covers(&self, depth: u32) -> bool {
+        depth >= self.min_depth && self.max_depth.is_none_or(|max| depth <= max)
+    }
+
+    // Every entry's minimum, then weighted picks among the entries that
+    // haven't hit their maximum yet
+    pub fn roll<R: Rng>(&self, rng: &mut R) -> Vec<Spawn> {
+        let mut counts: Vec<u32> = self.entries.iter().map(|entry| entry.min).collect();
+
+        for _ in 0..rng.gen_range(self.rolls.0..=self.rolls.1) {
+            let total: u32 = self
+                .entries
+                .iter()
+                .zip(&counts)
+                .filter(|(entry, &count)| count < entry.max)
+                .map(|(entry, _)| entry.weight)
+                .sum();
+            if total == 0 {
+                break;
+            }
+
+            let mut roll = rng.gen_range(0..total);
+            for (entry, count) in self.entries.iter().zip(counts.iter_mut()) {
+                if *count >= entry.max {
+                    continue;
+                }
+                if roll < entry.weight {
+                    *count += 1;
+                    break;
+                }
+                roll -= entry.weight;
+            }
+        }
+
+        self.entries
+            .iter()
+            .zip(counts)
+            .flat_map(|(entry, count)| std::iter::repeat_n(entry.spawn, count as usize))
+            .collect()
+    }
+
+    fn parse(mut section: Section, bestiary: &Bestiary, items: &ItemBook) -> Result<Self, String> {
+        let room = section.required_with("room", RoomType::parse)?;
+        let min_depth = section.optional("min_depth")?.unwrap_or(1);
+        let max_depth: Option<u32> = section.optional("max_depth")?;
+        let rolls = section.required_with("rolls", parse_count)?;
+
+        if room == RoomType::Spawn {
+            return Err(format!(
+                "line {}: the spawn room is kept empty and can't have a table",
+                section.line
+            ));
+        }
+        if max_depth.is_some_and(|max| max < min_depth) {
+            return Err(format!(
+                "line {}: '{}' has max_depth below min_depth",
+                section.line, section.name
+            ));
+        }
+
+        // Whatever is left are the entries
+        let mut entries = Vec::new();
+        while let Some((key, value, line)) = section.take_next() {
+            let entry = Spawn::parse(&key, bestiary, items)
+                .and_then(|spawn| parse_entry(spawn, &value))
+                .map_err(|e| format!("line {}: {}", line, e))?;
+            entries.push(entry);
+        }
+        if entries.is_empty() {
+            return Err(format!(
+                "line {}: '{}' doesn't spawn anything",
+                section.line, section.name
+            ));
+        }
+
+        Ok(SpawnTable {
+            name: section.name.clone(),
+            room,
+            min_depth,
+            max_depth,
+            rolls,
+            entries,
+        })
+    }
+}
+
+// Every spawn table, each room type has exactly one for any depth
+#[derive(Debug)]
+pub struct SpawnTables {
+    tables: Vec<SpawnTable>,
+}
+
+impl SpawnTables {
+    pub fn parse(source: &str, bestiary: &Bestiary, items: &ItemBook) -> Result<Self, String> {
+        let tables = parse_sections(source)?
+            .into_iter()
+            .map(|section| SpawnTable::parse(section, bestiary, items))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for room in RoomType::POPULATED {
+            let mut covering: Vec<&SpawnTable> =
+                tables.iter().filter(|table| table.room == room).collect();
+            covering.sort_by_key(|table| table.min_depth);
+
+            // The tables have to follow on from each other without gaps or overlaps
+            let mut next_depth = Some(1);
+            for table in covering {
+                match next_depth {
+                    Some(depth) if table.min_depth > depth => {
+                        return Err(format!(
+                            "'{}' rooms have no table from depth {}",
+                            room.name(),
+                            depth
+                        ))
+                    }
+                    Some(depth) if table.min_depth == depth => {}
+                    _ => {
+                        return Err(format!(
+                        "'{}' rooms need exactly one table for every depth, '{}' overlaps another",
+                        room.name(),
+                        table.name
+                    ))
+                    }
+                }
+                next_depth = table.max_depth.map(|max| max + 1);
+            }
+            if let Some(depth) = next_depth {
+                return Err(format!(
+                    "'{}' rooms have no table from depth {}",
+                    room.name(),
+                    depth
+                ));
+            }
+        }
+
+        Ok(SpawnTables { tables })
+    }
+
+    pub fn tables(&self) -> impl Iterator<Item = &SpawnTable> {
+        self.tables.iter()
+    }
+
+    pub fn for_room(&self, room: RoomType, depth: u32) -> Option<&SpawnTable> {
+        self.tables
+            .iter()
+            .find(|table| table.room == room && table.covers(depth))
+    }
+}
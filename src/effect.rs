@@ -0,0 +1,315 @@
+// Timed status effects that tick once per turn on the player or on monsters
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Effect {
+    Poison { damage: u32 },
+    Blindness { fov_radius: u32 }, // sight is limited to this radius
+    Curse { damage: u32, fov_radius: u32 }, // an obelisk's curse
+    Regeneration { heal: u32 },
+    Light { fov_radius: u32 }, // sight reaches at least this far
+    Warded,                    // obelisk curses can't take hold
+}
+
+// How a new effect combines with one of the same kind that's already active
+enum Stacking {
+    Intensify, // potencies add up, the longer duration wins
+    Refresh,   // the stronger potency and the longer duration win
+    Extend,    // durations add up, the stronger potency wins
+}
+
+impl Effect {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Effect::Poison { .. } => "Poisoned",
+            Effect::Blindness { .. } => "Blind",
+            Effect::Curse { .. } => "Cursed",
+            Effect::Regeneration { .. } => "Regenerating",
+            Effect::Light { .. } => "Lit",
+            Effect::Warded => "Warded",
+        }
+    }
+
+    pub fn expire_message(&self) -> &'static str {
+        match self {
+            Effect::Poison { .. } => "The poison wears off.",
+            Effect::Blindness { .. } => "You can see clearly again.",
+            Effect::Curse { .. } => "The obelisk's curse lifts.",
+            Effect::Regeneration { .. } => "You stop regenerating.",
+            Effect::Light { .. } => "The magical light fades.",
+            Effect::Warded => "You feel exposed to curses again.",
+        }
+    }
+
+    fn stacking(&self) -> Stacking {
+        match self {
+            Effect::Poison { .. } => Stacking::Intensify,
+            Effect::Blindness { .. } | Effect::Curse { .. } | Effect::Light { .. } => {
+                Stacking::Refresh
+            }
+            Effect::Regeneration { .. } | Effect::Warded => Stacking::Extend,
+        }
+    }
+
+    fn same_kind(&self, other: &Effect) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    // Combine with another effect of the same kind, returns the new duration
+    fn stack(&mut self, turns: u32, other: Effect, other_turns: u32) -> u32 {
+        let stacking = self.stacking();
+        match (self, other) {
+            (Effect::Poison { damage }, Effect::Poison { damage: other }) => *damage += other,
+            (Effect::Regeneration { heal }, Effect::Regeneration { heal: other }) => {
+                *heal = (*heal).max(other);
+            }
+            // Smaller sight radius is the stronger blindness
+            (Effect::Blindness { fov_radius }, Effect::Blindness { fov_radius: other }) => {
+                *fov_radius = (*fov_radius).min(other);
+            }
+            (Effect::Light { fov_radius }, Effect::Light { fov_radius: other }) => {
+                *fov_radius = (*fov_radius).max(other);
+            }
+            (
+                Effect::Curse { damage, fov_radius },
+                Effect::Curse {
+                    damage: other_damage,
+                    fov_radius: other_fov_radius,
+                },
+            ) => {
+                *damage = (*damage).max(other_damage);
+                *fov_radius = (*fov_radius).min(other_fov_radius);
+            }
+            _ => {}
+        }
+
+        match stacking {
+            Stacking::Intensify | Stacking::Refresh => turns.max(other_turns),
+            Stacking::Extend => turns + other_turns,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct StatusEffect {
+    pub effect: Effect,
+    pub turns: u32, // turns left including the current one
+}
+
+// What a turn of effects did to whoever carries them
+#[derive(Default)]
+pub struct EffectTick {
+    pub damage: Vec<(Effect, u32)>,
+    pub heal: u32,
+    pub expired: Vec<Effect>,
+}
+
+#[derive(Clone, Default)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    pub fn new() -> Self {
+        StatusEffects {
+            effects: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, effect: Effect, turns: u32) {
+        if turns == 0 {
+            return;
+        }
+        match self
+            .effects
+            .iter_mut()
+            .find(|active| active.effect.same_kind(&effect))
+        {
+            Some(active) => active.turns = active.effect.stack(active.turns, effect, turns),
+            None => self.effects.push(StatusEffect { effect, turns }),
+        }
+    }
+
+    pub fn any<F: Fn(&Effect) -> bool>(&self, predicate: F) -> bool {
+        self.effects.iter().any(|active| predicate(&active.effect))
+    }
+
+    pub fn remove<F: Fn(&Effect) -> bool>(&mut self, predicate: F) {
+        self.effects.retain(|active| !predicate(&active.effect));
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &StatusEffect> {
+        self.effects.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    // Light widens the base sight radius, blindness and curses then limit it.
+    // `ignore_curse` for players warded against obelisks.
+    pub fn fov_radius(&self, base: u32, ignore_curse: bool) -> u32 {
+        let base = self
+            .effects
+            .iter()
+            .filter_map(|active| match active.effect {
+                Effect::Light { fov_radius } => Some(fov_radius),
+                _ => None,
+            })
+            .fold(base, u32::max);
+        self.effects
+            .iter()
+            .filter_map(|active| match active.effect {
+                Effect::Blindness { fov_radius } => Some(fov_radius),
+                Effect::Curse { fov_radius, .. } if !ignore_curse => Some(fov_radius),
+                _ => None,
+            })
+            .fold(base, u32::min)
+    }
+
+    // Runs one turn: every effect does its thing, then loses a turn
+    pub fn tick(&mut self) -> EffectTick {
+        let mut result = EffectTick::default();
+
+        for active in &mut self.effects {
+            match active.effect {
+                Effect::Poison { damage } | Effect::Curse { damage, .. } if damage > 0 => {
+                    result.damage.push((active.effect, damage));
+                }
+                Effect::Regeneration { heal } => result.heal += heal,
+                _ => {}
+            }
+            active.turns -= 1;
+        }
+
+        result.expired = self
+            .effects
+            .iter()
+            .filter(|active| active.turns == 0)
+            .map(|active| active.effect)
+            .collect();
+        self.effects.retain(|active| active.turns > 0);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn only(effects: &StatusEffects) -> StatusEffect {
+        let mut active = effects.iter();
+        let first = *active.next().expect("an active effect");
+        assert!(active.next().is_none());
+        first
+    }
+
+    #[test]
+    fn poison_adds_up() {
+        let mut effects = StatusEffects::new();
+        effects.add(Effect::Poison { damage: 1 }, 5);
+        effects.add(Effect::Poison { damage: 2 }, 3);
+
+        let active = only(&effects);
+        assert_eq!(active.effect, Effect::Poison { damage: 3 });
+        assert_eq!(active.turns, 5);
+    }
+
+    #[test]
+    fn refresh_keeps_the_strongest_and_the_longest() {
+        let mut effects = StatusEffects::new();
+        effects.add(Effect::Blindness { fov_radius: 4 }, 2);
+        effects.add(Effect::Blindness { fov_radius: 2 }, 6);
+        effects.add(Effect::Blindness { fov_radius: 3 }, 4);
+
+        let active = only(&effects);
+        assert_eq!(active.effect, Effect::Blindness { fov_radius: 2 });
+        assert_eq!(active.turns, 6);
+
+        let mut effects = StatusEffects::new();
+        effects.add(
+            Effect::Curse {
+                damage: 1,
+                fov_radius: 3,
+            },
+            2,
+        );
+        effects.add(
+            Effect::Curse {
+                damage: 2,
+                fov_radius: 5,
+            },
+            1,
+        );
+        let active = only(&effects);
+        assert_eq!(
+            active.effect,
+            Effect::Curse {
+                damage: 2,
+                fov_radius: 3
+            }
+        );
+        assert_eq!(active.turns, 2);
+    }
+
+    #[test]
+    fn extend_adds_the_durations() {
+        let mut effects = StatusEffects::new();
+        effects.add(Effect::Regeneration { heal: 2 }, 3);
+        effects.add(Effect::Regeneration { heal: 1 }, 4);
+
+        let active = only(&effects);
+        assert_eq!(active.effect, Effect::Regeneration { heal: 2 });
+        assert_eq!(active.turns, 7);
+    }
+
+    #[test]
+    fn different_kinds_are_kept_apart() {
+        let mut effects = StatusEffects::new();
+        effects.add(Effect::Poison { damage: 1 }, 2);
+        effects.add(Effect::Light { fov_radius: 12 }, 2);
+        effects.add(Effect::Warded, 0);
+
+        assert_eq!(effects.iter().count(), 2);
+        assert!(!effects.any(|effect| *effect == Effect::Warded));
+    }
+
+    #[test]
+    fn effects_expire_after_their_turns() {
+        let mut effects = StatusEffects::new();
+        effects.add(Effect::Poison { damage: 2 }, 2);
+        effects.add(Effect::Regeneration { heal: 1 }, 1);
+
+        let tick = effects.tick();
+        assert_eq!(tick.damage, vec![(Effect::Poison { damage: 2 }, 2)]);
+        assert_eq!(tick.heal, 1);
+        assert_eq!(tick.expired, vec![Effect::Regeneration { heal: 1 }]);
+
+        let tick = effects.tick();
+        assert_eq!(tick.expired, vec![Effect::Poison { damage: 2 }]);
+        assert!(effects.is_empty());
+        assert!(effects.tick().damage.is_empty());
+    }
+
+    #[test]
+    fn sight_is_widened_then_limited() {
+        let mut effects = StatusEffects::new();
+        assert_eq!(effects.fov_radius(10, false), 10);
+
+        effects.add(Effect::Light { fov_radius: 14 }, 3);
+        assert_eq!(effects.fov_radius(10, false), 14);
+
+        effects.add(
+            Effect::Curse {
+                damage: 1,
+                fov_radius: 3,
+            },
+            3,
+        );
+        assert_eq!(effects.fov_radius(10, false), 3);
+        assert_eq!(effects.fov_radius(10, true), 14);
+
+        effects.add(Effect::Blindness { fov_radius: 1 }, 3);
+        assert_eq!(effects.fov_radius(10, true), 1);
+    }
+}
@@ -0,0 +1,86 @@
+use crate::data::monster::Behaviour;
+use crate::effect::Effect;
+use crate::game::Game;
+use crate::map::types::Point;
+use crate::objective::Objective;
+use crate::player::perk::Perk;
+use crate::tile::Tile;
+
+// Poison from the Venomous perk, hits on a poisoned monster add up
+const VENOM_DAMAGE: u32 = 2;
+const VENOM_TURNS: u32 = 3;
+
+// `a bat`, `an ogre`
+pub(super) fn with_article(name: &str) -> String {
+    match name.chars().next() {
+        Some('a' | 'e' | 'i' | 'o' | 'u') => format!("an {}", name),
+        _ => format!("a {}", name),
+    }
+}
+
+impl Game {
+    // Bumping into a monster attacks it, monsters that survive strike back
+    pub(super) fn attack(&mut self, point: Point) {
+        let monster = self.map.get_tile(point);
+        let (Some(def), Some(hp)) = (monster.monster_def(), monster.monster_hp()) else {
+            return;
+        };
+        let name = &def.name;
+
+        let remaining_hp = hp.saturating_sub(self.player.strength);
+        if remaining_hp == 0 {
+            self.kill_monster(point, monster);
+            let exp = def.exp;
+            self.log_info_message(format!("You slay the {}! (+{} XP)", name, exp));
+            self.award_exp(exp);
+        } else {
+            self.map
+                .set_tile(point, monster.with_monster_hp(remaining_hp));
+            self.log_info_message(format!("You hit the {}.", name));
+            if self.player.has_perk(Perk::Venomous) {
+                self.add_monster_effect(
+                    point,
+                    Effect::Poison {
+                        damage: VENOM_DAMAGE,
+                    },
+                    VENOM_TURNS,
+                );
+            }
+
+            let damage_taken = def.damage.saturating_sub(self.player.defense);
+            if damage_taken > 0 {
+                self.log_damage_message(format!(
+                    "The {} hits you for {} damage",
+                    name, damage_taken
+                ));
+                let cause = match def.behaviour {
+                    Behaviour::Boss => format!("slain by the {}", name),
+                    _ => format!("killed by {}", with_article(name)),
+                };
+                self.hurt_player(damage_taken, cause);
+                if let Some((effect, turns)) = def.on_hit {
+                    self.add_player_effect(effect, turns);
+                }
+            }
+        }
+
+        self.tick();
+    }
+
+    // Dead monsters leave plain floor behind
+    pub(super) fn kill_monster(&mut self, point: Point, monster: Tile) {
+        if let Some(def) = monster.monster_def() {
+            *self.stats.kills.entry(def.name.clone()).or_default() += 1;
+            if def.behaviour == Behaviour::Boss {
+                self.advance_objective(Objective::SlayBoss);
+            }
+        }
+        self.map.set_tile(
+            point,
+            Tile::Floor {
+                visible: monster.visibility().unwrap_or(false),
+                cursed: false,
+            },
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use std::fmt;
+use std::str::FromStr;
+
+// How hard everything hits the player
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    // Easy halves damage and hard adds half again, anything that hurts
+    // still hurts for at least 1
+    pub fn scale_damage(&self, damage: u32) -> u32 {
+        if damage == 0 {
+            return 0;
+        }
+        match self {
+            Difficulty::Easy => damage.div_ceil(2),
+            Difficulty::Normal => damage,
+            Difficulty::Hard => damage + damage.div_ceil(2),
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Difficulty::ALL
+            .iter()
+            .find(|difficulty| difficulty.name() == s)
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = Difficulty::ALL.iter().map(|d| d.name()).collect();
+                format!(
+                    "Unknown difficulty '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
@@ -0,0 +1,128 @@
+use crate::effect::{Effect, StatusEffects};
+use crate::game::Game;
+use crate::map::types::Point;
+use crate::player::perk::Perk;
+use crate::tile::Tile;
+
+// Curses linger for a turn after the player leaves the obelisk's reach
+const CURSE_TURNS: u32 = 2;
+
+impl Game {
+    pub fn add_player_effect(&mut self, effect: Effect, turns: u32) {
+        self.player.effects.add(effect, turns);
+    }
+
+    pub fn add_monster_effect(&mut self, point: Point, effect: Effect, turns: u32) {
+        if self.map.get_tile(point).is_monster() {
+            self.monster_effects
+                .entry(point)
+                .or_default()
+                .add(effect, turns);
+        }
+    }
+
+    pub fn get_monster_effects(&self, point: Point) -> Option<&StatusEffects> {
+        self.monster_effects.get(&point)
+    }
+
+    // Standing in an obelisk's curse area (re)applies its curse
+    pub(super) fn catch_obelisk_curse(&mut self) {
+        let standing_in_curse = matches!(
+            self.map.get_tile(self.player_position),
+            Tile::Player {
+                is_cursed: true,
+                ..
+            }
+        );
+        let warded = self
+            .player
+            .effects
+            .any(|effect| matches!(effect, Effect::Warded));
+        if !standing_in_curse || warded {
+            return;
+        }
+
+        if let Some(Tile::Obelisk {
+            damage_hp,
+            reduce_fov_radius,
+            ..
+        }) = self.map.get_obelisk_cursing_tile(self.player_position)
+        {
+            let damage = if self.player.has_perk(Perk::IronWill) {
+                damage_hp / 2
+            } else {
+                damage_hp
+            };
+            if !self.is_player_cursed() {
+                self.log_damage_message("An obelisk's curse falls upon you!".to_string());
+            }
+            self.add_player_effect(
+                Effect::Curse {
+                    damage,
+                    fov_radius: reduce_fov_radius,
+                },
+                CURSE_TURNS,
+            );
+        }
+    }
+
+    pub(super) fn tick_status_effects(&mut self) {
+        let tick = self.player.effects.tick();
+        for (effect, damage) in tick.damage {
+            let message = match effect {
+                Effect::Curse { .. } => {
+                    format!("You take {} damage from the Obelisk curse", damage)
+                }
+                _ => format!("You take {} damage from being {}", damage, effect.name()),
+            };
+            self.log_damage_message(message);
+            let cause = match effect {
+                Effect::Curse { .. } => "withered by an obelisk curse".to_string(),
+                Effect::Poison { .. } => "succumbed to poison".to_string(),
+                _ => format!("wasted away while {}", effect.name().to_lowercase()),
+            };
+            self.hurt_player(damage, cause);
+        }
+        self.player.heal(tick.heal);
+        for effect in tick.expired {
+            self.log_info_message(effect.expire_message().to_string());
+        }
+
+        self.tick_monster_effects();
+    }
+
+    fn tick_monster_effects(&mut self) {
+        // Monsters that were killed in the meantime take their effects with them
+        let map = &self.map;
+        self.monster_effects
+            .retain(|&point, effects| map.get_tile(point).is_monster() && !effects.is_empty());
+
+        // In a fixed order, so kills and their loot come out the same in a replay
+        let mut points: Vec<Point> = self.monster_effects.keys().copied().collect();
+        points.sort_by_key(|point| (point.y, point.x));
+        for point in points {
+            let Some(effects) = self.monster_effects.get_mut(&point) else {
+                continue;
+            };
+            let tick = effects.tick();
+
+            let monster = self.map.get_tile(point);
+            let (Some(def), Some(hp)) = (monster.monster_def(), monster.monster_hp()) else {
+                continue;
+            };
+            let damage: u32 = tick.damage.iter().map(|&(_, damage)| damage).sum();
+            let hp = (hp + tick.heal).min(def.hp);
+            let remaining_hp = hp.saturating_sub(damage);
+
+            if remaining_hp == 0 {
+                self.monster_effects.remove(&point);
+                self.kill_monster(point, monster);
+                self.log_info_message(format!("The {} succumbs! (+{} XP)", def.name, def.exp));
+                self.award_exp(def.exp);
+            } else {
+                self.map
+                    .set_tile(point, monster.with_monster_hp(remaining_hp));
+            }
+        }
+    }
+}
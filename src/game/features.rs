@@ -0,0 +1,50 @@
+use crate::data::lore;
+use crate::effect::Effect;
+use crate::game::Game;
+use crate::map::types::Point;
+use crate::tile::Tile;
+
+impl Game {
+    // Shrines heal fully and lift every harmful effect, but only once
+    pub(super) fn pray_at_shrine(&mut self, point: Point, used: bool) {
+        if used {
+            self.log_info_message("The shrine is silent.".to_string());
+            return;
+        }
+
+        self.player.heal(self.player.max_hp);
+        self.player.effects.remove(|effect| {
+            matches!(
+                effect,
+                Effect::Poison { .. } | Effect::Blindness { .. } | Effect::Curse { .. }
+            )
+        });
+        self.map.set_tile(
+            point,
+            Tile::Shrine {
+                visible: true,
+                used: true,
+            },
+        );
+        self.log_info_message("You pray at the shrine. Warmth washes over you.".to_string());
+        self.update_fov();
+    }
+
+    pub(super) fn read_sign(&mut self, lore_index: u16) {
+        let lore = lore().get(lore_index);
+        self.log_info_message(format!("The sign reads: \"{}\"", lore.text));
+    }
+
+    // Walking over an item picks it up, unless the pack is full
+    pub(super) fn pick_up_floor_item(&mut self) {
+        let Tile::Item { item, .. } = self.previous_tile else {
+            return;
+        };
+        if self.pick_up(item) {
+            self.previous_tile = Tile::Floor {
+                visible: true,
+                cursed: false,
+            };
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use crate::data::item::ItemEffect;
+use crate::effect::Effect;
+use crate::game::combat::with_article;
+use crate::game::Game;
+use crate::item::{Item, ItemCategory, INVENTORY_SIZE};
+use crate::map::types::Point;
+use crate::objective::Objective;
+use crate::tile::Tile;
+
+impl Game {
+    // The name the player knows the item by
+    pub fn get_item_name(&self, item: Item) -> String {
+        self.item_names.display_name(item)
+    }
+
+    // Returns false if there was no room for it
+    pub(super) fn pick_up(&mut self, item: Item) -> bool {
+        let name = self.get_item_name(item);
+        if self.player.inventory.len() >= INVENTORY_SIZE {
+            self.log_info_message(format!(
+                "You find {}, but your pack is full.",
+                with_article(&name)
+            ));
+            return false;
+        }
+        self.player.inventory.push(item);
+        self.log_info_message(format!("You find {}.", with_article(&name)));
+        if item.category() == ItemCategory::Artefact {
+            self.advance_objective(Objective::RetrieveArtefact(item));
+        }
+        true
+    }
+
+    // Quaff or read the item in the given inventory slot, using it identifies
+    // every item of its kind
+    pub fn use_item(&mut self, slot: usize) {
+        let Some(&item) = self.player.inventory.get(slot) else {
+            return;
+        };
+        let verb = match item.category() {
+            ItemCategory::Potion => "drink",
+            ItemCategory::Scroll => "read",
+            // Keys are used by walking into their lock
+            ItemCategory::Key => {
+                self.log_info_message(format!(
+                    "Walk into the locked door the {} belongs to.",
+                    item.name()
+                ));
+                return;
+            }
+            ItemCategory::Artefact => {
+                self.log_info_message(format!("The {} hums in your hands.", item.name()));
+                return;
+            }
+        };
+        self.player.inventory.remove(slot);
+
+        if self.item_names.is_identified(item) {
+            self.log_info_message(format!("You {} the {}.", verb, item.name()));
+        } else {
+            let unknown_name = self.get_item_name(item);
+            self.item_names.identify(item);
+            self.log_info_message(format!(
+                "You {} the {}. It's {}!",
+                verb,
+                unknown_name,
+                with_article(item.name())
+            ));
+        }
+
+        if !self.apply_item(item, slot) {
+            self.tick();
+        }
+    }
+
+    // Returns true if the item already took the turn. `slot` is where the item
+    // was in the inventory before it was used.
+    fn apply_item(&mut self, item: Item, slot: usize) -> bool {
+        match item.def().effect {
+            ItemEffect::Heal(amount) => {
+                self.player.heal(amount);
+                self.log_info_message("You feel much better.".to_string());
+            }
+            ItemEffect::Reveal(None) => {
+                let revealed = self.map.reveal(self.player_position, None);
+                self.log_revealed(&revealed, "the whole level");
+            }
+            ItemEffect::CureCurse(turns) => {
+                self.player
+                    .effects
+                    .remove(|effect| matches!(effect, Effect::Curse { .. }));
+                self.add_player_effect(Effect::Warded, turns);
+                self.log_info_message("A protective warmth spreads through you.".to_string());
+            }
+            ItemEffect::Teleport => {
+                if let Some(destination) = self.map.random_safe_floor(&mut self.rng) {
+                    self.log_info_message("The world blurs around you.".to_string());
+                    self.walk_to_tile(destination);
+                    return true;
+                }
+                self.log_info_message("You feel a wrenching sensation.".to_string());
+            }
+            ItemEffect::Reveal(Some(radius)) => {
+                let revealed = self.map.reveal(self.player_position, Some(radius));
+                self.log_revealed(&revealed, "the surrounding area");
+            }
+            ItemEffect::Light { radius, turns } => {
+                self.add_player_effect(Effect::Light { fov_radius: radius }, turns);
+                self.log_info_message("A bright light fills the area.".to_string());
+            }
+            ItemEffect::BanishObelisk => {
+                match self.map.banish_nearest_obelisk(self.player_position) {
+                    Some(_) => {
+                        self.player
+                            .effects
+                            .remove(|effect| matches!(effect, Effect::Curse { .. }));
+                        self.log_info_message("The obelisk falls silent.".to_string());
+                        self.advance_objective(Objective::CleanseObelisks(1));
+                    }
+                    // The words stay on the page for an obelisk that's in reach,
+                    // and the scroll stays in its slot
+                    None => {
+                        self.player.inventory.insert(slot, item);
+                        self.log_info_message(
+                            "There is no obelisk in sight to banish. The words fade back onto the page."
+                                .to_string(),
+                        );
+                    }
+                }
+            }
+            // Keys and artefacts never get this far, see `use_item`
+            ItemEffect::Unlock | ItemEffect::Nothing => {}
+        }
+        false
+    }
+
+    fn log_revealed(&mut self, revealed: &[Point], area: &str) {
+        let monsters = revealed
+            .iter()
+            .filter(|&&point| self.map.get_tile(point).is_monster())
+            .count();
+        let secrets = revealed
+            .iter()
+            .filter(|&&point| matches!(self.map.get_tile(point), Tile::Secret { .. }))
+            .count();
+        self.log_info_message(format!(
+            "You sense the layout of {}: {} monsters and {} secrets.",
+            area, monsters, secrets
+        ));
+    }
+}
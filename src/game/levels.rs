@@ -0,0 +1,304 @@
+use crate::data::monster::Behaviour;
+use crate::effect::StatusEffects;
+use crate::game::{Game, DIRECTIONS, STARTING_DEPTH};
+use crate::generator::layout::LayoutKind;
+use crate::generator::map::{DungeonSize, MapGenerator};
+use crate::generator::prefab::PrefabLibrary;
+use crate::map::types::Point;
+use crate::map::Map;
+use crate::objective::{Objective, FINAL_DEPTH};
+use crate::tile::{Lock, Tile};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::HashMap;
+use std::io;
+use std::mem;
+
+// Spreads the run's seed over the depths so every level gets its own
+const DEPTH_SEED_STEP: u64 = 0x9E37_79B9_7F4A_7C15;
+// Seeds tried one after another when a level has no rooms or can't be
+// connected up
+const MAX_LEVEL_SEEDS: u64 = 10;
+
+// Where the archways of the current level are. The exit stays sealed until
+// the level's objective is done.
+pub(super) struct Level {
+    pub(super) depth: u32,
+    pub(super) entrance: Point, // the archway the player arrived beside
+    pub(super) exit: Option<Point>, // endless worlds have no way out
+    pub(super) objective: Objective,
+    pub(super) progress: u32, // the objective is done once this reaches `goal`
+    pub(super) goal: u32,
+}
+
+impl Level {
+    // Puts the entrance archway beside the spawn point, it leads back up
+    // unless this is the top of the dungeon
+    pub(super) fn new(
+        map: &mut Map,
+        depth: u32,
+        spawn: Point,
+        exit: Option<Point>,
+        objective: Objective,
+    ) -> Self {
+        let entrance = entrance_spot(map, spawn);
+        let lock = (depth <= STARTING_DEPTH).then_some(Lock::Sealed);
+        map.set_tile(entrance, Tile::Archway { lock });
+
+        let goal = match objective {
+            Objective::ReachExit => 0,
+            Objective::SlayBoss => map.count_tiles(|tile| {
+                tile.monster_def()
+                    .is_some_and(|def| def.behaviour == Behaviour::Boss)
+            }) as u32,
+            Objective::CleanseObelisks(count) => count,
+            Objective::RetrieveArtefact(_) => 1,
+        };
+        let level = Level {
+            depth,
+            entrance,
+            exit,
+            objective,
+            progress: 0,
+            goal,
+        };
+        if level.is_complete() {
+            level.open_exit(map);
+        }
+        level
+    }
+
+    // Hand made maps are a single level and their archways stay as drawn,
+    // there's nowhere to go back to from the spawn
+    pub(super) fn hand_made(spawn: Point) -> Self {
+        Level {
+            depth: STARTING_DEPTH,
+            entrance: spawn,
+            exit: None,
+            objective: Objective::ReachExit,
+            progress: 0,
+            goal: 0,
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.progress >= self.goal
+    }
+
+    // Lifts the seal, an exit locked with a key still needs it
+    fn open_exit(&self, map: &mut Map) {
+        if let Some(exit) = self.exit {
+            if map.get_tile(exit).lock() == Some(Lock::Sealed) {
+                map.set_tile(exit, Tile::Archway { lock: None });
+            }
+        }
+    }
+}
+
+// A free floor tile beside the spawn point, out in the open if there is one
+// so the archway doesn't block a way through. Only when the spawn point is
+// boxed in does the tile below it give way.
+fn entrance_spot(map: &Map, spawn: Point) -> Point {
+    let beside = |point: Point| {
+        DIRECTIONS.into_iter().filter_map(move |(dx, dy)| {
+            let x = point.x.checked_add_signed(dx as isize)?;
+            let y = point.y.checked_add_signed(dy as isize)?;
+            Some(Point::new(x, y))
+        })
+    };
+    let is_free_floor = |point: Point| {
+        map.contains(point) && matches!(map.get_tile(point), Tile::Floor { cursed: false, .. })
+    };
+
+    let free: Vec<Point> = beside(spawn)
+        .filter(|&point| is_free_floor(point))
+        .collect();
+    free.iter()
+        .find(|&&point| beside(point).all(|next| next == spawn || is_free_floor(next)))
+        .or(free.first())
+        .copied()
+        .unwrap_or(Point::new(spawn.x, spawn.y + 1))
+}
+
+// A level the player left, kept as it was for when they come back
+pub(super) struct SavedLevel {
+    map: Map,
+    level: Level,
+    player_position: Point, // where the player stood when they left
+    monster_effects: HashMap<Point, StatusEffects>,
+}
+
+// Builds a fresh level with a random objective, returning the map, the spawn
+// point and where its archways are. The same seed always builds the same level.
+pub(super) fn generate_level(
+    prefabs: &PrefabLibrary,
+    depth: u32,
+    layout: Option<LayoutKind>,
+    size: DungeonSize,
+    seed: u64,
+) -> io::Result<(Map, Point, Level)> {
+    let seed = seed.wrapping_add(DEPTH_SEED_STEP.wrapping_mul(depth as u64));
+    let objective = Objective::random_for_depth(depth, &mut StdRng::seed_from_u64(seed));
+
+    // A level without rooms can't be played and one with rooms that can't be
+    // reached might not be finishable, so the next seed gets a go. The last
+    // level with rooms is kept if none of them work out.
+    let mut kept = None;
+    for offset in 0..MAX_LEVEL_SEEDS {
+        let mut map_generator = MapGenerator::new(size.width, size.height);
+        map_generator
+            .set_prefabs(prefabs.clone())
+            .set_layout(layout.unwrap_or_else(|| LayoutKind::for_depth(depth)))
+            .set_depth(depth)
+            .set_seed(seed.wrapping_add(offset))
+            .set_objective(objective)
+            .generate(size.min_room_size, size.max_room_size);
+        let Some(spawn) = map_generator.get_spawn_room().map(|room| room.center()) else {
+            continue;
+        };
+        let connected = !map_generator.get_connectivity_report().gave_up;
+        kept = Some((map_generator, spawn));
+        if connected {
+            break;
+        }
+    }
+
+    let (map_generator, spawn) = kept.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "No rooms fit in a {}x{} level with rooms of {} to {} tiles",
+                size.width, size.height, size.min_room_size, size.max_room_size
+            ),
+        )
+    })?;
+    let exit = map_generator.get_exit();
+    let objective = map_generator.get_objective();
+
+    let mut map = Map::from_tiles(map_generator.into_dungeon());
+    let level = Level::new(&mut map, depth, spawn, exit, objective);
+    Ok((map, spawn, level))
+}
+
+impl Game {
+    pub fn get_depth(&self) -> u32 {
+        self.level.depth
+    }
+
+    // What the player has to do on this level and how far along they are,
+    // `None` in endless worlds since they have no exit to open
+    pub fn get_objective(&self) -> Option<(Objective, u32, u32)> {
+        self.level.exit?;
+        Some((self.level.objective, self.level.progress, self.level.goal))
+    }
+
+    // Counts towards the objective if it's the one the level asks for, the
+    // exit opens once it's done
+    pub(super) fn advance_objective(&mut self, done: Objective) {
+        let matches = match (done, self.level.objective) {
+            (Objective::RetrieveArtefact(found), Objective::RetrieveArtefact(wanted)) => {
+                found == wanted
+            }
+            (done, wanted) => mem::discriminant(&done) == mem::discriminant(&wanted),
+        };
+        if !matches || self.level.is_complete() {
+            return;
+        }
+
+        self.level.progress += 1;
+        if self.level.is_complete() && self.level.exit.is_some() {
+            self.level.open_exit(&mut self.map);
+            self.stats.objectives_completed += 1;
+            self.log_info_message(format!(
+                "{}: done! Far away, an archway grinds open.",
+                self.level.objective.describe()
+            ));
+        }
+    }
+
+    // Why a sealed archway won't open
+    pub(super) fn explain_seal(&self, point: Point) -> String {
+        if Some(point) == self.level.exit {
+            format!(
+                "The archway is sealed. {} to open it.",
+                self.level.objective.describe()
+            )
+        } else if point == self.level.entrance {
+            "The archway you woke beside is sealed shut.".to_string()
+        } else {
+            "The archway is sealed.".to_string()
+        }
+    }
+
+    // The exit leads one level down, or out of the dungeon from the last
+    // level, the entrance back up to where the player came from
+    pub(super) fn pass_through_archway(&mut self, point: Point) {
+        if Some(point) == self.level.exit && self.level.depth >= FINAL_DEPTH {
+            self.stats.escaped = true;
+            self.log_info_message(
+                "You step through the archway and into daylight. You're free!".to_string(),
+            );
+            return;
+        }
+
+        let depth = if Some(point) == self.level.exit {
+            self.level.depth + 1
+        } else if point == self.level.entrance && self.level.depth > STARTING_DEPTH {
+            self.level.depth - 1
+        } else {
+            self.log_info_message("The archway leads nowhere.".to_string());
+            return;
+        };
+
+        let message = if depth > self.level.depth {
+            format!("You step through the archway, down to depth {}.", depth)
+        } else {
+            format!("You climb back through the archway to depth {}.", depth)
+        };
+
+        let (map, level, position, monster_effects) = match self.saved_levels.remove(&depth) {
+            Some(saved) => (
+                saved.map,
+                saved.level,
+                saved.player_position,
+                saved.monster_effects,
+            ),
+            None => match generate_level(&self.prefabs, depth, None, self.size, self.seed) {
+                Ok((map, spawn, level)) => (map, level, spawn, HashMap::new()),
+                Err(e) => {
+                    self.log_damage_message(format!("The archway leads nowhere: {}", e));
+                    return;
+                }
+            },
+        };
+
+        // Leave the current level as it is, without the player in it
+        self.map.set_tile(self.player_position, self.previous_tile);
+        let left = SavedLevel {
+            map: mem::replace(&mut self.map, map),
+            level: mem::replace(&mut self.level, level),
+            player_position: self.player_position,
+            monster_effects: mem::replace(&mut self.monster_effects, monster_effects),
+        };
+        self.saved_levels.insert(left.level.depth, left);
+
+        self.player_position = position;
+        self.previous_tile = self.map.get_tile(position);
+        self.map.set_tile(
+            position,
+            Tile::Player {
+                is_dead: false,
+                is_cursed: false,
+            },
+        );
+
+        self.stats.deepest = self.stats.deepest.max(depth);
+        self.log_info_message(message);
+        if let Some((objective, progress, goal)) = self.get_objective() {
+            if progress < goal {
+                self.log_info_message(format!("Objective: {}.", objective.describe()));
+            }
+        }
+        self.tick();
+    }
+}
@@ -0,0 +1,138 @@
+use crate::game::{Game, DIRECTIONS};
+use crate::map::types::Point;
+use crate::tile::{Lock, Tile};
+use rand::Rng;
+
+// Picking gets easier with experience, bashing with strength
+const PICK_BASE_CHANCE: f64 = 0.35;
+const PICK_CHANCE_PER_LEVEL: f64 = 0.05;
+const PICK_MAX_CHANCE: f64 = 0.9;
+const BASH_CHANCE_PER_STRENGTH: f64 = 0.06;
+const BASH_MAX_CHANCE: f64 = 0.75;
+const BASH_DAMAGE: u32 = 1;
+
+fn lock_noun(tile: Tile) -> &'static str {
+    match tile {
+        Tile::Archway { .. } => "archway",
+        _ => "door",
+    }
+}
+
+impl Game {
+    // Opens the lock with the key that fits it, or explains why it stays shut
+    pub(super) fn try_unlock(&mut self, point: Point) {
+        let tile = self.map.get_tile(point);
+        let noun = lock_noun(tile);
+        match tile.lock() {
+            None => {}
+            Some(Lock::Sealed) if matches!(tile, Tile::Archway { .. }) => {
+                let message = self.explain_seal(point);
+                self.log_info_message(message);
+            }
+            Some(Lock::Sealed) => {
+                self.log_info_message(format!("The {} is sealed. No key fits it.", noun));
+            }
+            Some(Lock::Key(key)) => {
+                let Some(slot) = self.player.inventory.iter().position(|&item| item == key) else {
+                    self.log_info_message(format!(
+                        "The {} is locked. It needs a {}, or you could try to pick or bash it.",
+                        noun,
+                        key.name()
+                    ));
+                    return;
+                };
+                self.player.inventory.remove(slot);
+                self.set_unlocked(point, true);
+                self.log_info_message(format!("You unlock the {} with the {}.", noun, key.name()));
+                self.tick();
+            }
+        }
+    }
+
+    // Takes a turn whether or not the lock gives
+    pub fn pick_lock(&mut self) {
+        let Some(point) = self.adjacent_locked_door() else {
+            return;
+        };
+        let chance = (PICK_BASE_CHANCE + PICK_CHANCE_PER_LEVEL * self.player.level as f64)
+            .min(PICK_MAX_CHANCE);
+        if self.rng.gen_bool(chance) {
+            self.set_unlocked(point, false);
+            self.log_info_message("The lock clicks open.".to_string());
+        } else {
+            self.log_info_message("You fiddle with the lock, but it won't give.".to_string());
+        }
+        self.tick();
+    }
+
+    // Like picking, but a failed attempt hurts
+    pub fn bash_door(&mut self) {
+        let Some(point) = self.adjacent_locked_door() else {
+            return;
+        };
+        let chance = (BASH_CHANCE_PER_STRENGTH * self.player.strength as f64).min(BASH_MAX_CHANCE);
+        if self.rng.gen_bool(chance) {
+            self.set_unlocked(point, true);
+            self.log_info_message("You bash the door open!".to_string());
+        } else {
+            self.hurt_player(BASH_DAMAGE, "broke against a locked door".to_string());
+            self.log_damage_message(format!(
+                "The door holds. You bruise your shoulder for {} damage.",
+                BASH_DAMAGE
+            ));
+        }
+        self.tick();
+    }
+
+    // The first locked door next to the player, logs why there's nothing to
+    // work on otherwise. Archways are too sturdy to pick or bash.
+    fn adjacent_locked_door(&mut self) -> Option<Point> {
+        let mut refusal = None;
+        for (dx, dy) in DIRECTIONS {
+            let (Some(x), Some(y)) = (
+                self.player_position.x.checked_add_signed(dx as isize),
+                self.player_position.y.checked_add_signed(dy as isize),
+            ) else {
+                continue;
+            };
+            let point = Point::new(x, y);
+            let tile = self.map.get_tile(point);
+            match tile.lock() {
+                Some(Lock::Key(_)) if matches!(tile, Tile::Door { .. }) => return Some(point),
+                Some(Lock::Key(key)) => {
+                    refusal = Some(format!(
+                        "The {} won't give to anything but a {}.",
+                        lock_noun(tile),
+                        key.name()
+                    ))
+                }
+                Some(Lock::Sealed) => {
+                    refusal = Some(format!(
+                        "The {} is sealed by something stronger than a lock.",
+                        lock_noun(tile)
+                    ))
+                }
+                None => {}
+            }
+        }
+        match refusal {
+            Some(message) => self.log_info_message(message),
+            None => self.log_info_message("There's no locked door next to you.".to_string()),
+        }
+        None
+    }
+
+    fn set_unlocked(&mut self, point: Point, open: bool) {
+        let tile = match self.map.get_tile(point) {
+            Tile::Door { visible, .. } => Tile::Door {
+                visible,
+                open,
+                lock: None,
+            },
+            Tile::Archway { .. } => Tile::Archway { lock: None },
+            _ => return,
+        };
+        self.map.set_tile(point, tile);
+        self.update_fov();
+    }
+}
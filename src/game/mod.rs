@@ -1,14 +1,38 @@
-use crate::map::{
-    types::{GameMapTiles, Point},
-    Map,
-};
-use crate::player::Player;
+use crate::effect::{Effect, StatusEffects};
+use crate::generator::layout::LayoutKind;
+use crate::generator::map::DungeonSize;
+use crate::generator::prefab::{PrefabLibrary, PREFAB_DIRECTORY};
+use crate::item::ItemNames;
+use crate::map::{chunk::ChunkStore, error::MapError, grid::TileGrid, types::Point, Map};
+use crate::objective::Objective;
+use crate::player::{perk::Perk, Player};
 use crate::tile::Tile;
-use crate::MapGenerator;
-use rand::seq::SliceRandom;
+pub use difficulty::Difficulty;
+use levels::{Level, SavedLevel};
+pub use morgue::RunRecord;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+pub use replay::{Action, Replay, SAVE_FILE};
+pub use run::{RunStats, RunSummary};
+pub use setup::{custom_maps, GameSetup, MAP_DIRECTORY};
 use std::cmp::{max, min};
+use std::collections::HashMap;
 use std::io;
-use std::sync::{Arc, RwLock};
+use std::path::Path;
+
+mod combat;
+mod difficulty;
+mod effects;
+mod features;
+mod items;
+mod levels;
+mod locks;
+mod morgue;
+mod progression;
+mod replay;
+mod run;
+mod setup;
+mod travel;
 
 pub enum MessageType {
     Info,
@@ -26,9 +50,28 @@ pub struct Game {
     player_position: Point,
     previous_tile: Tile,
     turns: u32,
-    log_messages: Vec<GameMessage>, // Game log is a FIFO queue of 5 messages
+    seed: u64,
+    rng: StdRng,          // seeded, so a replay of the same actions plays out the same
+    size: DungeonSize,    // of every level further down
+    setup: GameSetup,     // what the run was started with, for saves and replays
+    actions: Vec<Action>, // everything the player did, in order
+    difficulty: Difficulty,
+    item_names: ItemNames,     // unidentified item names are shuffled per seed
+    exploration_progress: u32, // newly seen tiles that haven't been turned into experience yet
+    monster_effects: HashMap<Point, StatusEffects>, // monsters don't move, so they're keyed by position
+    log_messages: Vec<GameMessage>,                 // Game log is a FIFO queue of 5 messages
+    level: Level,                                   // the archways of the level the player is on
+    saved_levels: HashMap<u32, SavedLevel>,         // levels left behind, by depth
+    prefabs: PrefabLibrary,                         // for building the levels further down
+    stats: RunStats,
 }
 
+// The player starts at the top of the dungeon
+const STARTING_DEPTH: u32 = 1;
+
+// Chunks of endless worlds are kept here while the player is away from them
+const WORLD_DIRECTORY: &str = "worlds";
+
 const DIRECTIONS: [(i32, i32); 8] = [
     (-1, -1),
     (0, -1),
@@ -41,40 +84,100 @@ const DIRECTIONS: [(i32, i32); 8] = [
 ];
 
 impl Game {
-    pub fn new() -> io::Result<Self> {
-        // Set the dimensions and room sizes for the map generator
-        let width = 400;
-        let height = 200;
-        let min_room_size = 10;
-        let max_room_size = 25;
-
-        // Create the map generator and generate the dungeon
-        let mut map_generator = MapGenerator::new(width, height);
-        map_generator.generate(min_room_size, max_room_size);
-
-        // Get the tiles and rooms from the map generator
-        let tiles_arc = map_generator.get_dungeon();
-        let rooms = map_generator.get_rooms();
-
-        // Choose a random room to place the player
-        let mut rng = rand::thread_rng();
-        let random_room = rooms.choose(&mut rng).expect("No rooms generated");
-        let player_position = random_room.center();
-        // nuke the spawn room for a good time
-        random_room.reset(&tiles_arc);
+    // Uses the layout for the starting depth unless one is asked for. Every
+    // level of the dungeon is `size` big and built from `seed`.
+    pub fn new(layout: Option<LayoutKind>, size: DungeonSize, seed: u64) -> io::Result<Self> {
+        // Deeper levels are built from the same prefabs as the player goes
+        let prefabs = PrefabLibrary::load_or_builtin(PREFAB_DIRECTORY)?;
+        let (map, player_position, level) =
+            levels::generate_level(&prefabs, STARTING_DEPTH, layout, size, seed)?;
+
+        let mut game = Game::start(map, player_position, level, prefabs, seed);
+        game.size = size;
+        Ok(game)
+    }
+
+    // A single hand made level. The player starts on its '@', or on the first
+    // floor tile if it doesn't have one.
+    pub fn from_map_file<P: AsRef<Path>>(path: P, seed: u64) -> io::Result<Self> {
+        let path = path.as_ref();
+        // Secrets and signs without hints are rolled from the seed too
+        let map = Map::load_with(path, &mut StdRng::seed_from_u64(seed)).map_err(|e| match e {
+            MapError::Io(e) => e,
+            e => io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid map {}: {}", path.display(), e),
+            ),
+        })?;
+        Game::from_map(map, seed).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Map {} has nowhere to start", path.display()),
+            )
+        })
+    }
+
+    // Like `from_map_file`, for a level that's already in memory
+    pub fn from_tiles(tiles: TileGrid, seed: u64) -> io::Result<Self> {
+        Game::from_map(Map::from_tiles(tiles), seed).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "The map has nowhere to start")
+        })
+    }
+
+    fn from_map(map: Map, seed: u64) -> Option<Self> {
+        let player_position = map
+            .find_player()
+            .or_else(|| map.find_tile(|tile| matches!(tile, Tile::Floor { .. })))?;
+        Some(Game::start(
+            map,
+            player_position,
+            Level::hand_made(player_position),
+            PrefabLibrary::default(),
+            seed,
+        ))
+    }
+
+    // An endless world made of chunks that stream in as the player explores.
+    // The same seed always builds the same world.
+    pub fn new_infinite(seed: u64) -> io::Result<Self> {
+        let chunks = ChunkStore::new(seed, Path::new(WORLD_DIRECTORY))?;
+        let player_position = chunks.spawn_point();
+
+        let mut map = Map::from_chunks(chunks);
+        map.stream_around(player_position)?;
+
+        let level = Level::new(
+            &mut map,
+            STARTING_DEPTH,
+            player_position,
+            None,
+            Objective::ReachExit,
+        );
+
+        Ok(Game::start(
+            map,
+            player_position,
+            level,
+            PrefabLibrary::default(),
+            seed,
+        ))
+    }
 
+    fn start(
+        mut map: Map,
+        player_position: Point,
+        level: Level,
+        prefabs: PrefabLibrary,
+        seed: u64,
+    ) -> Self {
         // Place the player tile '@' in the selected room
-        {
-            let mut tiles = tiles_arc.write().unwrap();
-            tiles[player_position.y][player_position.x] = Tile::Player {
+        map.set_tile(
+            player_position,
+            Tile::Player {
                 is_dead: false,
                 is_cursed: false,
-            };
-            tiles[player_position.y + 1][player_position.x] = Tile::Archway { locked: true };
-        }
-
-        // Create the Map instance from the generated tiles
-        let map = Map::from_tiles(tiles_arc);
+            },
+        );
 
         // Create the player
         let player = Player::new();
@@ -89,20 +192,40 @@ impl Game {
                 cursed: false,
             },
             turns: 0,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            size: DungeonSize::default(),
+            setup: GameSetup::default(),
+            actions: Vec::new(),
+            difficulty: Difficulty::default(),
+            item_names: ItemNames::new(seed),
+            exploration_progress: 0,
+            monster_effects: HashMap::new(),
             log_messages: Vec::with_capacity(5),
+            level,
+            saved_levels: HashMap::new(),
+            prefabs,
+            stats: RunStats {
+                deepest: STARTING_DEPTH,
+                ..Default::default()
+            },
         };
 
         // Perform initial FOV update
         game.tick();
 
-        Ok(game)
+        game
     }
 
     // This function updates the game state by one turn
     // everything that updates goes in here
     fn tick(&mut self) {
         self.turns += 1;
+        if let Err(e) = self.map.stream_around(self.player_position) {
+            self.log_damage_message(format!("The world around you fails to load: {}", e));
+        }
         self.map.apply_obelisk_curses();
+        self.catch_obelisk_curse();
         self.update_fov();
         self.check_effects();
     }
@@ -116,38 +239,37 @@ impl Game {
             .max(0)
             .min((self.map.height() - 1) as i32) as usize;
 
-        self.walk_to_tile(Point {
+        let search_position = Point {
             x: search_x,
             y: search_y,
-        });
+        };
+        let tile = self.map.get_tile(search_position);
+        if tile.is_monster() {
+            self.attack(search_position);
+        } else if tile.lock().is_some() {
+            self.try_unlock(search_position);
+        } else {
+            self.walk_to_tile(search_position);
+        }
     }
 
     pub fn show_hint(&mut self) {}
 
     fn walk_to_tile(&mut self, search_position: Point) {
+        // Open archways lead to other levels
+        if let Tile::Archway { lock: None } = self.map.get_tile(search_position) {
+            self.pass_through_archway(search_position);
+            return;
+        }
         if self.map.is_walkable(search_position) {
-            // Restore the previous tile
-            self.map.set_tile(self.player_position, self.previous_tile);
-
-            // Store the new tile before moving onto it
-            self.previous_tile = self.map.get_tile(search_position);
-
             let is_destination_deadly = self.map.is_deadly(search_position);
 
-            // Update player position
-            self.player_position = search_position;
-            // Place the player on the new tile
-            self.map.set_tile(
-                self.player_position,
-                Tile::Player {
-                    is_dead: false,
-                    is_cursed: false, // don't need to check for curses here
-                },
-            );
+            self.relocate_player(search_position);
+            self.pick_up_floor_item();
 
             if is_destination_deadly {
                 self.log_damage_message("You died!".to_string());
-                self.player.take_damage(self.player.current_hp);
+                self.kill_player("fell into a pit".to_string());
             }
 
             self.tick();
@@ -156,53 +278,60 @@ impl Game {
         }
     }
 
-    fn check_effects(&mut self) {
-        if self.is_player_cursed() {
-            self.apply_curse_effects();
-        } else {
-            self.player.gain_exp(1); // @TODO implement normal exp gain
+    // Every source of damage names itself, so a death always has a cause
+    fn hurt_player(&mut self, damage: u32, cause: String) {
+        let was_alive = !self.player.is_dead();
+        self.player
+            .take_damage(self.difficulty.scale_damage(damage));
+        if was_alive && self.player.is_dead() {
+            self.stats.cause_of_death = Some(cause);
         }
     }
 
-    fn apply_curse_effects(&mut self) {
-        let cursing_tile = self.map.get_obelisk_cursing_tile(self.player_position);
-        if let Some(Tile::Obelisk {
-            visible: true,
-            damage_hp,
-            ..
-        }) = cursing_tile
-        {
-            self.log_damage_message(format!(
-                "You take {} damage from the Obelisk curse",
-                damage_hp
-            ));
-            self.player.take_damage(damage_hp);
+    // No difficulty saves the player from this one
+    fn kill_player(&mut self, cause: String) {
+        if !self.player.is_dead() {
+            self.player.take_damage(self.player.current_hp);
+            self.stats.cause_of_death = Some(cause);
         }
     }
 
-    fn is_player_cursed(&self) -> bool {
-        matches!(
-            self.map.get_tile(self.player_position),
+    // Moves the player tile without taking a turn
+    fn relocate_player(&mut self, position: Point) {
+        // Restore the previous tile
+        self.map.set_tile(self.player_position, self.previous_tile);
+
+        // Store the new tile before moving onto it
+        self.previous_tile = self.map.get_tile(position);
+
+        // Update player position
+        self.player_position = position;
+        // Place the player on the new tile
+        self.map.set_tile(
+            self.player_position,
             Tile::Player {
-                is_cursed: true,
-                ..
-            }
-        )
+                is_dead: false,
+                is_cursed: false, // don't need to check for curses here
+            },
+        );
+    }
+
+    fn check_effects(&mut self) {
+        self.tick_status_effects();
+        self.award_exploration_exp();
+    }
+
+    fn is_player_cursed(&self) -> bool {
+        self.player
+            .effects
+            .any(|effect| matches!(effect, Effect::Curse { .. }))
     }
 
     fn get_player_fov_radius(&self) -> u32 {
-        if self.is_player_cursed() {
-            if let Some(Tile::Obelisk {
-                reduce_fov_radius, ..
-            }) = self.map.get_obelisk_cursing_tile(self.player_position)
-            {
-                reduce_fov_radius
-            } else {
-                self.player.fov_radius
-            }
-        } else {
-            self.player.fov_radius
-        }
+        self.player.effects.fov_radius(
+            self.player.fov_radius,
+            self.player.has_perk(Perk::CurseWard),
+        )
     }
 
     fn update_fov(&mut self) {
@@ -210,8 +339,20 @@ impl Game {
         self.map.update_fov(self.player_position, fov_radius);
     }
 
-    pub fn get_map(&self) -> &Arc<RwLock<GameMapTiles>> {
-        self.map.get_tiles()
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn get_difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+    }
+
+    pub fn get_map(&self) -> &Map {
+        &self.map
     }
 
     pub fn get_player(&self) -> &Player {
@@ -222,8 +363,12 @@ impl Game {
         self.player_position
     }
 
+    pub fn get_map_size(&self) -> (usize, usize) {
+        (self.map.width(), self.map.height())
+    }
+
     pub fn is_game_over(&self) -> bool {
-        self.player.is_dead()
+        self.player.is_dead() || self.has_escaped()
     }
 
     pub fn log_message(&mut self, message: String, message_type: MessageType) {
@@ -266,6 +411,38 @@ impl Game {
             ) as usize;
             let search_position = Point::new(search_x, search_y);
 
+            if let Tile::Secret {
+                visible: true,
+                rarity,
+            } = self.map.get_tile(search_position)
+            {
+                self.reveal_secret(search_position, rarity);
+                self.tick();
+                return;
+            }
+            match self.map.get_tile(search_position) {
+                Tile::Shrine {
+                    visible: true,
+                    used,
+                } => {
+                    self.pray_at_shrine(search_position, used);
+                    self.tick();
+                    return;
+                }
+                Tile::Sign {
+                    visible: true,
+                    lore,
+                } => {
+                    self.read_sign(lore);
+                    self.tick();
+                    return;
+                }
+                tile if tile.lock().is_some() => {
+                    self.try_unlock(search_position);
+                    return;
+                }
+                _ => {}
+            }
             if self.map.is_interactable(search_position) {
                 self.map.interact_tile(search_position);
                 self.tick();
@@ -0,0 +1,123 @@
+use crate::game::Game;
+use crate::map::types::Point;
+use crate::scores::{HighScores, ScoreEntry};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Where a finished run ended up: its morgue file and the high score table
+// with it in place
+#[derive(Clone, Debug)]
+pub struct RunRecord {
+    pub morgue: PathBuf,
+    pub scores: HighScores,
+    pub rank: Option<usize>, // counting from 0, `None` if it didn't make the table
+}
+
+impl Game {
+    // Everything worth remembering about the run as plain text
+    pub fn get_morgue_report(&self) -> String {
+        let summary = self.get_run_summary();
+        let mut lines = vec![
+            "RustyRogue morgue".to_string(),
+            String::new(),
+            format!("Seed: {}", self.seed),
+            format!("Outcome: {}", summary.outcome()),
+            format!("Score: {}", summary.score()),
+            format!("Turns: {}", summary.turns),
+            format!(
+                "Depth: {} (deepest {})",
+                self.get_depth(),
+                summary.stats.deepest
+            ),
+            format!("Level: {}", summary.level),
+            format!("Secrets found: {}", summary.stats.secrets_found),
+            format!(
+                "Objectives completed: {}",
+                summary.stats.objectives_completed
+            ),
+            String::new(),
+            "Kills:".to_string(),
+        ];
+        if summary.stats.kills.is_empty() {
+            lines.push("  none".to_string());
+        }
+        for (name, count) in &summary.stats.kills {
+            lines.push(format!("  {:>3} {}", count, name));
+        }
+
+        lines.push(String::new());
+        lines.push("Last messages:".to_string());
+        for message in &self.log_messages {
+            lines.push(format!("  {}", message.message));
+        }
+
+        lines.push(String::new());
+        lines.push("Map:".to_string());
+        lines.extend(self.map_snapshot());
+
+        lines.join("\n") + "\n"
+    }
+
+    // The explored part of the current level, unexplored tiles left blank
+    fn map_snapshot(&self) -> Vec<String> {
+        let explored = self.map.get_explored_tiles();
+        let (Some(min_x), Some(max_x), Some(min_y), Some(max_y)) = (
+            explored.iter().map(|point| point.x).min(),
+            explored.iter().map(|point| point.x).max(),
+            explored.iter().map(|point| point.y).min(),
+            explored.iter().map(|point| point.y).max(),
+        ) else {
+            return Vec::new();
+        };
+
+        (min_y..=max_y)
+            .map(|y| {
+                let row: String = (min_x..=max_x)
+                    .map(|x| {
+                        let point = Point::new(x, y);
+                        match self.map.try_get(point) {
+                            Ok(tile)
+                                if point == self.player_position || explored.contains(&point) =>
+                            {
+                                tile.as_char()
+                            }
+                            _ => ' ',
+                        }
+                    })
+                    .collect();
+                row.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    // Writes the morgue file and puts the run on the high score table
+    pub fn record_run(&self, morgue_directory: &Path, scores_file: &Path) -> io::Result<RunRecord> {
+        fs::create_dir_all(morgue_directory)?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        let morgue = morgue_directory.join(format!("{}-{}.txt", self.seed, timestamp));
+        fs::write(&morgue, self.get_morgue_report())?;
+
+        let summary = self.get_run_summary();
+        let mut scores = HighScores::load(scores_file)?;
+        let rank = scores.add(ScoreEntry {
+            score: summary.score(),
+            depth: summary.stats.deepest,
+            level: summary.level,
+            turns: summary.turns,
+            seed: self.seed,
+            outcome: summary.outcome(),
+        });
+        scores.save(scores_file)?;
+
+        Ok(RunRecord {
+            morgue,
+            scores,
+            rank,
+        })
+    }
+}
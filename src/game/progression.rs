@@ -0,0 +1,61 @@
+use crate::game::Game;
+use crate::item::Item;
+use crate::map::types::Point;
+use crate::player::perk::{LevelUpChoice, Perk};
+
+// One experience point for every this many tiles seen for the first time
+const TILES_PER_EXPLORATION_EXP: u32 = 10;
+// Experience for a secret of rarity 1, every order of magnitude rarer adds another share
+const SECRET_EXP: u32 = 15;
+
+impl Game {
+    pub fn get_level_up_choices(&self) -> Vec<LevelUpChoice> {
+        self.player.get_level_up_choices()
+    }
+
+    pub fn has_pending_level_up(&self) -> bool {
+        self.player.pending_level_ups > 0
+    }
+
+    pub fn choose_level_up(&mut self, choice: LevelUpChoice) {
+        if self.player.choose_level_up(choice) {
+            self.log_info_message(format!(
+                "You chose {}: {}.",
+                choice.name(),
+                choice.description()
+            ));
+            // Perks like Keen Eyes change what the player can see right away
+            self.update_fov();
+        }
+    }
+
+    pub(super) fn award_exp(&mut self, amount: u32) {
+        let levels_gained = self.player.gain_exp(amount);
+        let first_new_level = self.player.level + 1 - levels_gained;
+        for level in first_new_level..=self.player.level {
+            self.log_info_message(format!("You reached level {}!", level));
+        }
+    }
+
+    pub(super) fn award_exploration_exp(&mut self) {
+        self.exploration_progress += self.map.get_newly_explored_tiles().len() as u32;
+        let exp = self.exploration_progress / TILES_PER_EXPLORATION_EXP;
+        self.exploration_progress %= TILES_PER_EXPLORATION_EXP;
+        if exp > 0 {
+            self.award_exp(exp);
+        }
+    }
+
+    pub(super) fn reveal_secret(&mut self, point: Point, rarity: u32) {
+        let mut exp = SECRET_EXP * (rarity.max(1).ilog10() + 1);
+        if self.player.has_perk(Perk::TreasureHunter) {
+            exp *= 2;
+        }
+        self.map.interact_tile(point);
+        self.stats.secrets_found += 1;
+        self.log_info_message(format!("You uncover a secret! (+{} XP)", exp));
+        self.award_exp(exp);
+        let item = Item::random_for_rarity(rarity, &mut self.rng);
+        self.pick_up(item);
+    }
+}
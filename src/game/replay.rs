@@ -0,0 +1,270 @@
+use crate::game::{Difficulty, Game, GameSetup};
+use crate::generator::layout::LayoutKind;
+use crate::generator::map::DungeonSize;
+use crate::map::types::Point;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// An unfinished run is kept here between sessions
+pub const SAVE_FILE: &str = "save.txt";
+
+const ACTIONS_HEADER: &str = "actions";
+
+// Something the player did that takes the game forward. Runs are seeded, so
+// doing the same actions again plays out the same way.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    Move(i32, i32),
+    Interact,
+    AutoExplore,
+    TravelToStairs,
+    TravelTo(Point),
+    UseItem(usize), // inventory slot
+    PickLock,
+    BashDoor,
+    LevelUp(usize), // index into the level up choices
+}
+
+impl Action {
+    fn encode(&self) -> String {
+        match self {
+            Action::Move(dx, dy) => format!("move {} {}", dx, dy),
+            Action::Interact => "interact".to_string(),
+            Action::AutoExplore => "explore".to_string(),
+            Action::TravelToStairs => "stairs".to_string(),
+            Action::TravelTo(point) => format!("travel {} {}", point.x, point.y),
+            Action::UseItem(slot) => format!("use {}", slot),
+            Action::PickLock => "pick".to_string(),
+            Action::BashDoor => "bash".to_string(),
+            Action::LevelUp(choice) => format!("level {}", choice),
+        }
+    }
+
+    fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let args: Vec<&str> = words.collect();
+        let number = |index: usize| -> Result<i64, String> {
+            let value = args
+                .get(index)
+                .ok_or_else(|| format!("'{}' is missing a value", name))?;
+            value
+                .parse()
+                .map_err(|e| format!("invalid value '{}' for '{}': {}", value, name, e))
+        };
+        let expect_args = |count: usize| {
+            if args.len() == count {
+                Ok(())
+            } else {
+                Err(format!(
+                    "'{}' takes {} values, found {}",
+                    name,
+                    count,
+                    args.len()
+                ))
+            }
+        };
+
+        let action = match name {
+            "move" => {
+                expect_args(2)?;
+                let (dx, dy) = (number(0)?, number(1)?);
+                if dx.abs() > 1 || dy.abs() > 1 {
+                    return Err(format!("can't move {} {} in one step", dx, dy));
+                }
+                Action::Move(dx as i32, dy as i32)
+            }
+            "interact" => Action::Interact,
+            "explore" => Action::AutoExplore,
+            "stairs" => Action::TravelToStairs,
+            "travel" => {
+                expect_args(2)?;
+                let (x, y) = (number(0)?, number(1)?);
+                if x < 0 || y < 0 {
+                    return Err(format!("can't travel to {} {}", x, y));
+                }
+                Action::TravelTo(Point::new(x as usize, y as usize))
+            }
+            "use" => {
+                expect_args(1)?;
+                Action::UseItem(number(0)?.max(0) as usize)
+            }
+            "pick" => Action::PickLock,
+            "bash" => Action::BashDoor,
+            "level" => {
+                expect_args(1)?;
+                Action::LevelUp(number(0)?.max(0) as usize)
+            }
+            _ => return Err(format!("unknown action '{}'", name)),
+        };
+        if !matches!(
+            action,
+            Action::Move(..) | Action::TravelTo(_) | Action::UseItem(_) | Action::LevelUp(_)
+        ) {
+            expect_args(0)?;
+        }
+        Ok(action)
+    }
+}
+
+// A run as the setup it started from and everything the player did since,
+// enough to play it back or pick it up again
+#[derive(Clone, Debug)]
+pub struct Replay {
+    pub setup: GameSetup,
+    pub actions: Vec<Action>,
+}
+
+impl Replay {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)?;
+        Replay::parse(&source).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid replay {}: {}", path.display(), e),
+            )
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.encode())
+    }
+
+    // `key value` lines for the setup, then one action per line
+    pub fn encode(&self) -> String {
+        let setup = &self.setup;
+        let mut lines = vec!["# rustyrogue replay".to_string()];
+        if let Some(seed) = setup.seed {
+            lines.push(format!("seed {}", seed));
+        }
+        lines.push(format!("difficulty {}", setup.difficulty));
+        if let Some(layout) = setup.layout {
+            lines.push(format!("layout {}", layout));
+        }
+        let size = setup.size;
+        lines.push(format!(
+            "size {} {} {} {}",
+            size.width, size.height, size.min_room_size, size.max_room_size
+        ));
+        if let Some(map) = &setup.map {
+            lines.push(format!("map {}", map.display()));
+        }
+        lines.push(ACTIONS_HEADER.to_string());
+        lines.extend(self.actions.iter().map(Action::encode));
+        lines.join("\n") + "\n"
+    }
+
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut setup = GameSetup::default();
+        let mut actions = Vec::new();
+        let mut in_actions = false;
+
+        for (number, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let at_line = |e: String| format!("line {}: {}", number + 1, e);
+            if in_actions {
+                actions.push(Action::parse(line).map_err(at_line)?);
+            } else if line == ACTIONS_HEADER {
+                in_actions = true;
+            } else {
+                parse_setup_line(&mut setup, line).map_err(at_line)?;
+            }
+        }
+
+        if !in_actions {
+            return Err(format!("missing '{}' section", ACTIONS_HEADER));
+        }
+        if setup.seed.is_none() {
+            return Err("missing seed".to_string());
+        }
+        Ok(Replay { setup, actions })
+    }
+
+    // Starts the run again and plays every action, leaving the game where
+    // the player left it
+    pub fn resume(&self) -> io::Result<Game> {
+        let mut game = self.setup.start()?;
+        for &action in &self.actions {
+            game.perform(action);
+        }
+        Ok(game)
+    }
+}
+
+fn parse_setup_line(setup: &mut GameSetup, line: &str) -> Result<(), String> {
+    let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+    let value = value.trim();
+    match key {
+        "seed" => {
+            let seed = value
+                .parse()
+                .map_err(|e| format!("invalid seed '{}': {}", value, e))?;
+            setup.seed = Some(seed);
+        }
+        "difficulty" => setup.difficulty = value.parse::<Difficulty>()?,
+        "layout" => setup.layout = Some(value.parse::<LayoutKind>()?),
+        "size" => {
+            let numbers = value
+                .split_whitespace()
+                .map(|number| number.parse::<usize>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("invalid size '{}': {}", value, e))?;
+            let [width, height, min_room_size, max_room_size] = numbers[..] else {
+                return Err(format!("size needs 4 numbers, found '{}'", value));
+            };
+            setup.size = DungeonSize {
+                width,
+                height,
+                min_room_size,
+                max_room_size,
+            };
+            setup.size.validate()?;
+        }
+        "map" => setup.map = Some(PathBuf::from(value)),
+        "endless" => {
+            return Err("endless worlds keep their own state and can't be replayed".to_string())
+        }
+        _ => return Err(format!("unknown setting '{}'", key)),
+    }
+    Ok(())
+}
+
+impl Game {
+    // The one way the player changes the game, so that it can be recorded
+    pub fn perform(&mut self, action: Action) {
+        self.actions.push(action);
+        match action {
+            Action::Move(dx, dy) => self.move_player(dx, dy),
+            Action::Interact => self.interact(),
+            Action::AutoExplore => self.auto_explore(),
+            Action::TravelToStairs => self.travel_to_stairs(),
+            Action::TravelTo(point) => self.travel_to(point),
+            Action::UseItem(slot) => self.use_item(slot),
+            Action::PickLock => self.pick_lock(),
+            Action::BashDoor => self.bash_door(),
+            Action::LevelUp(index) => {
+                if let Some(&choice) = self.get_level_up_choices().get(index) {
+                    self.choose_level_up(choice);
+                }
+            }
+        }
+    }
+
+    // Endless worlds write their chunks to disk as they go, so only the
+    // dungeon and hand made maps can be played back
+    pub fn can_replay(&self) -> bool {
+        !self.setup.endless
+    }
+
+    pub fn get_replay(&self) -> Replay {
+        Replay {
+            setup: self.setup.clone(),
+            actions: self.actions.clone(),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::game::Game;
+use std::collections::BTreeMap;
+
+// What the player got up to over the whole run
+#[derive(Clone, Debug, Default)]
+pub struct RunStats {
+    pub escaped: bool, // made it out through the exit of the last level
+    pub cause_of_death: Option<String>, // e.g. "fell into a pit"
+    pub deepest: u32,
+    pub kills: BTreeMap<String, u32>, // by monster name
+    pub secrets_found: u32,
+    pub objectives_completed: u32,
+}
+
+impl RunStats {
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+}
+
+// The end of a run at a glance
+#[derive(Clone, Debug)]
+pub struct RunSummary {
+    pub stats: RunStats,
+    pub turns: u32,
+    pub level: u32,
+}
+
+// What each achievement is worth on the high score table
+const DEPTH_SCORE: u32 = 100;
+const KILL_SCORE: u32 = 10;
+const SECRET_SCORE: u32 = 25;
+const OBJECTIVE_SCORE: u32 = 200;
+const ESCAPE_SCORE: u32 = 1000;
+
+impl RunSummary {
+    pub fn score(&self) -> u32 {
+        let escaped = if self.stats.escaped { ESCAPE_SCORE } else { 0 };
+        self.stats.deepest * DEPTH_SCORE
+            + self.stats.total_kills() * KILL_SCORE
+            + self.stats.secrets_found * SECRET_SCORE
+            + self.stats.objectives_completed * OBJECTIVE_SCORE
+            + escaped
+    }
+
+    // How the run ended, in a few words
+    pub fn outcome(&self) -> String {
+        if self.stats.escaped {
+            "escaped".to_string()
+        } else {
+            self.stats
+                .cause_of_death
+                .clone()
+                .unwrap_or_else(|| "gave up".to_string())
+        }
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let kills = if self.stats.kills.is_empty() {
+            "nothing".to_string()
+        } else {
+            self.stats
+                .kills
+                .iter()
+                .map(|(name, count)| format!("{} {}", count, name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        vec![
+            format!(
+                "Reached depth {} at level {} in {} turns",
+                self.stats.deepest, self.level, self.turns
+            ),
+            format!("Slew {}", kills),
+            format!("Found {} secrets", self.stats.secrets_found),
+            format!("Completed {} objectives", self.stats.objectives_completed),
+            format!("Scored {}", self.score()),
+        ]
+    }
+}
+
+impl Game {
+    pub fn has_escaped(&self) -> bool {
+        self.stats.escaped
+    }
+
+    pub fn get_run_summary(&self) -> RunSummary {
+        RunSummary {
+            stats: self.stats.clone(),
+            turns: self.turns,
+            level: self.player.level,
+        }
+    }
+}
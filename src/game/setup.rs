@@ -0,0 +1,49 @@
+use crate::game::{Difficulty, Game};
+use crate::generator::layout::LayoutKind;
+use crate::generator::map::DungeonSize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Hand made maps are plain text files in here
+pub const MAP_DIRECTORY: &str = "maps";
+
+// Everything picked before a run starts, from the menu or the command line
+#[derive(Clone, Debug, Default)]
+pub struct GameSetup {
+    pub seed: Option<u64>, // a random one when not given
+    pub difficulty: Difficulty,
+    pub layout: Option<LayoutKind>, // the depth decides when not given
+    pub size: DungeonSize,
+    pub endless: bool,        // an endless chunked world instead of the dungeon
+    pub map: Option<PathBuf>, // a hand made map instead of a generated one
+}
+
+impl GameSetup {
+    pub fn start(&self) -> io::Result<Game> {
+        let seed = self.seed.unwrap_or_else(rand::random);
+        let mut game = match &self.map {
+            Some(path) => Game::from_map_file(path, seed)?,
+            None if self.endless => Game::new_infinite(seed)?,
+            None => Game::new(self.layout, self.size, seed)?,
+        };
+        game.set_difficulty(self.difficulty);
+        // Remember the seed that was rolled, so the run can be played again
+        game.setup = GameSetup {
+            seed: Some(seed),
+            ..self.clone()
+        };
+        Ok(game)
+    }
+}
+
+// The `.txt` maps in `directory`, sorted by name. Sidecar files and
+// subdirectories like the prefabs are left out.
+pub fn custom_maps<P: AsRef<Path>>(directory: P) -> io::Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<_>>()?;
+    paths.retain(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "txt"));
+    paths.sort();
+    Ok(paths)
+}
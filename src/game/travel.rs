@@ -0,0 +1,218 @@
+use crate::game::Game;
+use crate::map::pathfinding::{astar, DijkstraMap, PathCost};
+use crate::map::types::Point;
+use crate::tile::Tile;
+use std::collections::HashSet;
+
+// Safety net so a single command can never loop forever
+const MAX_TRAVEL_STEPS: u32 = 1000;
+
+enum TravelStop {
+    Arrived,
+    NothingLeftToExplore,
+    NoPath,
+    MonsterInView,
+    TookDamage,
+    ItemSeen,
+    Cursed,
+    LevelUp,
+    TooManySteps,
+}
+
+impl TravelStop {
+    fn message(&self) -> &'static str {
+        match self {
+            TravelStop::Arrived => "You arrive at your destination.",
+            TravelStop::NothingLeftToExplore => "There is nothing left to explore nearby.",
+            TravelStop::NoPath => "You don't know how to get there.",
+            TravelStop::MonsterInView => "You stop. There is a monster in view!",
+            TravelStop::TookDamage => "You stop. You are taking damage!",
+            TravelStop::ItemSeen => "You stop. You spot something interesting.",
+            TravelStop::Cursed => "You stop. You stepped into an obelisk curse!",
+            TravelStop::LevelUp => "You stop. You feel more experienced!",
+            TravelStop::TooManySteps => "You stop to catch your breath.",
+        }
+    }
+}
+
+impl Game {
+    // Walk towards the nearest reachable unexplored tile until something interesting happens
+    pub fn auto_explore(&mut self) {
+        if self.map.is_monster_in_view() {
+            self.log_info_message("You can't explore with a monster in view.".to_string());
+            return;
+        }
+
+        let stop = self.travel_loop(|game| {
+            let frontier = game.find_unexplored_frontier();
+            game.next_step_towards_nearest(&frontier)
+        });
+
+        let stop = match stop {
+            TravelStop::NoPath => TravelStop::NothingLeftToExplore,
+            stop => stop,
+        };
+        self.log_info_message(stop.message().to_string());
+    }
+
+    // Walk to a known location picked by the player
+    pub fn travel_to(&mut self, target: Point) {
+        if target == self.player_position {
+            return;
+        }
+        if !self.map.is_known(target) {
+            self.log_info_message(TravelStop::NoPath.message().to_string());
+            return;
+        }
+
+        let stop = self.travel_loop(|game| {
+            if game.player_position == target {
+                return None;
+            }
+            astar(&game.map, game.player_position, target, |point, tile| {
+                game.travel_cost(point, tile, &[])
+            })
+            .and_then(|path| path.first().copied())
+        });
+
+        let stop = match stop {
+            TravelStop::NoPath if self.player_position == target => TravelStop::Arrived,
+            stop => stop,
+        };
+        self.log_info_message(stop.message().to_string());
+    }
+
+    // Walk to the nearest stairs the player has already seen
+    pub fn travel_to_stairs(&mut self) {
+        let known_stairs = self.find_known_stairs();
+        if known_stairs.is_empty() {
+            self.log_info_message("You don't know of any stairs.".to_string());
+            return;
+        }
+
+        let stop = self.travel_loop(|game| {
+            if known_stairs.contains(&game.player_position) {
+                return None;
+            }
+            game.next_step_towards_nearest(&known_stairs)
+        });
+
+        let stop = match stop {
+            TravelStop::NoPath if known_stairs.contains(&self.player_position) => {
+                TravelStop::Arrived
+            }
+            stop => stop,
+        };
+        self.log_info_message(stop.message().to_string());
+    }
+
+    // Keeps taking the step given by `next_step` until it runs out of steps or
+    // something interrupts the journey
+    fn travel_loop<F>(&mut self, next_step: F) -> TravelStop
+    where
+        F: Fn(&Game) -> Option<Point>,
+    {
+        for _ in 0..MAX_TRAVEL_STEPS {
+            let next_step = match next_step(self) {
+                Some(step) => step,
+                None => return TravelStop::NoPath,
+            };
+
+            let hp_before = self.player.current_hp;
+
+            if matches!(self.map.get_tile(next_step), Tile::Door { open: false, .. }) {
+                // Open the door in the way, then keep going on the next step
+                self.map.interact_tile(next_step);
+                self.tick();
+            } else {
+                self.walk_to_tile(next_step);
+            }
+
+            if let Some(stop) = self.check_travel_interrupts(hp_before) {
+                return stop;
+            }
+        }
+
+        TravelStop::TooManySteps
+    }
+
+    fn check_travel_interrupts(&self, hp_before: u32) -> Option<TravelStop> {
+        if self.player.current_hp < hp_before || self.is_game_over() {
+            Some(TravelStop::TookDamage)
+        } else if self.is_player_cursed() {
+            Some(TravelStop::Cursed)
+        } else if self.has_pending_level_up() {
+            Some(TravelStop::LevelUp)
+        } else if self.map.is_monster_in_view() {
+            Some(TravelStop::MonsterInView)
+        } else if self.map.get_newly_explored_tiles().iter().any(|&point| {
+            matches!(
+                self.map.get_tile(point),
+                Tile::Secret { .. } | Tile::Item { .. }
+            )
+        }) {
+            Some(TravelStop::ItemSeen)
+        } else {
+            None
+        }
+    }
+
+    fn next_step_towards_nearest(&self, goals: &[Point]) -> Option<Point> {
+        if goals.is_empty() {
+            return None;
+        }
+        let dijkstra_map = DijkstraMap::new(&self.map, goals, |point, tile| {
+            self.travel_cost(point, tile, goals)
+        });
+        dijkstra_map.next_step(self.player_position)
+    }
+
+    // Only plan routes through tiles the player has seen or had revealed,
+    // goals may be unknown
+    fn travel_cost(&self, point: Point, tile: Tile, goals: &[Point]) -> Option<u32> {
+        if self.map.is_known(point) || goals.contains(&point) {
+            PathCost::CAUTIOUS.of(tile)
+        } else {
+            None
+        }
+    }
+
+    // Unexplored walkable tiles right next to ones the player has already seen
+    fn find_unexplored_frontier(&self) -> Vec<Point> {
+        let (width, height) = (self.map.width(), self.map.height());
+        let mut frontier = HashSet::new();
+
+        for &explored in self.map.get_explored_tiles() {
+            for (dx, dy) in [(0, -1), (-1, 0), (1, 0), (0, 1)] {
+                let nx = explored.x as i32 + dx;
+                let ny = explored.y as i32 + dy;
+                if nx < 0 || ny < 0 || nx as usize >= width || ny as usize >= height {
+                    continue;
+                }
+                let point = Point::new(nx as usize, ny as usize);
+                if !self.map.is_explored(point) && self.map.get_tile(point).is_walkable() {
+                    frontier.insert(point);
+                }
+            }
+        }
+
+        // Sets come out in any order, keep ties between equally near goals stable
+        let mut frontier: Vec<Point> = frontier.into_iter().collect();
+        frontier.sort_by_key(|point| (point.y, point.x));
+        frontier
+    }
+
+    fn find_known_stairs(&self) -> Vec<Point> {
+        let mut stairs: Vec<Point> = self
+            .map
+            .get_explored_tiles()
+            .iter()
+            .chain(self.map.get_revealed_tiles())
+            .filter(|&&point| matches!(self.map.get_tile(point), Tile::Stairs { .. }))
+            .copied()
+            .collect();
+        stairs.sort_by_key(|point| (point.y, point.x));
+        stairs.dedup();
+        stairs
+    }
+}
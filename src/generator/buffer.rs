@@ -0,0 +1,83 @@
+use crate::generator::room::{Room, RoomShape};
+use crate::map::grid::TileGrid;
+use crate::map::types::Point;
+use crate::tile::Tile;
+
+// A room's own copy of the map around it, so rooms can be worked on at the
+// same time without seeing each other's changes. What a room changed is
+// merged back afterwards, in room order. Buffers of rooms that share a wall
+// overlap there, and when both change a shared tile the later room wins.
+pub struct RoomBuffer {
+    origin: Point, // where the buffer's top left corner is on the map
+    before: TileGrid,
+    tiles: TileGrid,
+    room: Room, // moved into buffer coordinates
+}
+
+impl RoomBuffer {
+    // The room's footprint and one tile around it, as far as the map goes
+    pub fn new(room: &Room, map: &TileGrid) -> Self {
+        let origin = Point::new(
+            room.location.x.saturating_sub(1),
+            room.location.y.saturating_sub(1),
+        );
+        let right = (room.location.x + room.width + 1).min(map.width().saturating_sub(1));
+        let bottom = (room.location.y + room.height + 1).min(map.height().saturating_sub(1));
+
+        let mut tiles = TileGrid::new(
+            (right + 1).saturating_sub(origin.x),
+            (bottom + 1).saturating_sub(origin.y),
+        );
+        for point in tiles.points() {
+            if let Some(tile) = map.get(Point::new(origin.x + point.x, origin.y + point.y)) {
+                tiles.set(point, tile);
+            }
+        }
+
+        let to_local = |point: Point| Point::new(point.x - origin.x, point.y - origin.y);
+        let shape = match &room.shape {
+            RoomShape::Prefab {
+                name,
+                anchor,
+                doors,
+            } => RoomShape::Prefab {
+                name: name.clone(),
+                anchor: to_local(*anchor),
+                doors: doors.iter().copied().map(to_local).collect(),
+            },
+            shape => shape.clone(),
+        };
+
+        RoomBuffer {
+            origin,
+            before: tiles.clone(),
+            tiles,
+            room: Room {
+                location: to_local(room.location),
+                shape,
+                ..room.clone()
+            },
+        }
+    }
+
+    pub fn run<T>(&mut self, work: impl FnOnce(&Room, &mut TileGrid) -> T) -> T {
+        work(&self.room, &mut self.tiles)
+    }
+
+    // Every tile the room changed, in map coordinates. Tiles it left alone
+    // aren't included, so they can't undo an earlier room's change.
+    pub fn into_changes(self) -> Vec<(Point, Tile)> {
+        self.tiles
+            .points()
+            .filter_map(|point| {
+                let tile = self.tiles.get(point)?;
+                (self.before.get(point) != Some(tile)).then(|| {
+                    (
+                        Point::new(self.origin.x + point.x, self.origin.y + point.y),
+                        tile,
+                    )
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,205 @@
+use crate::data::spawns;
+use crate::generator::layout::carve_corridor;
+use crate::generator::room::Room;
+use crate::map::chunk::{ChunkCoord, CHUNK_SIZE, WORLD_SIZE_IN_CHUNKS};
+use crate::map::grid::TileGrid;
+use crate::map::types::{Coordinate, Point};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const MIN_ROOMS: usize = 2;
+const MAX_ROOMS: usize = 4;
+const ROOM_PLACEMENT_ATTEMPTS: usize = 30;
+const MIN_ROOM_SIZE: Coordinate = 5;
+const MAX_ROOM_SIZE: Coordinate = 12;
+const ROOM_MARGIN: Coordinate = 2; // keeps rooms apart and off the chunk border
+const GATE_MARGIN: Coordinate = 8; // gates stay away from the chunk corners
+const CHUNKS_PER_DEPTH: Coordinate = 2; // monsters get tougher further from spawn
+
+// Salts so the different things we roll for a chunk don't share a seed
+const ROOMS_SALT: u64 = 1;
+const EAST_GATE_SALT: u64 = 2;
+const SOUTH_GATE_SALT: u64 = 3;
+
+// Generates chunks of the infinite world. Everything is derived from the world
+// seed and the chunk's coordinates, so a chunk comes out the same every time.
+// Neighbouring chunks agree on a gate in their shared border and both dig a
+// corridor to it, which is what stitches the world together.
+#[derive(Clone, Copy)]
+pub struct ChunkGenerator {
+    seed: u64,
+}
+
+impl ChunkGenerator {
+    pub fn new(seed: u64) -> Self {
+        ChunkGenerator { seed }
+    }
+
+    // The player starts in the middle of the world, in the first room of that chunk
+    pub fn spawn_chunk() -> ChunkCoord {
+        ChunkCoord::new(WORLD_SIZE_IN_CHUNKS / 2, WORLD_SIZE_IN_CHUNKS / 2)
+    }
+
+    pub fn spawn_point(&self) -> Point {
+        let coord = Self::spawn_chunk();
+        let (_, rooms) = self.generate(coord);
+        let center = rooms[0].center();
+        coord.to_world(center)
+    }
+
+    // How dangerous the chunk is, grows with the distance from the spawn chunk
+    pub fn depth(coord: ChunkCoord) -> u32 {
+        let spawn = Self::spawn_chunk();
+        let distance = coord.x.abs_diff(spawn.x).max(coord.y.abs_diff(spawn.y));
+        1 + (distance / CHUNKS_PER_DEPTH) as u32
+    }
+
+    // Tiles of the chunk in chunk-local coordinates and the rooms carved into it
+    pub fn generate(&self, coord: ChunkCoord) -> (TileGrid, Vec<Room>) {
+        let mut rng = StdRng::seed_from_u64(self.chunk_seed(coord, ROOMS_SALT));
+        let mut tiles = TileGrid::new(CHUNK_SIZE, CHUNK_SIZE);
+
+        let rooms = place_rooms(&mut rng);
+        for room in &rooms {
+            room.carve(&mut tiles);
+        }
+
+        // Chain the rooms together
+        for pair in rooms.windows(2) {
+            carve_l_corridor(&mut tiles, pair[0].center(), pair[1].center(), rng.gen());
+        }
+
+        // Then run a corridor from the closest room to every gate
+        for gate in self.gates(coord) {
+            let distance = |room: &&Room| {
+                let center = room.center();
+                center.x.abs_diff(gate.x) + center.y.abs_diff(gate.y)
+            };
+            // Reach the border head on rather than running along it
+            let horizontal_first = gate.y == 0 || gate.y == CHUNK_SIZE - 1;
+            if let Some(room) = rooms.iter().min_by_key(distance) {
+                carve_l_corridor(&mut tiles, room.center(), gate, horizontal_first);
+            }
+        }
+
+        let is_spawn_chunk = coord == Self::spawn_chunk();
+        let depth = Self::depth(coord);
+        for (index, room) in rooms.iter().enumerate() {
+            // Keep the spawn room quiet
+            if is_spawn_chunk && index == 0 {
+                continue;
+            }
+            populate_room(&mut tiles, room, depth, &mut rng);
+        }
+
+        (tiles, rooms)
+    }
+
+    // Points on the chunk border where corridors cross into the neighbours
+    fn gates(&self, coord: ChunkCoord) -> Vec<Point> {
+        let last = CHUNK_SIZE - 1;
+        let mut gates = Vec::with_capacity(4);
+
+        if coord.x + 1 < WORLD_SIZE_IN_CHUNKS {
+            gates.push(Point::new(last, self.gate_offset(coord, EAST_GATE_SALT)));
+        }
+        if coord.x > 0 {
+            let west = ChunkCoord::new(coord.x - 1, coord.y);
+            gates.push(Point::new(0, self.gate_offset(west, EAST_GATE_SALT)));
+        }
+        if coord.y + 1 < WORLD_SIZE_IN_CHUNKS {
+            gates.push(Point::new(self.gate_offset(coord, SOUTH_GATE_SALT), last));
+        }
+        if coord.y > 0 {
+            let north = ChunkCoord::new(coord.x, coord.y - 1);
+            gates.push(Point::new(self.gate_offset(north, SOUTH_GATE_SALT), 0));
+        }
+
+        gates
+    }
+
+    // Where along the east or south border of `coord` the gate sits
+    fn gate_offset(&self, coord: ChunkCoord, salt: u64) -> Coordinate {
+        let range = (CHUNK_SIZE - GATE_MARGIN * 2) as u64;
+        GATE_MARGIN + (self.chunk_seed(coord, salt) % range) as Coordinate
+    }
+
+    fn chunk_seed(&self, coord: ChunkCoord, salt: u64) -> u64 {
+        let mut value = self.seed;
+        for part in [coord.x as u64, coord.y as u64, salt] {
+            value = splitmix64(value ^ part);
+        }
+        value
+    }
+}
+
+// A well mixed hash so neighbouring chunks don't get similar seeds
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+fn place_rooms(rng: &mut StdRng) -> Vec<Room> {
+    let target = rng.gen_range(MIN_ROOMS..=MAX_ROOMS);
+    let mut rooms: Vec<Room> = Vec::with_capacity(target);
+
+    for _ in 0..ROOM_PLACEMENT_ATTEMPTS {
+        if rooms.len() >= target {
+            break;
+        }
+        let width = rng.gen_range(MIN_ROOM_SIZE..=MAX_ROOM_SIZE);
+        let height = rng.gen_range(MIN_ROOM_SIZE..=MAX_ROOM_SIZE);
+        let x = rng.gen_range(ROOM_MARGIN..CHUNK_SIZE - width - ROOM_MARGIN);
+        let y = rng.gen_range(ROOM_MARGIN..CHUNK_SIZE - height - ROOM_MARGIN);
+        let room = Room::new(Point::new(x, y), width, height);
+
+        if !rooms.iter().any(|other| overlaps(&room, other)) {
+            rooms.push(room);
+        }
+    }
+
+    // Every chunk needs at least one room for its corridors to meet in
+    if rooms.is_empty() {
+        let offset = (CHUNK_SIZE - MIN_ROOM_SIZE) / 2;
+        rooms.push(Room::new(
+            Point::new(offset, offset),
+            MIN_ROOM_SIZE,
+            MIN_ROOM_SIZE,
+        ));
+    }
+
+    rooms
+}
+
+fn overlaps(a: &Room, b: &Room) -> bool {
+    a.location.x < b.location.x + b.width + ROOM_MARGIN
+        && b.location.x < a.location.x + a.width + ROOM_MARGIN
+        && a.location.y < b.location.y + b.height + ROOM_MARGIN
+        && b.location.y < a.location.y + a.height + ROOM_MARGIN
+}
+
+fn carve_l_corridor(tiles: &mut TileGrid, from: Point, to: Point, horizontal_first: bool) {
+    let corner = if horizontal_first {
+        Point::new(to.x, from.y)
+    } else {
+        Point::new(from.x, to.y)
+    };
+
+    for (start, end) in [(from, corner), (corner, to)] {
+        for y in start.y.min(end.y)..=start.y.max(end.y) {
+            for x in start.x.min(end.x)..=start.x.max(end.x) {
+                carve_corridor(tiles, x, y);
+            }
+        }
+    }
+}
+
+// Chunk rooms are plain rooms, filled from the spawn tables with the chunk's own rng
+fn populate_room(tiles: &mut TileGrid, room: &Room, depth: u32, rng: &mut StdRng) {
+    if let Some(table) = spawns().for_room(room.room_type, depth) {
+        let rolled = table.roll(rng);
+        room.place_spawns(tiles, &rolled, depth, rng);
+    }
+}
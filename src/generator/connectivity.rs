@@ -0,0 +1,203 @@
+use crate::generator::room::{Room, RoomShape};
+use crate::map::grid::TileGrid;
+use crate::map::pathfinding::{astar, DijkstraMap};
+use crate::map::types::Point;
+use crate::tile::Tile;
+use std::collections::HashSet;
+
+// Outcome of checking a generated dungeon for reachability
+#[derive(Clone, Debug, Default)]
+pub struct ConnectivityReport {
+    pub rooms: usize,
+    pub unreachable_rooms: Vec<usize>, // indices into the generator's rooms
+    pub unreachable_cells: usize,      // walkable cells that can't be reached from spawn
+    pub dead_ends: usize,              // reachable floor cells with a single way out
+    pub invalid_doors: Vec<Point>,     // doors that don't sit in a one tile wall gap
+    pub repaired_rooms: usize,
+    pub removed_doors: usize,
+    pub attempts: usize, // how many times the dungeon had to be generated
+    pub gave_up: bool,   // still broken after the last attempt, the level can't be finished
+}
+
+impl ConnectivityReport {
+    pub fn is_valid(&self) -> bool {
+        self.unreachable_rooms.is_empty() && self.invalid_doors.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "Rooms {} | Unreachable rooms {} cells {} | Dead ends {} | Repaired {} | Doors removed {} | Attempts {}{}",
+            self.rooms,
+            self.unreachable_rooms.len(),
+            self.unreachable_cells,
+            self.dead_ends,
+            self.repaired_rooms,
+            self.removed_doors,
+            self.attempts,
+            if self.gave_up { " | Gave up" } else { "" }
+        )
+    }
+}
+
+// What the player can eventually walk over. Doors can be opened and monsters
+// can be dealt with, but pits, obelisks and columns are in the way for good.
+pub fn reachability_cost(_: Point, tile: Tile) -> Option<u32> {
+    match tile {
+        Tile::Pit { .. } | Tile::Empty => None,
+        Tile::Door { .. } => Some(1),
+        tile if tile.is_monster() => Some(1),
+        tile if tile.is_walkable() => Some(1),
+        _ => None,
+    }
+}
+
+// Repair corridors prefer existing floor, then empty space and only cut
+// through walls when they have to. Room features are never carved away.
+fn carving_cost(point: Point, tile: Tile, width: usize, height: usize) -> Option<u32> {
+    if point.x == 0 || point.y == 0 || point.x >= width - 1 || point.y >= height - 1 {
+        return None;
+    }
+    match tile {
+        Tile::Empty => Some(3),
+        Tile::Wall { .. } => Some(6),
+        tile => reachability_cost(point, tile),
+    }
+}
+
+pub fn validate(tiles: &TileGrid, rooms: &[Room], spawn_room: usize) -> ConnectivityReport {
+    let spawn = rooms[spawn_room].center();
+    let reachable = DijkstraMap::new(tiles, &[spawn], reachability_cost);
+
+    let height = tiles.height();
+    let width = tiles.width();
+
+    let mut report = ConnectivityReport {
+        rooms: rooms.len(),
+        ..Default::default()
+    };
+
+    for (index, room) in rooms.iter().enumerate() {
+        if !room
+            .interior_points()
+            .any(|point| reachable.get(point).is_some())
+        {
+            report.unreachable_rooms.push(index);
+        }
+    }
+
+    let is_passable = |point: Point| {
+        tiles
+            .get(point)
+            .is_some_and(|tile| reachability_cost(point, tile).is_some())
+    };
+
+    for point in tiles.points() {
+        let Some(tile) = tiles.get(point) else {
+            continue;
+        };
+
+        if is_passable(point) && reachable.get(point).is_none() {
+            report.unreachable_cells += 1;
+        }
+
+        if matches!(tile, Tile::Floor { .. }) && reachable.get(point).is_some() {
+            let exits = neighbours(point, width, height)
+                .filter(|&n| is_passable(n))
+                .count();
+            if exits == 1 {
+                report.dead_ends += 1;
+            }
+        }
+
+        if matches!(tile, Tile::Door { .. }) && !is_valid_door(tiles, point) {
+            report.invalid_doors.push(point);
+        }
+    }
+
+    report
+}
+
+// Cells the player could walk on but never get to from `start`
+pub fn unreachable_points(tiles: &TileGrid, start: Point) -> Vec<Point> {
+    let reachable = DijkstraMap::new(tiles, &[start], reachability_cost);
+    tiles
+        .points()
+        .filter(|&point| {
+            tiles
+                .get(point)
+                .is_some_and(|tile| reachability_cost(point, tile).is_some())
+                && reachable.get(point).is_none()
+        })
+        .collect()
+}
+
+// A door must plug a one tile gap: walls on two opposite sides, open space on the others
+fn is_valid_door(tiles: &TileGrid, point: Point) -> bool {
+    let height = tiles.height();
+    let width = tiles.width();
+    if point.x == 0 || point.y == 0 || point.x >= width - 1 || point.y >= height - 1 {
+        return false;
+    }
+
+    let is_wall = |x, y| matches!(tiles.get(Point::new(x, y)), Some(Tile::Wall { .. }));
+    let is_open = |x, y| {
+        !matches!(
+            tiles.get(Point::new(x, y)),
+            None | Some(Tile::Wall { .. } | Tile::Empty)
+        )
+    };
+    let (x, y) = (point.x, point.y);
+
+    let horizontal_gap =
+        is_wall(x - 1, y) && is_wall(x + 1, y) && is_open(x, y - 1) && is_open(x, y + 1);
+    let vertical_gap =
+        is_wall(x, y - 1) && is_wall(x, y + 1) && is_open(x - 1, y) && is_open(x + 1, y);
+
+    horizontal_gap || vertical_gap
+}
+
+// Cheapest corridor to dig from inside `rooms[index]` towards `target`. The
+// walls of prefab rooms are left alone, a way in has to go through their doors.
+pub fn find_repair_path(
+    tiles: &TileGrid,
+    rooms: &[Room],
+    index: usize,
+    target: Point,
+) -> Option<Vec<Point>> {
+    let (width, height) = (tiles.width(), tiles.height());
+    let room = &rooms[index];
+
+    let protected: HashSet<Point> = rooms
+        .iter()
+        .filter(|room| matches!(room.shape, RoomShape::Prefab { .. }))
+        .flat_map(|room| room.footprint())
+        .filter(|&point| matches!(tiles.get(point), Some(Tile::Wall { .. })))
+        .collect();
+
+    let start = room.interior_points().find(|&point| {
+        tiles
+            .get(point)
+            .is_some_and(|tile| reachability_cost(point, tile).is_some())
+    })?;
+
+    astar(tiles, start, target, |point, tile| {
+        if protected.contains(&point) {
+            return None;
+        }
+        carving_cost(point, tile, width, height)
+    })
+}
+
+pub(super) fn neighbours(point: Point, width: usize, height: usize) -> impl Iterator<Item = Point> {
+    [(0, -1), (-1, 0), (1, 0), (0, 1)]
+        .into_iter()
+        .filter_map(move |(dx, dy): (i32, i32)| {
+            let x = point.x as i32 + dx;
+            let y = point.y as i32 + dy;
+            if (0..width as i32).contains(&x) && (0..height as i32).contains(&y) {
+                Some(Point::new(x as usize, y as usize))
+            } else {
+                None
+            }
+        })
+}
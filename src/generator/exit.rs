@@ -0,0 +1,51 @@
+use crate::generator::locks;
+use crate::generator::room::{self, Room, RoomType, ALL_OFFSETS};
+use crate::map::grid::TileGrid;
+use crate::map::pathfinding::DijkstraMap;
+use crate::map::types::Point;
+use crate::tile::{Lock, Tile};
+
+// Open floor all around, so the archway never blocks the way through a room
+fn in_the_open(tiles: &TileGrid, point: Point) -> bool {
+    std::iter::once(point)
+        .chain(room::around(point, &ALL_OFFSETS))
+        .all(|point| matches!(tiles.get(point), Some(Tile::Floor { cursed: false, .. })))
+}
+
+// Puts the sealed archway leading deeper into the boss room, or failing that
+// the room furthest from spawn. Returns where it went.
+pub fn place_exit(tiles: &mut TileGrid, rooms: &[Room], spawn_room: usize) -> Option<Point> {
+    let spawn = rooms[spawn_room].center();
+    let distances = DijkstraMap::new(tiles, &[spawn], locks::unlocked_cost);
+
+    let mut candidates: Vec<(bool, u32, &Room)> = rooms
+        .iter()
+        .enumerate()
+        .filter(|&(index, room)| index != spawn_room && room.room_type != RoomType::Vault)
+        .filter_map(|(_, room)| {
+            let distance = room
+                .interior_points()
+                .filter_map(|point| distances.get(point))
+                .min()?;
+            Some((room.room_type == RoomType::Boss, distance, room))
+        })
+        .collect();
+    candidates.sort_by_key(|&(is_boss, distance, _)| std::cmp::Reverse((is_boss, distance)));
+
+    // As close to the middle of the room as there's space for
+    let exit = candidates.into_iter().find_map(|(_, _, room)| {
+        let center = room.center();
+        room.interior_points()
+            .filter(|&point| distances.get(point).is_some() && in_the_open(tiles, point))
+            .min_by_key(|point| {
+                point.x.abs_diff(center.x).pow(2) + point.y.abs_diff(center.y).pow(2)
+            })
+    })?;
+    tiles.set(
+        exit,
+        Tile::Archway {
+            lock: Some(Lock::Sealed),
+        },
+    );
+    Some(exit)
+}
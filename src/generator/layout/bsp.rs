@@ -0,0 +1,417 @@
+use crate::generator::layout::{carve_corridor, Carving, Layout};
+use crate::generator::prefab::{Prefab, PrefabLibrary};
+use crate::generator::room::{Room, RoomShape};
+use crate::map::grid::TileGrid;
+use crate::map::types::{Coordinate, Point};
+use crate::tile::Tile;
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::sync::Arc;
+
+const TURN_PROBABILITY: f64 = 0.2; // 20% chance to turn at each step
+const MAX_DEPTH: usize = 5; // Adjust this value to control the depth of the tree
+pub(crate) const LEAF_PADDING: Coordinate = 2; // Padding between the room and the partition edges
+const PREFAB_PROBABILITY: f64 = 0.15; // chance for a big enough leaf to get a prefab room
+
+#[derive(Clone)]
+pub struct BSPNode {
+    pub x: Coordinate,
+    pub y: Coordinate,
+    pub width: Coordinate,
+    pub height: Coordinate,
+    pub left: Option<Box<BSPNode>>,
+    pub right: Option<Box<BSPNode>>,
+    pub room: Option<Room>,
+}
+
+impl BSPNode {
+    fn new(x: Coordinate, y: Coordinate, width: Coordinate, height: Coordinate) -> Self {
+        BSPNode {
+            x,
+            y,
+            width,
+            height,
+            left: None,
+            right: None,
+            room: None,
+        }
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.left.is_none() && self.right.is_none()
+    }
+
+    pub fn for_each_leaf_mut<F: FnMut(&mut BSPNode)>(&mut self, f: &mut F) {
+        if self.is_leaf() {
+            f(self);
+            return;
+        }
+        if let Some(ref mut left) = self.left {
+            left.for_each_leaf_mut(f);
+        }
+        if let Some(ref mut right) = self.right {
+            right.for_each_leaf_mut(f);
+        }
+    }
+
+    // The partitions that weren't split any further, together they cover the map
+    pub fn leaves(&self) -> Vec<&BSPNode> {
+        if self.is_leaf() {
+            return vec![self];
+        }
+        let mut leaves = Vec::new();
+        for child in [&self.left, &self.right].into_iter().flatten() {
+            leaves.extend(child.leaves());
+        }
+        leaves
+    }
+
+    pub fn collect_rooms(&self, rooms: &mut Vec<Room>) {
+        if let Some(ref room) = self.room {
+            rooms.push(room.clone());
+        }
+        if let Some(ref left) = self.left {
+            left.collect_rooms(rooms);
+        }
+        if let Some(ref right) = self.right {
+            right.collect_rooms(rooms);
+        }
+    }
+}
+
+// Rectangular rooms in the leaves of a BSP tree, joined by drunken-walk corridors.
+// Now and then a leaf gets a hand made prefab room instead.
+pub struct BspLayout {
+    prefabs: Arc<PrefabLibrary>,
+}
+
+impl BspLayout {
+    pub fn new(prefabs: Arc<PrefabLibrary>) -> Self {
+        BspLayout { prefabs }
+    }
+}
+
+impl Layout for BspLayout {
+    fn carve(
+        &self,
+        tiles: &mut TileGrid,
+        min_room_size: Coordinate,
+        max_room_size: Coordinate,
+        rng: &mut StdRng,
+    ) -> Carving {
+        let height = tiles.height();
+        let width = tiles.width();
+
+        let mut root = build_bsp_tree(width, height, min_room_size, rng);
+        let mut prefabs = Vec::new();
+
+        // Create rooms in the leaf nodes
+        root.for_each_leaf_mut(&mut |leaf| {
+            if !self.prefabs.is_empty() && rng.gen_bool(PREFAB_PROBABILITY) {
+                if let Some((room, prefab)) = place_prefab_in_leaf(leaf, &self.prefabs, rng) {
+                    prefab.stamp(tiles, room.location, rng);
+                    prefabs.push((room.location, prefab));
+                    leaf.room = Some(room);
+                    return;
+                }
+            }
+            if let Some(room) = place_room_in_leaf(leaf, min_room_size, max_room_size, rng) {
+                room.carve(tiles);
+                leaf.room = Some(room);
+            }
+        });
+
+        // Connect rooms via depth-first traversal
+        let mut corridors = Vec::new();
+        connect_rooms_bsp(tiles, &root, &mut corridors, rng);
+
+        // Corridors may have cut through a prefab on their way past, stamp it
+        // again and brick up the doors no corridor ended up at
+        for (location, prefab) in &prefabs {
+            prefab.stamp(tiles, *location, rng);
+        }
+        root.for_each_leaf_mut(&mut |leaf| {
+            if let Some(room) = &leaf.room {
+                wall_up_unused_doors(tiles, room);
+            }
+        });
+
+        let mut rooms = Vec::new();
+        root.collect_rooms(&mut rooms);
+        Carving {
+            rooms,
+            bsp: Some(root),
+            corridors,
+        }
+    }
+}
+
+pub fn build_bsp_tree<R: Rng>(
+    width: Coordinate,
+    height: Coordinate,
+    min_size: Coordinate,
+    rng: &mut R,
+) -> BSPNode {
+    let mut root = BSPNode::new(0, 0, width, height);
+    split_node(&mut root, min_size, MAX_DEPTH, 0, rng);
+    root
+}
+
+fn split_node<R: Rng>(
+    node: &mut BSPNode,
+    min_size: Coordinate,
+    max_depth: usize,
+    current_depth: usize,
+    rng: &mut R,
+) {
+    if current_depth >= max_depth {
+        return;
+    }
+
+    let can_split_horizontally = node.width >= min_size * 2;
+    let can_split_vertically = node.height >= min_size * 2;
+
+    if !can_split_horizontally && !can_split_vertically {
+        return;
+    }
+
+    let split_vertically = if can_split_horizontally && can_split_vertically {
+        rng.gen::<bool>()
+    } else {
+        can_split_horizontally
+    };
+
+    if split_vertically {
+        // Split vertically
+        let split = rng.gen_range(min_size..(node.width - min_size + 1));
+        node.left = Some(Box::new(BSPNode::new(node.x, node.y, split, node.height)));
+        node.right = Some(Box::new(BSPNode::new(
+            node.x + split,
+            node.y,
+            node.width - split,
+            node.height,
+        )));
+    } else {
+        // Split horizontally
+        let split = rng.gen_range(min_size..(node.height - min_size + 1));
+        node.left = Some(Box::new(BSPNode::new(node.x, node.y, node.width, split)));
+        node.right = Some(Box::new(BSPNode::new(
+            node.x,
+            node.y + split,
+            node.width,
+            node.height - split,
+        )));
+    }
+
+    if let Some(ref mut left) = node.left {
+        split_node(left, min_size, max_depth, current_depth + 1, rng);
+    }
+    if let Some(ref mut right) = node.right {
+        split_node(right, min_size, max_depth, current_depth + 1, rng);
+    }
+}
+
+// Pick a random room rectangle that fits inside the leaf, if the leaf is big enough
+pub fn place_room_in_leaf<R: Rng>(
+    node: &BSPNode,
+    min_room_size: Coordinate,
+    max_room_size: Coordinate,
+    rng: &mut R,
+) -> Option<Room> {
+    let padding = LEAF_PADDING;
+
+    if node.width < padding * 2 || node.height < padding * 2 {
+        return None;
+    }
+
+    let max_room_width = (node.width - padding * 2).min(max_room_size);
+    let max_room_height = (node.height - padding * 2).min(max_room_size);
+
+    if max_room_width < min_room_size || max_room_height < min_room_size {
+        // If the partition is too small, skip room creation
+        return None;
+    }
+
+    let room_width = rng.gen_range(min_room_size..=max_room_width);
+    let room_height = rng.gen_range(min_room_size..=max_room_height);
+
+    let x_range = node.x + padding..=node.x + node.width - room_width - padding;
+    let y_range = node.y + padding..=node.y + node.height - room_height - padding;
+
+    let room_x = if x_range.is_empty() {
+        node.x + padding
+    } else {
+        rng.gen_range(x_range)
+    };
+
+    let room_y = if y_range.is_empty() {
+        node.y + padding
+    } else {
+        rng.gen_range(y_range)
+    };
+
+    Some(Room::new(
+        Point::new(room_x, room_y),
+        room_width,
+        room_height,
+    ))
+}
+
+// Stamp a random prefab in the middle of the leaf if one fits with padding to spare
+fn place_prefab_in_leaf<R: Rng>(
+    node: &BSPNode,
+    prefabs: &PrefabLibrary,
+    rng: &mut R,
+) -> Option<(Room, Prefab)> {
+    let padding = LEAF_PADDING;
+    if node.width < padding * 2 || node.height < padding * 2 {
+        return None;
+    }
+
+    let prefab =
+        prefabs.random_fitting(node.width - padding * 2, node.height - padding * 2, rng)?;
+    let location = Point::new(
+        node.x + (node.width - prefab.width()) / 2,
+        node.y + (node.height - prefab.height()) / 2,
+    );
+    Some((Room::new_prefab(location, &prefab), prefab))
+}
+
+// Where a corridor to this room should end. Prefabs are entered through the
+// door marker closest to where the corridor comes from.
+fn entrance_towards(room: &Room, from: Point) -> Point {
+    let RoomShape::Prefab { doors, .. } = &room.shape else {
+        return room.center();
+    };
+
+    let distance = |point: Point| point.x.abs_diff(from.x) + point.y.abs_diff(from.y);
+    doors
+        .iter()
+        .map(|&door| outside_of_door(room, door))
+        .min_by_key(|&point| distance(point))
+        .unwrap_or_else(|| room.center())
+}
+
+fn outside_of_door(room: &Room, door: Point) -> Point {
+    if door.x == room.location.x {
+        Point::new(door.x - 1, door.y)
+    } else if door.x == room.location.x + room.width {
+        Point::new(door.x + 1, door.y)
+    } else if door.y == room.location.y {
+        Point::new(door.x, door.y - 1)
+    } else {
+        Point::new(door.x, door.y + 1)
+    }
+}
+
+fn wall_up_unused_doors(tiles: &mut TileGrid, room: &Room) {
+    let RoomShape::Prefab { doors, .. } = &room.shape else {
+        return;
+    };
+
+    for &door in doors {
+        let outside = outside_of_door(room, door);
+        if matches!(
+            tiles.get(outside),
+            None | Some(Tile::Empty | Tile::Wall { .. })
+        ) {
+            tiles.set(door, Tile::Wall { visible: false });
+        }
+    }
+}
+
+// Joins the rooms of sibling subtrees, adding the path of every corridor it
+// digs to `corridors`
+pub fn connect_rooms_bsp<R: Rng>(
+    tiles: &mut TileGrid,
+    node: &BSPNode,
+    corridors: &mut Vec<Vec<Point>>,
+    rng: &mut R,
+) {
+    if !node.is_leaf() {
+        if let (Some(left), Some(right)) = (node.left.as_ref(), node.right.as_ref()) {
+            connect_rooms_bsp(tiles, left, corridors, rng);
+            connect_rooms_bsp(tiles, right, corridors, rng);
+
+            let left_room = get_room_in_subtree(left);
+            let right_room = get_room_in_subtree(right);
+            if let (Some(lr), Some(rr)) = (left_room, right_room) {
+                let start = entrance_towards(lr, rr.center());
+                let end = entrance_towards(rr, start);
+                corridors.push(drunken_walk_corridor(tiles, start, end, rng));
+            }
+        }
+    }
+}
+
+fn get_room_in_subtree(node: &BSPNode) -> Option<&Room> {
+    if let Some(ref room) = node.room {
+        Some(room)
+    } else {
+        node.left
+            .as_ref()
+            .and_then(|left| get_room_in_subtree(left))
+            .or_else(|| {
+                node.right
+                    .as_ref()
+                    .and_then(|right| get_room_in_subtree(right))
+            })
+    }
+}
+
+// Digs from `start` to `end` and returns every tile it dug, in order
+pub fn drunken_walk_corridor<R: Rng>(
+    tiles: &mut TileGrid,
+    start: Point,
+    end: Point,
+    rng: &mut R,
+) -> Vec<Point> {
+    let height = tiles.height();
+    let width = tiles.width();
+    let mut current = start;
+    let mut path = Vec::new();
+
+    // Randomly choose the initial direction
+    let mut direction = if rng.gen_bool(0.5) { 'x' } else { 'y' };
+
+    while current != end {
+        carve_corridor(tiles, current.x, current.y);
+        path.push(current);
+
+        let dx = end.x as isize - current.x as isize;
+        let dy = end.y as isize - current.y as isize;
+
+        // Check if we need to change direction
+        let at_end_in_direction = match direction {
+            'x' => dx == 0,
+            'y' => dy == 0,
+            _ => false,
+        };
+
+        // Decide whether to turn based on TURN_PROBABILITY
+        if at_end_in_direction || rng.gen_bool(TURN_PROBABILITY) {
+            // Change direction
+            direction = if direction == 'x' { 'y' } else { 'x' };
+        }
+
+        // Move in the current direction
+        match direction {
+            'x' if dx != 0 => {
+                current.x = (current.x as isize + dx.signum())
+                    .clamp(0, width.saturating_sub(1) as isize)
+                    as Coordinate;
+            }
+            'y' if dy != 0 => {
+                current.y = (current.y as isize + dy.signum())
+                    .clamp(0, height.saturating_sub(1) as isize)
+                    as Coordinate;
+            }
+            _ => {}
+        }
+    }
+
+    // The end is usually inside a room already, but not when it's outside a prefab door
+    carve_corridor(tiles, end.x, end.y);
+    path.push(end);
+    path
+}
@@ -0,0 +1,184 @@
+use crate::generator::layout::{write_open_grid, Carving, Layout};
+use crate::generator::room::Room;
+use crate::map::grid::TileGrid;
+use crate::map::types::{Coordinate, Point};
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::collections::VecDeque;
+
+const INITIAL_OPEN_PROBABILITY: f64 = 0.55; // chance for a cell to start out as open space
+const SMOOTHING_STEPS: usize = 5;
+const MIN_CAVE_AREA: usize = 30; // pockets smaller than this get filled back in
+const MIN_REGION_OPEN_RATIO: f64 = 0.3; // how much of a region has to be cave to count
+
+// Cellular automata caverns. The map is split into a grid of regions the size
+// of the largest room and every region with enough cave in it becomes a room
+// with a small cleared chamber in the middle.
+pub struct CaveLayout;
+
+impl Layout for CaveLayout {
+    fn carve(
+        &self,
+        tiles: &mut TileGrid,
+        _min_room_size: Coordinate,
+        max_room_size: Coordinate,
+        rng: &mut StdRng,
+    ) -> Carving {
+        let height = tiles.height();
+        let width = tiles.width();
+
+        let mut open = cave_grid(width, height, rng);
+        let mut rooms = Vec::new();
+        let region_size = max_room_size.max(3);
+
+        for region_y in (0..height).step_by(region_size) {
+            for region_x in (0..width).step_by(region_size) {
+                let region_width = region_size.min(width - region_x);
+                let region_height = region_size.min(height - region_y);
+                if region_width < 3 || region_height < 3 {
+                    continue;
+                }
+
+                let open_cells = (region_y..region_y + region_height)
+                    .flat_map(|y| (region_x..region_x + region_width).map(move |x| (x, y)))
+                    .filter(|&(x, y)| open[y][x])
+                    .count();
+                let ratio = open_cells as f64 / (region_width * region_height) as f64;
+                if ratio < MIN_REGION_OPEN_RATIO {
+                    continue;
+                }
+
+                // The room's far walls sit on the last row and column of the region
+                let room = Room::new_cave(
+                    Point::new(region_x, region_y),
+                    region_width - 1,
+                    region_height - 1,
+                );
+                clear_chamber(&mut open, room.center());
+                rooms.push(room);
+            }
+        }
+
+        write_open_grid(tiles, Point::new(0, 0), &open);
+        Carving::rooms(rooms)
+    }
+}
+
+// Run the automaton over a `width` x `height` area, `true` marks open space.
+// The outer ring always stays closed so caves never touch the edge.
+pub fn cave_grid<R: Rng>(width: usize, height: usize, rng: &mut R) -> Vec<Vec<bool>> {
+    let is_border = |x: usize, y: usize| x == 0 || y == 0 || x + 1 >= width || y + 1 >= height;
+
+    let mut open: Vec<Vec<bool>> = (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| !is_border(x, y) && rng.gen_bool(INITIAL_OPEN_PROBABILITY))
+                .collect()
+        })
+        .collect();
+
+    for _ in 0..SMOOTHING_STEPS {
+        let mut next = open.clone();
+        for (y, row) in next.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                if is_border(x, y) {
+                    continue;
+                }
+                // Classic 4-5 rule: rock when most of the 3x3 block around the cell is rock
+                let closed_self = usize::from(!open[y][x]);
+                *cell = count_closed_neighbours(&open, x, y) + closed_self < 5;
+            }
+        }
+        open = next;
+    }
+
+    fill_small_pockets(&mut open);
+    open
+}
+
+fn count_closed_neighbours(open: &[Vec<bool>], x: usize, y: usize) -> usize {
+    let mut count = 0;
+    for dy in -1..=1_i32 {
+        for dx in -1..=1_i32 {
+            if dx == 0 && dy == 0 {
+                continue;
+            }
+            let nx = x as i32 + dx;
+            let ny = y as i32 + dy;
+            let is_open = nx >= 0
+                && ny >= 0
+                && open
+                    .get(ny as usize)
+                    .and_then(|row| row.get(nx as usize))
+                    .copied()
+                    .unwrap_or(false);
+            if !is_open {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+// Flood fill every pocket of open space and close the ones too small to matter
+fn fill_small_pockets(open: &mut [Vec<bool>]) {
+    let height = open.len();
+    let width = open.first().map_or(0, |row| row.len());
+    let mut seen = vec![vec![false; width]; height];
+
+    for start_y in 0..height {
+        for start_x in 0..width {
+            if !open[start_y][start_x] || seen[start_y][start_x] {
+                continue;
+            }
+
+            let mut pocket = Vec::new();
+            let mut queue = VecDeque::from([(start_x, start_y)]);
+            seen[start_y][start_x] = true;
+
+            while let Some((x, y)) = queue.pop_front() {
+                pocket.push((x, y));
+                for (dx, dy) in [(0, -1), (-1, 0), (1, 0), (0, 1)] {
+                    let nx = x as i32 + dx;
+                    let ny = y as i32 + dy;
+                    if nx < 0 || ny < 0 || nx as usize >= width || ny as usize >= height {
+                        continue;
+                    }
+                    let (nx, ny) = (nx as usize, ny as usize);
+                    if open[ny][nx] && !seen[ny][nx] {
+                        seen[ny][nx] = true;
+                        queue.push_back((nx, ny));
+                    }
+                }
+            }
+
+            if pocket.len() < MIN_CAVE_AREA {
+                for (x, y) in pocket {
+                    open[y][x] = false;
+                }
+            }
+        }
+    }
+}
+
+// Open a 3x3 chamber so that a region's center is always standing room
+pub fn clear_chamber(open: &mut [Vec<bool>], center: Point) {
+    let height = open.len();
+    let width = open.first().map_or(0, |row| row.len());
+
+    // Stay clear of the outer ring
+    let (min_x, max_x) = (
+        center.x.max(2) - 1,
+        (center.x + 1).min(width.saturating_sub(2)),
+    );
+    let (min_y, max_y) = (
+        center.y.max(2) - 1,
+        (center.y + 1).min(height.saturating_sub(2)),
+    );
+
+    for row in open.iter_mut().take(max_y + 1).skip(min_y) {
+        for cell in row.iter_mut().take(max_x + 1).skip(min_x) {
+            *cell = true;
+        }
+    }
+}
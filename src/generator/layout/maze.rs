@@ -0,0 +1,265 @@
+use crate::generator::layout::{write_open_grid, Carving, Layout};
+use crate::generator::room::Room;
+use crate::map::grid::TileGrid;
+use crate::map::types::{Coordinate, Point};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+const ROOM_PLACEMENT_ATTEMPTS: usize = 300;
+const WINDINESS: f64 = 0.6; // chance the maze keeps carving from the newest cell
+const EXTRA_CONNECTOR_PROBABILITY: f64 = 0.02; // loops make the maze less of a chore
+
+const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+// Rooms scattered over the map with the space between them filled by a
+// growing tree maze, then joined up and trimmed of dead ends.
+// Everything lives on odd coordinates so walls always fit between passages.
+pub struct MazeLayout;
+
+struct MazeGrid {
+    width: usize,
+    height: usize,
+    regions: Vec<Vec<Option<usize>>>, // which carved region a cell belongs to
+    current_region: usize,
+}
+
+impl MazeGrid {
+    fn new(width: usize, height: usize) -> Self {
+        MazeGrid {
+            width,
+            height,
+            regions: vec![vec![None; width]; height],
+            current_region: 0,
+        }
+    }
+
+    fn start_region(&mut self) {
+        self.current_region += 1;
+    }
+
+    fn carve(&mut self, x: usize, y: usize) {
+        self.regions[y][x] = Some(self.current_region);
+    }
+
+    fn is_open(&self, x: usize, y: usize) -> bool {
+        self.regions[y][x].is_some()
+    }
+
+    fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x > 0 && y > 0 && (x as usize) < self.width - 1 && (y as usize) < self.height - 1
+    }
+
+    fn open_grid(&self) -> Vec<Vec<bool>> {
+        self.regions
+            .iter()
+            .map(|row| row.iter().map(|region| region.is_some()).collect())
+            .collect()
+    }
+}
+
+impl Layout for MazeLayout {
+    fn carve(
+        &self,
+        tiles: &mut TileGrid,
+        min_room_size: Coordinate,
+        max_room_size: Coordinate,
+        rng: &mut StdRng,
+    ) -> Carving {
+        let height = tiles.height();
+        let width = tiles.width();
+        if width < 5 || height < 5 {
+            return Carving::rooms(Vec::new());
+        }
+
+        let mut grid = MazeGrid::new(width, height);
+        let rooms = place_rooms(&mut grid, min_room_size, max_room_size, rng);
+
+        // Fill every untouched odd cell with maze
+        for y in (1..height - 1).step_by(2) {
+            for x in (1..width - 1).step_by(2) {
+                if !grid.is_open(x, y) {
+                    grow_maze(&mut grid, x, y, rng);
+                }
+            }
+        }
+
+        connect_regions(&mut grid, rng);
+        remove_dead_ends(&mut grid, &rooms);
+
+        write_open_grid(tiles, Point::new(0, 0), &grid.open_grid());
+        Carving::rooms(rooms)
+    }
+}
+
+fn place_rooms<R: Rng>(
+    grid: &mut MazeGrid,
+    min_room_size: Coordinate,
+    max_room_size: Coordinate,
+    rng: &mut R,
+) -> Vec<Room> {
+    let mut rooms: Vec<Room> = Vec::new();
+
+    // Rooms are described by their interior here, odd sized and odd positioned
+    let min_size = (min_room_size | 1).max(3);
+    let max_size = (max_room_size | 1).max(min_size);
+    if grid.width < max_size + 4 || grid.height < max_size + 4 {
+        return rooms;
+    }
+
+    for _ in 0..ROOM_PLACEMENT_ATTEMPTS {
+        let room_width = rng.gen_range(min_size / 2..=max_size / 2) * 2 + 1;
+        let room_height = rng.gen_range(min_size / 2..=max_size / 2) * 2 + 1;
+        let x = rng.gen_range(0..(grid.width - room_width - 1) / 2) * 2 + 1;
+        let y = rng.gen_range(0..(grid.height - room_height - 1) / 2) * 2 + 1;
+
+        // Keep a wall's width between rooms
+        let overlaps = rooms.iter().any(|room| {
+            x <= room.location.x + room.width
+                && room.location.x <= x + room_width
+                && y <= room.location.y + room.height
+                && room.location.y <= y + room_height
+        });
+        if overlaps {
+            continue;
+        }
+
+        grid.start_region();
+        for cy in y..y + room_height {
+            for cx in x..x + room_width {
+                grid.carve(cx, cy);
+            }
+        }
+
+        // The room's walls sit one tile outside the carved interior
+        rooms.push(Room::new(
+            Point::new(x - 1, y - 1),
+            room_width + 1,
+            room_height + 1,
+        ));
+    }
+
+    rooms
+}
+
+// Growing tree algorithm, picking the newest cell most of the time gives long winding passages
+fn grow_maze<R: Rng>(grid: &mut MazeGrid, start_x: usize, start_y: usize, rng: &mut R) {
+    let mut cells = vec![(start_x, start_y)];
+
+    grid.start_region();
+    grid.carve(start_x, start_y);
+
+    while !cells.is_empty() {
+        let index = if rng.gen_bool(WINDINESS) {
+            cells.len() - 1
+        } else {
+            rng.gen_range(0..cells.len())
+        };
+        let (x, y) = cells[index];
+
+        let unmade: Vec<(i32, i32)> = DIRECTIONS
+            .iter()
+            .copied()
+            .filter(|&(dx, dy)| {
+                let nx = x as i32 + dx * 2;
+                let ny = y as i32 + dy * 2;
+                grid.in_bounds(nx, ny) && !grid.is_open(nx as usize, ny as usize)
+            })
+            .collect();
+
+        match unmade.choose(rng) {
+            Some(&(dx, dy)) => {
+                let between = ((x as i32 + dx) as usize, (y as i32 + dy) as usize);
+                let next = ((x as i32 + dx * 2) as usize, (y as i32 + dy * 2) as usize);
+                grid.carve(between.0, between.1);
+                grid.carve(next.0, next.1);
+                cells.push(next);
+            }
+            None => {
+                cells.remove(index);
+            }
+        }
+    }
+}
+
+// Open walls between different regions until everything is one connected piece
+fn connect_regions<R: Rng>(grid: &mut MazeGrid, rng: &mut R) {
+    // Union-find over region ids
+    let mut parent: Vec<usize> = (0..=grid.current_region).collect();
+    fn find(parent: &mut [usize], region: usize) -> usize {
+        let mut root = region;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        parent[region] = root;
+        root
+    }
+
+    let mut connectors = Vec::new();
+    for y in 1..grid.height - 1 {
+        for x in 1..grid.width - 1 {
+            if grid.is_open(x, y) {
+                continue;
+            }
+            // A connector separates two different regions on opposite sides
+            let horizontal = (grid.regions[y][x - 1], grid.regions[y][x + 1]);
+            let vertical = (grid.regions[y - 1][x], grid.regions[y + 1][x]);
+            for pair in [horizontal, vertical] {
+                if let (Some(a), Some(b)) = pair {
+                    if a != b {
+                        connectors.push((x, y, a, b));
+                    }
+                }
+            }
+        }
+    }
+    connectors.shuffle(rng);
+
+    for (x, y, a, b) in connectors {
+        let root_a = find(&mut parent, a);
+        let root_b = find(&mut parent, b);
+        if root_a != root_b {
+            parent[root_a] = root_b;
+            grid.regions[y][x] = Some(a);
+        } else if rng.gen_bool(EXTRA_CONNECTOR_PROBABILITY) && !grid.is_open(x, y) {
+            grid.regions[y][x] = Some(a);
+        }
+    }
+}
+
+// Fill in passages that lead nowhere, rooms are left untouched
+fn remove_dead_ends(grid: &mut MazeGrid, rooms: &[Room]) {
+    let mut in_room = vec![vec![false; grid.width]; grid.height];
+    for room in rooms {
+        for point in room.interior_points() {
+            in_room[point.y][point.x] = true;
+        }
+    }
+
+    let is_dead_end = |grid: &MazeGrid, x: usize, y: usize| {
+        let exits = DIRECTIONS
+            .iter()
+            .filter(|(dx, dy)| grid.is_open((x as i32 + dx) as usize, (y as i32 + dy) as usize))
+            .count();
+        grid.is_open(x, y) && !in_room[y][x] && exits <= 1
+    };
+
+    let mut candidates: Vec<(usize, usize)> = (1..grid.height - 1)
+        .flat_map(|y| (1..grid.width - 1).map(move |x| (x, y)))
+        .collect();
+
+    // Filling a dead end can turn the passage behind it into one, so keep following it
+    while let Some((x, y)) = candidates.pop() {
+        if !is_dead_end(grid, x, y) {
+            continue;
+        }
+        grid.regions[y][x] = None;
+        for (dx, dy) in DIRECTIONS {
+            let nx = (x as i32 + dx) as usize;
+            let ny = (y as i32 + dy) as usize;
+            if grid.in_bounds(nx as i32, ny as i32) && grid.is_open(nx, ny) {
+                candidates.push((nx, ny));
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use crate::generator::layout::bsp::{build_bsp_tree, connect_rooms_bsp, place_room_in_leaf};
+use crate::generator::layout::caves::{cave_grid, clear_chamber};
+use crate::generator::layout::{write_open_grid, Carving, Layout};
+use crate::generator::room::Room;
+use crate::map::grid::TileGrid;
+use crate::map::types::{Coordinate, Point};
+use rand::rngs::StdRng;
+use rand::Rng;
+
+const CAVE_LEAF_PROBABILITY: f64 = 0.3; // 30% of the BSP leaves collapse into caves
+
+// BSP rooms as usual, except some leaves have crumbled into caves
+pub struct MixedLayout;
+
+impl Layout for MixedLayout {
+    fn carve(
+        &self,
+        tiles: &mut TileGrid,
+        min_room_size: Coordinate,
+        max_room_size: Coordinate,
+        rng: &mut StdRng,
+    ) -> Carving {
+        let height = tiles.height();
+        let width = tiles.width();
+
+        let mut root = build_bsp_tree(width, height, min_room_size, rng);
+
+        root.for_each_leaf_mut(&mut |leaf| {
+            let Some(room) = place_room_in_leaf(leaf, min_room_size, max_room_size, rng) else {
+                return;
+            };
+
+            if rng.gen_bool(CAVE_LEAF_PROBABILITY) {
+                // The cave takes up the same footprint the room would have, walls included
+                let mut open = cave_grid(room.width + 1, room.height + 1, rng);
+                clear_chamber(&mut open, Point::new(room.width / 2, room.height / 2));
+                write_open_grid(tiles, room.location, &open);
+                leaf.room = Some(Room::new_cave(room.location, room.width, room.height));
+            } else {
+                room.carve(tiles);
+                leaf.room = Some(room);
+            }
+        });
+
+        let mut corridors = Vec::new();
+        connect_rooms_bsp(tiles, &root, &mut corridors, rng);
+
+        let mut rooms = Vec::new();
+        root.collect_rooms(&mut rooms);
+        Carving {
+            rooms,
+            bsp: Some(root),
+            corridors,
+        }
+    }
+}
@@ -0,0 +1,184 @@
+pub mod bsp;
+pub mod caves;
+pub mod maze;
+pub mod mixed;
+
+use crate::generator::prefab::PrefabLibrary;
+use crate::generator::room::Room;
+use crate::map::grid::TileGrid;
+use crate::map::types::{Coordinate, Point};
+use crate::tile::Tile;
+use bsp::BSPNode;
+use rand::rngs::StdRng;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+// A layout carves the shape of a level (rooms, caves, corridors) into an
+// empty map and describes the regions it made, so that the generator can
+// populate them and pick a spawn room regardless of how they were made.
+pub trait Layout: Send + Sync {
+    fn carve(
+        &self,
+        tiles: &mut TileGrid,
+        min_room_size: Coordinate,
+        max_room_size: Coordinate,
+        rng: &mut StdRng,
+    ) -> Carving;
+}
+
+// What a layout carved: the regions to populate, and for the layouts built
+// on a BSP tree the partitions they sit in and the corridors dug between them
+pub struct Carving {
+    pub rooms: Vec<Room>,
+    pub bsp: Option<BSPNode>,
+    pub corridors: Vec<Vec<Point>>, // every tile of each corridor, in digging order
+}
+
+impl Carving {
+    pub fn rooms(rooms: Vec<Room>) -> Self {
+        Carving {
+            rooms,
+            bsp: None,
+            corridors: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LayoutKind {
+    Bsp,   // rectangular rooms in BSP leaves joined by drunken-walk corridors
+    Caves, // cellular automata caverns
+    Maze,  // rooms scattered over a growing tree maze
+    Mixed, // BSP rooms where some leaves turn into caves
+}
+
+impl LayoutKind {
+    pub const ALL: [LayoutKind; 4] = [
+        LayoutKind::Bsp,
+        LayoutKind::Caves,
+        LayoutKind::Maze,
+        LayoutKind::Mixed,
+    ];
+
+    // The deeper you go the less the dungeon looks man made
+    pub fn for_depth(depth: u32) -> Self {
+        match depth {
+            0..=2 => LayoutKind::Bsp,
+            3..=4 => LayoutKind::Mixed,
+            5..=6 => LayoutKind::Maze,
+            _ => LayoutKind::Caves,
+        }
+    }
+
+    // Only the BSP layout has leaves tidy enough to hold prefabs
+    pub fn build(&self, prefabs: &Arc<PrefabLibrary>) -> Box<dyn Layout> {
+        match self {
+            LayoutKind::Bsp => Box::new(bsp::BspLayout::new(Arc::clone(prefabs))),
+            LayoutKind::Caves => Box::new(caves::CaveLayout),
+            LayoutKind::Maze => Box::new(maze::MazeLayout),
+            LayoutKind::Mixed => Box::new(mixed::MixedLayout),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LayoutKind::Bsp => "bsp",
+            LayoutKind::Caves => "caves",
+            LayoutKind::Maze => "maze",
+            LayoutKind::Mixed => "mixed",
+        }
+    }
+}
+
+impl fmt::Display for LayoutKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for LayoutKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LayoutKind::ALL
+            .iter()
+            .find(|kind| kind.name() == s)
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = LayoutKind::ALL.iter().map(|kind| kind.name()).collect();
+                format!(
+                    "Unknown layout '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+// Carve a single corridor tile and wall it in where there's nothing yet
+pub fn carve_corridor(tiles: &mut TileGrid, x: Coordinate, y: Coordinate) {
+    for dy in -1..=1_isize {
+        for dx in -1..=1_isize {
+            // Neighbours off the edge of the map are skipped
+            let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) else {
+                continue;
+            };
+            let Some(tile) = tiles.get_mut(Point::new(nx, ny)) else {
+                continue;
+            };
+            if dx == 0 && dy == 0 {
+                // Only dig through walls and empty space, never through room features
+                if matches!(tile, Tile::Empty | Tile::Wall { .. }) {
+                    *tile = Tile::Floor {
+                        visible: false,
+                        cursed: false,
+                    };
+                }
+            } else if *tile == Tile::Empty {
+                *tile = Tile::Wall { visible: false };
+            }
+        }
+    }
+}
+
+// Write an open/closed grid into the map at `origin`. Open cells become floor,
+// closed cells touching an open one become walls and the rest is left alone.
+pub fn write_open_grid(tiles: &mut TileGrid, origin: Point, open: &[Vec<bool>]) {
+    let height = open.len();
+    let width = open.first().map_or(0, |row| row.len());
+
+    for y in 0..height {
+        for x in 0..width {
+            let (map_x, map_y) = (origin.x + x, origin.y + y);
+            let point = Point::new(map_x, map_y);
+            if open[y][x] {
+                tiles.set(
+                    point,
+                    Tile::Floor {
+                        visible: false,
+                        cursed: false,
+                    },
+                );
+            } else if touches_open(open, x, y) {
+                tiles.set(point, Tile::Wall { visible: false });
+            }
+        }
+    }
+}
+
+fn touches_open(open: &[Vec<bool>], x: usize, y: usize) -> bool {
+    for dy in -1..=1_i32 {
+        for dx in -1..=1_i32 {
+            let nx = x as i32 + dx;
+            let ny = y as i32 + dy;
+            if nx < 0 || ny < 0 {
+                continue;
+            }
+            if let Some(&true) = open.get(ny as usize).and_then(|row| row.get(nx as usize)) {
+                return true;
+            }
+        }
+    }
+    false
+}
@@ -0,0 +1,178 @@
+use crate::data::items;
+use crate::generator::connectivity;
+use crate::generator::population::SpawnReport;
+use crate::generator::room::{Room, RoomType};
+use crate::item::{Item, ItemCategory};
+use crate::map::grid::TileGrid;
+use crate::map::pathfinding::DijkstraMap;
+use crate::map::types::Point;
+use crate::tile::{Lock, Tile};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+// Reachable without opening a single lock
+pub(super) fn unlocked_cost(point: Point, tile: Tile) -> Option<u32> {
+    match tile {
+        Tile::Door { lock: Some(_), .. } | Tile::Archway { lock: Some(_) } => None,
+        tile => connectivity::reachability_cost(point, tile),
+    }
+}
+
+// Room floor the player can walk to from spawn without opening a lock, spawn
+// itself is left free
+pub(super) fn reachable_floor<'a>(
+    tiles: &TileGrid,
+    rooms: impl Iterator<Item = &'a Room>,
+    spawn: Point,
+) -> Vec<Point> {
+    let reachable = DijkstraMap::new(tiles, &[spawn], unlocked_cost);
+    rooms
+        .flat_map(|room| room.interior_points())
+        .filter(|&point| {
+            point != spawn
+                && reachable.get(point).is_some()
+                && matches!(tiles.get(point), Some(Tile::Floor { cursed: false, .. }))
+        })
+        .collect()
+}
+
+// Every way into the room, `None` if one of them has no door to lock
+fn vault_doors(tiles: &TileGrid, room: &Room) -> Option<Vec<Point>> {
+    let mut doors = Vec::new();
+    for point in room.footprint() {
+        if room.contains_interior(point) {
+            continue;
+        }
+        match tiles.get(point) {
+            Some(Tile::Door { .. }) => doors.push(point),
+            Some(Tile::Wall { .. } | Tile::Empty) | None => {}
+            Some(_) => return None,
+        }
+    }
+    (!doors.is_empty()).then_some(doors)
+}
+
+// Locks the doors of every vault and drops a matching key somewhere the player
+// can walk to without passing a lock, so every level can be solved
+pub fn lock_vaults<R: Rng>(
+    tiles: &mut TileGrid,
+    rooms: &[Room],
+    spawn_room: usize,
+    rng: &mut R,
+) -> SpawnReport {
+    let mut report = SpawnReport::default();
+    let keys: Vec<Item> = items()
+        .all()
+        .filter(|item| item.category() == ItemCategory::Key)
+        .collect();
+    if keys.is_empty() {
+        return report;
+    }
+
+    let mut locked = Vec::new();
+    for room in rooms
+        .iter()
+        .filter(|room| room.room_type == RoomType::Vault)
+    {
+        let Some(doors) = vault_doors(tiles, room) else {
+            continue;
+        };
+        let key = *keys.choose(rng).unwrap();
+        set_locks(tiles, &doors, Some(Lock::Key(key)));
+        locked.push((key, doors));
+    }
+    if locked.is_empty() {
+        return report;
+    }
+
+    // Keys lie on room floor on this side of every lock
+    let mut spots = reachable_floor(
+        tiles,
+        rooms
+            .iter()
+            .filter(|room| room.room_type != RoomType::Vault),
+        rooms[spawn_room].center(),
+    );
+    spots.shuffle(rng);
+
+    let mut spots = spots.into_iter();
+    for (key, doors) in locked {
+        let Some(spot) = spots.next() else {
+            // Nowhere to leave the key, so the vault stays open
+            set_locks(tiles, &doors, None);
+            continue;
+        };
+        tiles.set(
+            spot,
+            Tile::Item {
+                visible: false,
+                item: key,
+            },
+        );
+        for _ in &doors {
+            report.add_placed("locked door".to_string());
+        }
+        report.add_placed(key.name().to_string());
+    }
+    report
+}
+
+// Swaps the exit's seal for a key lock and drops the key on this side of every
+// lock. The exit stays sealed when there's no key or nowhere to leave it.
+pub fn lock_exit<R: Rng>(
+    tiles: &mut TileGrid,
+    rooms: &[Room],
+    spawn_room: usize,
+    exit: Point,
+    rng: &mut R,
+) -> SpawnReport {
+    let mut report = SpawnReport::default();
+    let keys: Vec<Item> = items()
+        .all()
+        .filter(|item| item.category() == ItemCategory::Key)
+        .collect();
+    let Some(&key) = keys.choose(rng) else {
+        return report;
+    };
+
+    let spots = reachable_floor(
+        tiles,
+        rooms
+            .iter()
+            .filter(|room| room.room_type != RoomType::Vault),
+        rooms[spawn_room].center(),
+    );
+    let Some(&spot) = spots.choose(rng) else {
+        return report;
+    };
+
+    tiles.set(
+        exit,
+        Tile::Archway {
+            lock: Some(Lock::Key(key)),
+        },
+    );
+    tiles.set(
+        spot,
+        Tile::Item {
+            visible: false,
+            item: key,
+        },
+    );
+    report.add_placed("locked archway".to_string());
+    report.add_placed(key.name().to_string());
+    report
+}
+
+fn set_locks(tiles: &mut TileGrid, doors: &[Point], lock: Option<Lock>) {
+    for &door in doors {
+        tiles.set(
+            door,
+            Tile::Door {
+                visible: false,
+                open: false,
+                lock,
+            },
+        );
+    }
+}
@@ -1,361 +1,481 @@
-use crate::generator::room::Room;
-use crate::map::types::{Coordinate, GameMapTiles, Point};
+use crate::generator::buffer::RoomBuffer;
+use crate::generator::connectivity::{self, ConnectivityReport};
+use crate::generator::exit;
+use crate::generator::layout::bsp::{BSPNode, LEAF_PADDING};
+use crate::generator::layout::{self, LayoutKind};
+use crate::generator::locks;
+use crate::generator::objective;
+use crate::generator::population::SpawnReport;
+use crate::generator::prefab::PrefabLibrary;
+use crate::generator::room::{Room, RoomShape, RoomType};
+use crate::generator::structure::LevelStructure;
+use crate::map::grid::TileGrid;
+use crate::map::types::{Coordinate, Point};
+use crate::objective::Objective;
 use crate::tile::Tile;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use rayon::prelude::*;
-use std::sync::{Arc, RwLock};
-
-#[derive(Clone)]
-struct BSPNode {
-    x: Coordinate,
-    y: Coordinate,
-    width: Coordinate,
-    height: Coordinate,
-    left: Option<Box<BSPNode>>,
-    right: Option<Box<BSPNode>>,
-    room: Option<Room>,
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// How big a level is and how big its rooms get
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DungeonSize {
+    pub width: Coordinate,
+    pub height: Coordinate,
+    pub min_room_size: Coordinate,
+    pub max_room_size: Coordinate,
 }
 
-impl BSPNode {
-    fn new(x: Coordinate, y: Coordinate, width: Coordinate, height: Coordinate) -> Self {
-        BSPNode {
-            x,
-            y,
-            width,
-            height,
-            left: None,
-            right: None,
-            room: None,
+impl DungeonSize {
+    // The levels the game is played on
+    pub const LEVEL: DungeonSize = DungeonSize {
+        width: 400,
+        height: 200,
+        min_room_size: 10,
+        max_room_size: 25,
+    };
+    // Small enough to take in at a glance in the viewer
+    pub const VIEWER: DungeonSize = DungeonSize {
+        width: 300,
+        height: 120,
+        min_room_size: 5,
+        max_room_size: 20,
+    };
+
+    const MIN_SIDE: Coordinate = 20;
+    const MAX_SIDE: Coordinate = 2000;
+
+    // Rooms need space to fit, and huge maps take forever to generate
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, side) in [("width", self.width), ("height", self.height)] {
+            if !(Self::MIN_SIDE..=Self::MAX_SIDE).contains(&side) {
+                return Err(format!(
+                    "Map {} {} has to be between {} and {}",
+                    name,
+                    side,
+                    Self::MIN_SIDE,
+                    Self::MAX_SIDE
+                ));
+            }
+        }
+        if self.min_room_size < MapGenerator::MIN_ROOM_SIZE {
+            return Err(format!(
+                "Rooms have to be at least {} tiles",
+                MapGenerator::MIN_ROOM_SIZE
+            ));
+        }
+        if self.min_room_size > self.max_room_size {
+            return Err(format!(
+                "Smallest room size {} is bigger than the largest {}",
+                self.min_room_size, self.max_room_size
+            ));
+        }
+        // A room plus the padding around it has to fit in half the map,
+        // otherwise BSP levels mostly come out without any rooms
+        if (self.max_room_size + LEAF_PADDING * 2) * 2 > self.width.min(self.height) {
+            return Err(format!(
+                "Rooms of up to {} tiles don't fit in a {}x{} map",
+                self.max_room_size, self.width, self.height
+            ));
         }
+        Ok(())
     }
+}
 
-    fn is_leaf(&self) -> bool {
-        self.left.is_none() && self.right.is_none()
+impl Default for DungeonSize {
+    fn default() -> Self {
+        DungeonSize::LEVEL
     }
 }
 
 pub struct MapGenerator {
-    width: Coordinate,
-    height: Coordinate,
-    tiles: Arc<RwLock<GameMapTiles>>,
+    tiles: TileGrid,
     rooms: Vec<Room>,
-    bsp_root: Option<BSPNode>,
+    bsp: Option<BSPNode>,
+    corridors: Vec<Vec<Point>>, // paths dug between rooms, by the layout and by repairs
+    structure: LevelStructure,
+    layout: LayoutKind,
+    prefabs: Arc<PrefabLibrary>,
+    spawn_room: Option<usize>,
+    seed: u64,
+    rng: StdRng,    // everything random comes from here, so a seed rebuilds the level
+    depth: u32,     // decides which monsters show up
+    parallel: bool, // rooms are populated on the rayon thread pool
+    exit: Option<Point>, // the sealed archway leading deeper
+    objective: Objective, // what opens the exit, as asked for and then as achieved
+    connectivity_report: ConnectivityReport,
+    spawn_report: SpawnReport,
 }
 
 impl MapGenerator {
-    const TURN_PROBABILITY: f64 = 0.2; // 20% chance to turn at each step
+    pub const MAX_GENERATION_ATTEMPTS: usize = 5; // regenerate the level when repairs don't help
+    const MIN_ROOM_SIZE: Coordinate = 3; // anything smaller is all wall and no floor
 
     pub fn new(width: Coordinate, height: Coordinate) -> Self {
+        let seed = rand::random();
         MapGenerator {
-            width,
-            height,
-            tiles: Arc::new(RwLock::new(vec![vec![Tile::Empty; width]; height])),
+            tiles: TileGrid::new(width, height),
             rooms: Vec::new(),
-            bsp_root: None,
+            bsp: None,
+            corridors: Vec::new(),
+            structure: LevelStructure::default(),
+            layout: LayoutKind::Bsp,
+            prefabs: Arc::new(PrefabLibrary::default()),
+            spawn_room: None,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            depth: 1,
+            parallel: true,
+            exit: None,
+            objective: Objective::SlayBoss,
+            connectivity_report: ConnectivityReport::default(),
+            spawn_report: SpawnReport::default(),
+        }
+    }
+
+    pub fn set_layout(&mut self, layout: LayoutKind) -> &mut Self {
+        self.layout = layout;
+        self
+    }
+
+    // Generating again with the same seed and settings gives the same level
+    pub fn set_seed(&mut self, seed: u64) -> &mut Self {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    pub fn set_depth(&mut self, depth: u32) -> &mut Self {
+        self.depth = depth;
+        self
+    }
+
+    // Rooms come out the same either way, this only changes how fast
+    pub fn set_parallel(&mut self, parallel: bool) -> &mut Self {
+        self.parallel = parallel;
+        self
+    }
+
+    pub fn set_objective(&mut self, objective: Objective) -> &mut Self {
+        self.objective = objective;
+        self
+    }
+
+    pub fn set_prefabs(&mut self, prefabs: PrefabLibrary) -> &mut Self {
+        self.prefabs = Arc::new(prefabs);
+        self
+    }
+
+    pub fn generate(
+        &mut self,
+        min_room_size: Coordinate,
+        max_room_size: Coordinate,
+    ) -> &LevelStructure {
+        let min_room_size = min_room_size.max(Self::MIN_ROOM_SIZE);
+        let max_room_size = max_room_size.max(min_room_size);
+
+        for attempt in 1..=Self::MAX_GENERATION_ATTEMPTS {
+            self.generate_level(min_room_size, max_room_size);
+
+            let mut report = self.validate_and_repair();
+            report.attempts = attempt;
+            let is_valid = report.is_valid();
+            self.connectivity_report = report;
+
+            if is_valid {
+                break;
+            }
         }
+        // Callers decide what to do with a level that couldn't be fixed
+        self.connectivity_report.gave_up = !self.connectivity_report.is_valid();
+
+        // Locks go in last, once nothing changes the way through the level
+        self.lock_vaults();
+        self.prepare_objective();
+        self.exit = self
+            .spawn_room
+            .and_then(|spawn_room| exit::place_exit(&mut self.tiles, &self.rooms, spawn_room));
+        self.lock_exit();
+
+        self.structure = LevelStructure::new(
+            &self.tiles,
+            &self.rooms,
+            self.spawn_room,
+            self.exit,
+            self.bsp.take(),
+            &self.corridors,
+        );
+        &self.structure
     }
 
-    pub fn generate(&mut self, min_room_size: Coordinate, max_room_size: Coordinate) -> &mut Self {
+    fn generate_level(&mut self, min_room_size: Coordinate, max_room_size: Coordinate) {
         self.fill_with_empty();
 
-        // Build the BSP tree
-        self.build_bsp_tree(min_room_size);
+        // Carve rooms and the passages between them
+        let carving = self.layout.build(&self.prefabs).carve(
+            &mut self.tiles,
+            min_room_size,
+            max_room_size,
+            &mut self.rng,
+        );
+        self.rooms = carving.rooms;
+        self.bsp = carving.bsp;
+        self.corridors = carving.corridors;
 
-        // Create rooms in the leaf nodes
-        self.create_rooms_in_bsp(min_room_size, max_room_size);
+        // Pick the room the player starts in before anything gets placed in it
+        self.choose_spawn_room();
+        self.assign_room_types();
 
         // Populate rooms in parallel using Rayon
         self.populate_all_rooms();
 
-        // Connect rooms via depth-first traversal
-        if let Some(ref root) = self.bsp_root {
-            self.connect_rooms_bsp(root);
-        }
-
         self.place_all_room_doors();
+    }
 
-        self
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn get_dungeon(&self) -> &TileGrid {
+        &self.tiles
     }
 
-    pub fn get_dungeon(&self) -> Arc<RwLock<GameMapTiles>> {
-        Arc::clone(&self.tiles)
+    // Hands the finished dungeon over, e.g. to build a `Map` from it
+    pub fn into_dungeon(self) -> TileGrid {
+        self.tiles
     }
 
     pub fn get_rooms(&self) -> &Vec<Room> {
         &self.rooms
     }
 
-    fn fill_with_empty(&self) {
-        let mut tiles = self.tiles.write().unwrap();
-        for y in 0..self.height {
-            for x in 0..self.width {
-                tiles[y][x] = Tile::Empty;
-            }
-        }
+    // The partitions of the BSP based layouts, None for the others
+    pub fn get_bsp_tree(&self) -> Option<&BSPNode> {
+        self.structure.bsp.as_ref()
     }
 
-    fn build_bsp_tree(&mut self, min_size: Coordinate) {
-        let root = BSPNode::new(0, 0, self.width, self.height);
-        self.bsp_root = Some(root);
-        let max_depth = 5; // Adjust this value to control the depth of the tree
-        if let Some(ref mut root_node) = self.bsp_root {
-            Self::split_node(root_node, min_size, max_depth, 0);
-        }
+    // Rooms, corridors and how they connect, as of the last `generate`
+    pub fn get_structure(&self) -> &LevelStructure {
+        &self.structure
     }
 
-    fn split_node(
-        node: &mut BSPNode,
-        min_size: Coordinate,
-        max_depth: usize,
-        current_depth: usize,
-    ) {
-        if current_depth >= max_depth {
-            return;
-        }
+    pub fn get_spawn_room(&self) -> Option<&Room> {
+        self.spawn_room.map(|index| &self.rooms[index])
+    }
 
-        let can_split_horizontally = node.width >= min_size * 2;
-        let can_split_vertically = node.height >= min_size * 2;
+    pub fn get_exit(&self) -> Option<Point> {
+        self.exit
+    }
 
-        if !can_split_horizontally && !can_split_vertically {
-            return;
-        }
+    pub fn get_objective(&self) -> Objective {
+        self.objective
+    }
 
-        let split_vertically = if can_split_horizontally && can_split_vertically {
-            rand::random::<bool>()
-        } else {
-            can_split_horizontally
-        };
+    pub fn get_connectivity_report(&self) -> &ConnectivityReport {
+        &self.connectivity_report
+    }
 
-        if split_vertically {
-            // Split vertically
-            let split = rand::thread_rng().gen_range(min_size..(node.width - min_size + 1));
-            node.left = Some(Box::new(BSPNode::new(node.x, node.y, split, node.height)));
-            node.right = Some(Box::new(BSPNode::new(
-                node.x + split,
-                node.y,
-                node.width - split,
-                node.height,
-            )));
-        } else {
-            // Split horizontally
-            let split = rand::thread_rng().gen_range(min_size..(node.height - min_size + 1));
-            node.left = Some(Box::new(BSPNode::new(node.x, node.y, node.width, split)));
-            node.right = Some(Box::new(BSPNode::new(
-                node.x,
-                node.y + split,
-                node.width,
-                node.height - split,
-            )));
-        }
+    pub fn get_spawn_report(&self) -> &SpawnReport {
+        &self.spawn_report
+    }
 
-        if let Some(ref mut left) = node.left {
-            Self::split_node(left, min_size, max_depth, current_depth + 1);
+    fn choose_spawn_room(&mut self) {
+        // Don't drop the player into the middle of a boss lair
+        let mut candidates: Vec<usize> = (0..self.rooms.len())
+            .filter(|&index| !matches!(self.rooms[index].shape, RoomShape::Prefab { .. }))
+            .collect();
+        if candidates.is_empty() {
+            candidates = (0..self.rooms.len()).collect();
         }
-        if let Some(ref mut right) = node.right {
-            Self::split_node(right, min_size, max_depth, current_depth + 1);
+        if candidates.is_empty() {
+            self.spawn_room = None;
+            return;
         }
-    }
 
-    fn create_rooms_in_bsp(&mut self, min_room_size: Coordinate, max_room_size: Coordinate) {
-        let mut rooms = Vec::new();
-        if let Some(ref mut root) = self.bsp_root {
-            Self::collect_rooms_in_node(root, &mut rooms, min_room_size, max_room_size);
-        }
-        self.rooms = rooms;
+        let index = candidates[self.rng.gen_range(0..candidates.len())];
+        self.rooms[index].room_type = RoomType::Spawn;
+        self.spawn_room = Some(index);
     }
 
-    fn collect_rooms_in_node(
-        node: &mut BSPNode,
-        rooms: &mut Vec<Room>,
-        min_room_size: Coordinate,
-        max_room_size: Coordinate,
-    ) {
-        if node.is_leaf() {
-            let mut rng = rand::thread_rng();
-            let padding = 2; // Padding between the room and the partition edges
-
-            let max_room_width = (node.width - padding * 2).min(max_room_size);
-            let max_room_height = (node.height - padding * 2).min(max_room_size);
-
-            if max_room_width < min_room_size || max_room_height < min_room_size {
-                // If the partition is too small, skip room creation
-                return;
-            }
-
-            let room_width = rng.gen_range(min_room_size..=max_room_width);
-            let room_height = rng.gen_range(min_room_size..=max_room_height);
-
-            let x_range = node.x + padding..=node.x + node.width - room_width - padding;
-            let y_range = node.y + padding..=node.y + node.height - room_height - padding;
+    // One room at a time, so special rooms can be limited per level
+    fn assign_room_types(&mut self) {
+        let mut order: Vec<usize> = (0..self.rooms.len()).collect();
+        order.shuffle(&mut self.rng);
 
-            let room_x = if x_range.is_empty() {
-                node.x + padding
-            } else {
-                rng.gen_range(x_range)
-            };
+        let mut placed: HashMap<RoomType, usize> = HashMap::new();
+        for index in order {
+            let room = &mut self.rooms[index];
+            room.determine_room_type(&self.tiles, self.depth, &placed, &mut self.rng);
+            *placed.entry(room.room_type).or_default() += 1;
+        }
+    }
 
-            let room_y = if y_range.is_empty() {
-                node.y + padding
-            } else {
-                rng.gen_range(y_range)
-            };
+    // Flood fill from the spawn room, dig corridors to any room that can't be
+    // reached and clear out doors that ended up in the wrong place
+    fn validate_and_repair(&mut self) -> ConnectivityReport {
+        let Some(spawn_room) = self.spawn_room else {
+            return ConnectivityReport::default();
+        };
+        let spawn = self.rooms[spawn_room].center();
 
-            let location = Point::new(room_x, room_y);
+        let mut report = connectivity::validate(&self.tiles, &self.rooms, spawn_room);
+        let mut repaired_rooms = 0;
+        let mut removed_doors = 0;
 
-            let room = Room::new(location, room_width, room_height);
-            node.room = Some(room.clone());
-            rooms.push(room);
-        } else {
-            if let Some(ref mut left) = node.left {
-                Self::collect_rooms_in_node(left, rooms, min_room_size, max_room_size);
+        // Repairing one room often connects others on the way, so re-check after each dig
+        while let Some(&index) = report.unreachable_rooms.first() {
+            if repaired_rooms >= self.rooms.len() {
+                break;
             }
-            if let Some(ref mut right) = node.right {
-                Self::collect_rooms_in_node(right, rooms, min_room_size, max_room_size);
+            match connectivity::find_repair_path(&self.tiles, &self.rooms, index, spawn) {
+                Some(path) => {
+                    for &point in &path {
+                        layout::carve_corridor(&mut self.tiles, point.x, point.y);
+                    }
+                    self.corridors.push(path);
+                    repaired_rooms += 1;
+                }
+                None => break,
             }
+            report = connectivity::validate(&self.tiles, &self.rooms, spawn_room);
         }
-    }
 
-    fn populate_all_rooms(&mut self) {
-        let tiles = Arc::clone(&self.tiles);
-
-        // Use Rayon to populate rooms in parallel
-        self.rooms.par_iter_mut().for_each(|room| {
-            let tiles_clone = Arc::clone(&tiles);
-            room.populate(&tiles_clone);
-        });
-    }
-
-    fn place_all_room_doors(&self) {
-        let tiles = Arc::clone(&self.tiles);
-        // Use Rayon to place doors in parallel
-        self.rooms.par_iter().for_each(|room| {
-            let tiles_clone = Arc::clone(&tiles);
-            room.place_doors(&tiles_clone);
-        });
-    }
-
-    fn connect_rooms_bsp(&self, node: &BSPNode) {
-        if !node.is_leaf() {
-            if let (Some(left), Some(right)) = (node.left.as_ref(), node.right.as_ref()) {
-                self.connect_rooms_bsp(left);
-                self.connect_rooms_bsp(right);
-
-                let left_room = Self::get_room_in_subtree(left);
-                let right_room = Self::get_room_in_subtree(right);
-                if let (Some(lr), Some(rr)) = (left_room, right_room) {
-                    self.drunken_walk_corridor(lr.center(), rr.center());
-                }
-            }
+        // Dug corridors can leave doors standing next to open space
+        for &door in &report.invalid_doors {
+            self.tiles.set(
+                door,
+                Tile::Floor {
+                    visible: false,
+                    cursed: false,
+                },
+            );
+            removed_doors += 1;
         }
-    }
 
-    fn get_room_in_subtree(node: &BSPNode) -> Option<&Room> {
-        if let Some(ref room) = node.room {
-            Some(room)
-        } else {
-            node.left
-                .as_ref()
-                .and_then(|left| Self::get_room_in_subtree(left))
-                .or_else(|| {
-                    node.right
-                        .as_ref()
-                        .and_then(|right| Self::get_room_in_subtree(right))
-                })
+        if removed_doors > 0 || repaired_rooms > 0 {
+            report = connectivity::validate(&self.tiles, &self.rooms, spawn_room);
         }
+        report.repaired_rooms = repaired_rooms;
+        report.removed_doors = removed_doors;
+        report
     }
 
-    fn drunken_walk_corridor(&self, start: Point, end: Point) {
-        let mut rng = rand::thread_rng();
-        let mut current = start;
+    // Whatever the objective needs goes on this side of the locks too
+    fn prepare_objective(&mut self) {
+        let Some(spawn_room) = self.spawn_room else {
+            return;
+        };
+        let (objective, report) = objective::prepare(
+            &mut self.tiles,
+            &self.rooms,
+            spawn_room,
+            self.objective,
+            &mut self.rng,
+        );
+        self.objective = objective;
+        self.spawn_report = std::mem::take(&mut self.spawn_report).merge(report);
+    }
 
-        // Randomly choose the initial direction
-        let mut direction = if rng.gen_bool(0.5) { 'x' } else { 'y' };
+    fn lock_vaults(&mut self) {
+        let Some(spawn_room) = self.spawn_room else {
+            return;
+        };
+        let report = locks::lock_vaults(&mut self.tiles, &self.rooms, spawn_room, &mut self.rng);
+        self.spawn_report = std::mem::take(&mut self.spawn_report).merge(report);
+    }
 
-        while current != end {
-            self.carve_corridor(current.x, current.y);
+    // Nothing else opens the way out when it only has to be reached, so it takes a key
+    fn lock_exit(&mut self) {
+        let (Some(spawn_room), Some(exit)) = (self.spawn_room, self.exit) else {
+            return;
+        };
+        if self.objective != Objective::ReachExit {
+            return;
+        }
+        let report = locks::lock_exit(
+            &mut self.tiles,
+            &self.rooms,
+            spawn_room,
+            exit,
+            &mut self.rng,
+        );
+        self.spawn_report = std::mem::take(&mut self.spawn_report).merge(report);
+    }
 
-            let dx = end.x as isize - current.x as isize;
-            let dy = end.y as isize - current.y as isize;
+    fn fill_with_empty(&mut self) {
+        self.tiles.fill(Tile::Empty);
+    }
 
-            // Check if we need to change direction
-            let at_end_in_direction = match direction {
-                'x' => dx == 0,
-                'y' => dy == 0,
-                _ => false,
-            };
+    fn populate_all_rooms(&mut self) {
+        let depth = self.depth;
+        self.spawn_report = self
+            .work_on_rooms(|room, tiles, rng| room.populate(tiles, depth, rng))
+            .into_iter()
+            .fold(SpawnReport::default(), SpawnReport::merge);
+    }
 
-            // Decide whether to turn based on TURN_PROBABILITY
-            if at_end_in_direction || rng.gen_bool(Self::TURN_PROBABILITY) {
-                // Change direction
-                direction = if direction == 'x' { 'y' } else { 'x' };
-            }
+    fn place_all_room_doors(&mut self) {
+        self.work_on_rooms(|room, tiles, rng| room.place_doors(tiles, rng));
+    }
 
-            // Move in the current direction
-            match direction {
-                'x' => {
-                    if dx != 0 {
-                        current.x = (current.x as isize + dx.signum())
-                            .clamp(0, (self.width - 1) as isize)
-                            as Coordinate;
-                    }
-                }
-                'y' => {
-                    if dy != 0 {
-                        current.y = (current.y as isize + dy.signum())
-                            .clamp(0, (self.height - 1) as isize)
-                            as Coordinate;
-                    }
-                }
-                _ => {}
-            }
-        }
+    // Threads can't share the generator's rng, so every room gets its own
+    // seeded from it, whatever order the threads run in
+    fn room_seeds(&mut self) -> Vec<u64> {
+        (0..self.rooms.len()).map(|_| self.rng.gen()).collect()
     }
 
-    fn carve_corridor(&self, x: Coordinate, y: Coordinate) {
-        let mut tiles = self.tiles.write().unwrap();
-        let max_x = self.width;
-        let max_y = self.height;
-
-        for dy in -1..=1 {
-            for dx in -1..=1 {
-                let nx = (x as isize + dx).clamp(0, (max_x - 1) as isize) as Coordinate;
-                let ny = (y as isize + dy).clamp(0, (max_y - 1) as isize) as Coordinate;
-                if dx == 0 && dy == 0 {
-                    tiles[ny][nx] = Tile::Floor {
-                        visible: false,
-                        cursed: false,
-                    };
-                } else if tiles[ny][nx] == Tile::Empty {
-                    tiles[ny][nx] = Tile::Wall { visible: false };
+    // Every room works on its own copy of the map around it, in parallel
+    // unless asked not to. The changes are merged back in room order, so a
+    // seed gives the same level however the threads were scheduled.
+    fn work_on_rooms<F, T>(&mut self, work: F) -> Vec<T>
+    where
+        F: Fn(&Room, &mut TileGrid, &mut StdRng) -> T + Sync,
+        T: Send,
+    {
+        let seeds = self.room_seeds();
+        let tiles = &self.tiles;
+        let run = |(room, seed): (&Room, u64)| {
+            let mut buffer = RoomBuffer::new(room, tiles);
+            let result =
+                buffer.run(|room, tiles| work(room, tiles, &mut StdRng::seed_from_u64(seed)));
+            (result, buffer.into_changes())
+        };
+        let results: Vec<(T, Vec<(Point, Tile)>)> = if self.parallel {
+            self.rooms.par_iter().zip(seeds).map(run).collect()
+        } else {
+            self.rooms.iter().zip(seeds).map(run).collect()
+        };
+
+        // Applied in room order whichever way the work ran, so on a tile two
+        // rooms both changed the later room's version is the one kept
+        results
+            .into_iter()
+            .map(|(result, changes)| {
+                for (point, tile) in changes {
+                    self.tiles.set(point, tile);
                 }
-            }
-        }
+                result
+            })
+            .collect()
     }
 
-    #[allow(dead_code)]
     pub fn print(&self, with_border: bool) {
-        println!("{}x{}", self.width, self.height);
+        println!("{}x{}", self.tiles.width(), self.tiles.height());
         if with_border {
-            println!("{}", "-".repeat(self.width));
+            println!("{}", "-".repeat(self.tiles.width()));
+        }
+        for row in self.tiles.to_text().lines() {
+            if with_border {
+                println!("|{}|", row);
+            } else {
+                println!("{}", row);
+            }
         }
-        let tiles = self.tiles.read().unwrap();
-        println!(
-            "{}",
-            tiles
-                .iter()
-                .map(|row| {
-                    let row_str: String = row.iter().map(|tile| tile.as_char()).collect();
-                    if with_border {
-                        format!("|{}|", row_str)
-                    } else {
-                        row_str
-                    }
-                })
-                .collect::<Vec<String>>()
-                .join("\n")
-        );
         if with_border {
-            println!("{}", "-".repeat(self.width));
+            println!("{}", "-".repeat(self.tiles.width()));
         }
     }
 }
@@ -1,2 +1,13 @@
+mod buffer;
+pub mod chunk;
+pub mod connectivity;
+mod exit;
+pub mod layout;
+mod locks;
 pub mod map;
-mod room;
+mod objective;
+mod population;
+pub mod prefab;
+pub mod room;
+pub mod stats;
+pub mod structure;
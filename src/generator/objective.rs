@@ -0,0 +1,107 @@
+use crate::data::monster::Behaviour;
+use crate::generator::locks;
+use crate::generator::population::SpawnReport;
+use crate::generator::room::{Room, RoomType};
+use crate::item::Item;
+use crate::map::grid::TileGrid;
+use crate::map::types::Point;
+use crate::objective::{self, Objective};
+use crate::tile::Tile;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+// Makes sure the objective can be done on this level: the boss has to be
+// there, obelisks need scrolls to banish them and artefacts have to be put
+// somewhere. Settles for `ReachExit` when it can't manage that.
+pub fn prepare<R: Rng>(
+    tiles: &mut TileGrid,
+    rooms: &[Room],
+    spawn_room: usize,
+    objective: Objective,
+    rng: &mut R,
+) -> (Objective, SpawnReport) {
+    let mut report = SpawnReport::default();
+    let spawn = rooms[spawn_room].center();
+    let open_rooms = || {
+        rooms
+            .iter()
+            .filter(|room| room.room_type != RoomType::Vault)
+    };
+
+    let objective = match objective {
+        Objective::SlayBoss => {
+            let has_boss = tiles.rows().flatten().any(|tile| {
+                tile.monster_def()
+                    .is_some_and(|def| def.behaviour == Behaviour::Boss)
+            });
+            if has_boss {
+                Objective::SlayBoss
+            } else {
+                Objective::ReachExit
+            }
+        }
+        Objective::CleanseObelisks(count) => {
+            let obelisks = tiles
+                .rows()
+                .flatten()
+                .filter(|tile| matches!(tile, Tile::Obelisk { curse: true, .. }))
+                .count() as u32;
+            let mut spots = locks::reachable_floor(tiles, open_rooms(), spawn);
+            spots.shuffle(rng);
+
+            // One scroll for every obelisk that has to go
+            let scrolls = objective::banishing_item()
+                .map(|scroll| {
+                    let spots = spots.into_iter().take(count.min(obelisks) as usize);
+                    place_items(tiles, spots, scroll, &mut report)
+                })
+                .unwrap_or(0);
+            if scrolls > 0 {
+                Objective::CleanseObelisks(scrolls)
+            } else {
+                Objective::ReachExit
+            }
+        }
+        Objective::RetrieveArtefact(artefact) => {
+            // Secret rooms if there are any, otherwise anywhere the player can get to
+            let secret_rooms = rooms
+                .iter()
+                .filter(|room| room.room_type == RoomType::Secret);
+            let mut spots = locks::reachable_floor(tiles, secret_rooms, spawn);
+            if spots.is_empty() {
+                spots = locks::reachable_floor(tiles, open_rooms(), spawn);
+            }
+            match spots.choose(rng) {
+                Some(&spot) => {
+                    place_items(tiles, [spot], artefact, &mut report);
+                    Objective::RetrieveArtefact(artefact)
+                }
+                None => Objective::ReachExit,
+            }
+        }
+        Objective::ReachExit => Objective::ReachExit,
+    };
+    (objective, report)
+}
+
+// Returns how many were placed
+fn place_items(
+    tiles: &mut TileGrid,
+    spots: impl IntoIterator<Item = Point>,
+    item: Item,
+    report: &mut SpawnReport,
+) -> u32 {
+    let mut placed = 0;
+    for spot in spots {
+        tiles.set(
+            spot,
+            Tile::Item {
+                visible: false,
+                item,
+            },
+        );
+        report.add_placed(item.name().to_string());
+        placed += 1;
+    }
+    placed
+}
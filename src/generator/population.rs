@@ -0,0 +1,61 @@
+use crate::generator::room::RoomType;
+use std::collections::BTreeMap;
+
+// What the spawn tables put into a generated dungeon
+#[derive(Clone, Debug, Default)]
+pub struct SpawnReport {
+    pub rooms: BTreeMap<&'static str, usize>, // populated rooms by type
+    pub placed: BTreeMap<String, usize>,      // monsters by name, features and secrets
+    pub skipped: usize,                       // rolled but found no space or no monster
+}
+
+impl SpawnReport {
+    pub fn add_room(&mut self, room_type: RoomType) {
+        *self.rooms.entry(room_type.name()).or_default() += 1;
+    }
+
+    pub fn add_placed(&mut self, name: String) {
+        *self.placed.entry(name).or_default() += 1;
+    }
+
+    pub fn merge(mut self, other: SpawnReport) -> SpawnReport {
+        for (room_type, count) in other.rooms {
+            *self.rooms.entry(room_type).or_default() += count;
+        }
+        for (name, count) in other.placed {
+            *self.placed.entry(name).or_default() += count;
+        }
+        self.skipped += other.skipped;
+        self
+    }
+
+    pub fn count(&self, name: &str) -> usize {
+        self.placed.get(name).copied().unwrap_or(0)
+    }
+
+    pub fn summary(&self) -> String {
+        let list = |counts: Vec<String>| {
+            if counts.is_empty() {
+                "none".to_string()
+            } else {
+                counts.join(", ")
+            }
+        };
+        format!(
+            "Rooms {} | Placed {} | Skipped {}",
+            list(
+                self.rooms
+                    .iter()
+                    .map(|(room_type, count)| format!("{} {}", count, room_type))
+                    .collect()
+            ),
+            list(
+                self.placed
+                    .iter()
+                    .map(|(name, count)| format!("{} {}", count, name))
+                    .collect()
+            ),
+            self.skipped
+        )
+    }
+}
@@ -0,0 +1,368 @@
+use crate::map::grid::TileGrid;
+use crate::map::types::{Coordinate, Point};
+use crate::tile::Tile;
+use rand::Rng;
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Prefabs live next to the hand made maps
+pub const PREFAB_DIRECTORY: &str = "maps/prefabs";
+
+// The shipped prefabs, used when the game runs away from its directory
+const BUILTIN_PREFABS: [(&str, &str); 4] = [
+    (
+        "boss_lair",
+        include_str!("../../maps/prefabs/boss_lair.txt"),
+    ),
+    (
+        "obelisk_circle",
+        include_str!("../../maps/prefabs/obelisk_circle.txt"),
+    ),
+    ("shrine", include_str!("../../maps/prefabs/shrine.txt")),
+    ("vault", include_str!("../../maps/prefabs/vault.txt")),
+];
+
+const DOOR_MARKER: char = 'D'; // where corridors may connect, walled up if nothing does
+const ANCHOR_MARKER: char = '*'; // the heart of the room, a floor tile
+const MIN_PREFAB_SIZE: Coordinate = 3;
+
+// A hand made room drawn in ASCII. Every prefab is a walled rectangle with at
+// least one door marker in its outer wall and a single anchor inside.
+#[derive(Clone, Debug)]
+pub struct Prefab {
+    name: String,
+    cells: Vec<Vec<char>>,
+}
+
+// Every prefab found in a directory, checked when it's loaded
+#[derive(Clone, Debug, Default)]
+pub struct PrefabLibrary {
+    prefabs: Vec<Prefab>,
+}
+
+impl PrefabLibrary {
+    // Load every `.txt` file in `directory`, a single broken template fails the lot
+    pub fn load<P: AsRef<Path>>(directory: P) -> io::Result<Self> {
+        let mut paths: Vec<_> = fs::read_dir(directory)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<_>>()?;
+        paths.retain(|path| path.extension().is_some_and(|ext| ext == "txt"));
+        paths.sort();
+
+        let mut prefabs = Vec::with_capacity(paths.len());
+        for path in paths {
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let source = fs::read_to_string(&path)?;
+            let prefab = Prefab::parse(&name, &source).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid prefab {}: {}", path.display(), e),
+                )
+            })?;
+            prefabs.push(prefab);
+        }
+
+        Ok(PrefabLibrary { prefabs })
+    }
+
+    // Like `load`, but falls back to the shipped prefabs when `directory`
+    // doesn't exist. Broken templates in an existing directory still fail.
+    pub fn load_or_builtin<P: AsRef<Path>>(directory: P) -> io::Result<Self> {
+        if directory.as_ref().is_dir() {
+            PrefabLibrary::load(directory)
+        } else {
+            Ok(PrefabLibrary::builtin())
+        }
+    }
+
+    pub fn builtin() -> Self {
+        PrefabLibrary {
+            prefabs: BUILTIN_PREFABS
+                .iter()
+                .map(|(name, source)| Prefab::parse(name, source).expect("Invalid builtin prefab"))
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prefabs.is_empty()
+    }
+
+    // A random prefab in a random orientation that fits in `width` x `height`
+    pub fn random_fitting<R: Rng>(
+        &self,
+        width: Coordinate,
+        height: Coordinate,
+        rng: &mut R,
+    ) -> Option<Prefab> {
+        let mut candidates: Vec<Prefab> = self
+            .prefabs
+            .iter()
+            .map(|prefab| prefab.random_variant(rng))
+            .filter(|prefab| prefab.width() <= width && prefab.height() <= height)
+            .collect();
+
+        if candidates.is_empty() {
+            return None;
+        }
+        let index = rng.gen_range(0..candidates.len());
+        Some(candidates.swap_remove(index))
+    }
+}
+
+impl Prefab {
+    pub fn parse(name: &str, source: &str) -> Result<Self, String> {
+        let cells: Vec<Vec<char>> = source
+            .lines()
+            .map(|line| line.trim_end().chars().collect())
+            .filter(|row: &Vec<char>| !row.is_empty())
+            .collect();
+
+        let prefab = Prefab {
+            name: name.to_string(),
+            cells,
+        };
+        prefab.validate()?;
+        Ok(prefab)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let (width, height) = (self.width(), self.height());
+        if width < MIN_PREFAB_SIZE || height < MIN_PREFAB_SIZE {
+            return Err(format!(
+                "must be at least {}x{}, got {}x{}",
+                MIN_PREFAB_SIZE, MIN_PREFAB_SIZE, width, height
+            ));
+        }
+
+        if let Some((y, row)) = self
+            .cells
+            .iter()
+            .enumerate()
+            .find(|(_, row)| row.len() != width)
+        {
+            return Err(format!(
+                "line {} is {} wide, expected {}",
+                y + 1,
+                row.len(),
+                width
+            ));
+        }
+
+        let mut anchors = 0;
+        for (y, row) in self.cells.iter().enumerate() {
+            for (x, &c) in row.iter().enumerate() {
+                let position = format!("line {} column {}", y + 1, x + 1);
+                if !is_known_char(c) {
+                    return Err(format!("unknown tile '{}' at {}", c, position));
+                }
+
+                let on_border = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+                let on_corner = (x == 0 || x == width - 1) && (y == 0 || y == height - 1);
+                match c {
+                    ANCHOR_MARKER if on_border => {
+                        return Err(format!("anchor at {} is in the outer wall", position))
+                    }
+                    ANCHOR_MARKER => anchors += 1,
+                    DOOR_MARKER if !on_border || on_corner => {
+                        return Err(format!(
+                            "door marker at {} is not in the outer wall",
+                            position
+                        ))
+                    }
+                    DOOR_MARKER => {
+                        let inside = self.inward(Point::new(x, y));
+                        if !is_passable(self.cells[inside.y][inside.x]) {
+                            return Err(format!("door marker at {} opens into a wall", position));
+                        }
+                    }
+                    '#' => {}
+                    _ if on_border => {
+                        return Err(format!("outer wall is broken by '{}' at {}", c, position))
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if anchors != 1 {
+            return Err(format!("needs exactly one anchor '*', found {}", anchors));
+        }
+        if self.doors().is_empty() {
+            return Err("needs at least one door marker 'D'".to_string());
+        }
+
+        // Every door has to lead to the anchor or the room is useless
+        let reachable = self.reachable_from(self.anchor());
+        if let Some(door) = self.doors().iter().find(|door| !reachable.contains(door)) {
+            return Err(format!(
+                "door marker at line {} column {} can't reach the anchor",
+                door.y + 1,
+                door.x + 1
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn width(&self) -> Coordinate {
+        self.cells.first().map_or(0, |row| row.len())
+    }
+
+    pub fn height(&self) -> Coordinate {
+        self.cells.len()
+    }
+
+    pub fn anchor(&self) -> Point {
+        self.find(ANCHOR_MARKER)
+            .into_iter()
+            .next()
+            .unwrap_or(Point::new(self.width() / 2, self.height() / 2))
+    }
+
+    pub fn doors(&self) -> Vec<Point> {
+        self.find(DOOR_MARKER)
+    }
+
+    // Turned a quarter clockwise
+    pub fn rotated(&self) -> Prefab {
+        let (width, height) = (self.width(), self.height());
+        let cells = (0..width)
+            .map(|y| (0..height).map(|x| self.cells[height - 1 - x][y]).collect())
+            .collect();
+        Prefab {
+            name: self.name.clone(),
+            cells,
+        }
+    }
+
+    // Flipped left to right
+    pub fn mirrored(&self) -> Prefab {
+        let cells = self
+            .cells
+            .iter()
+            .map(|row| row.iter().rev().copied().collect())
+            .collect();
+        Prefab {
+            name: self.name.clone(),
+            cells,
+        }
+    }
+
+    pub fn random_variant<R: Rng>(&self, rng: &mut R) -> Prefab {
+        let mut variant = self.clone();
+        for _ in 0..rng.gen_range(0..4) {
+            variant = variant.rotated();
+        }
+        if rng.gen_bool(0.5) {
+            variant = variant.mirrored();
+        }
+        variant
+    }
+
+    // Write the prefab into the map with its top left corner at `origin`
+    pub fn stamp<R: Rng>(&self, tiles: &mut TileGrid, origin: Point, rng: &mut R) {
+        for (y, row) in self.cells.iter().enumerate() {
+            for (x, &c) in row.iter().enumerate() {
+                let Some(tile) = tiles.get_mut(Point::new(origin.x + x, origin.y + y)) else {
+                    continue;
+                };
+                *tile = match c {
+                    DOOR_MARKER => Tile::Door {
+                        visible: false,
+                        open: false,
+                        lock: None,
+                    },
+                    ANCHOR_MARKER => Tile::Floor {
+                        visible: false,
+                        cursed: false,
+                    },
+                    c => Tile::from_char_with(c, rng),
+                };
+            }
+        }
+    }
+
+    // Which way a door marker in the outer wall faces, away from the room
+    pub fn door_facing(&self, door: Point) -> (isize, isize) {
+        if door.x == 0 {
+            (-1, 0)
+        } else if door.x == self.width() - 1 {
+            (1, 0)
+        } else if door.y == 0 {
+            (0, -1)
+        } else {
+            (0, 1)
+        }
+    }
+
+    // The tile just inside a door marker
+    fn inward(&self, door: Point) -> Point {
+        let (dx, dy) = self.door_facing(door);
+        Point::new(
+            (door.x as isize - dx) as Coordinate,
+            (door.y as isize - dy) as Coordinate,
+        )
+    }
+
+    fn find(&self, marker: char) -> Vec<Point> {
+        self.cells
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(move |(_, &c)| c == marker)
+                    .map(move |(x, _)| Point::new(x, y))
+            })
+            .collect()
+    }
+
+    fn reachable_from(&self, start: Point) -> HashSet<Point> {
+        let (width, height) = (self.width(), self.height());
+        let mut seen = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(point) = queue.pop_front() {
+            for (dx, dy) in [(0, -1), (-1, 0), (1, 0), (0, 1)] {
+                let x = point.x as i32 + dx;
+                let y = point.y as i32 + dy;
+                if x < 0 || y < 0 || x as usize >= width || y as usize >= height {
+                    continue;
+                }
+                let next = Point::new(x as usize, y as usize);
+                if is_passable(self.cells[next.y][next.x]) && seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        seen
+    }
+}
+
+fn is_known_char(c: char) -> bool {
+    // The archway and the player are placed by the game, never by a prefab
+    matches!(c, DOOR_MARKER | ANCHOR_MARKER)
+        || (!matches!(c, '∩' | '@') && Tile::from_char(c) != Tile::Empty)
+}
+
+// Doors open and monsters can be fought, everything else has to be walkable
+fn is_passable(c: char) -> bool {
+    match c {
+        DOOR_MARKER | ANCHOR_MARKER => true,
+        c => {
+            let tile = Tile::from_char(c);
+            tile.is_walkable() || tile.is_monster() || matches!(tile, Tile::Door { .. })
+        }
+    }
+}
@@ -1,14 +1,148 @@
-use crate::map::types::{Coordinate, GameMapTiles, Point};
+use crate::data::monster::Behaviour;
+use crate::data::spawn::{ItemPick, Spawn};
+use crate::data::{lore, monsters, spawns};
+use crate::generator::population::SpawnReport;
+use crate::generator::prefab::Prefab;
+use crate::item::Item;
+use crate::map::grid::TileGrid;
+use crate::map::types::{Coordinate, Point};
 use crate::tile::Tile;
 use rand::seq::SliceRandom;
 use rand::Rng;
-use std::sync::{Arc, RwLock};
+use std::collections::HashMap;
+
+const CARDINAL_OFFSETS: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+pub(super) const ALL_OFFSETS: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+// Points next to `point`, the room's walls keep its interior off the map edge
+pub(super) fn around(point: Point, offsets: &[(isize, isize)]) -> impl Iterator<Item = Point> + '_ {
+    offsets.iter().map(move |&(dx, dy)| {
+        Point::new(
+            point.x.wrapping_add_signed(dx),
+            point.y.wrapping_add_signed(dy),
+        )
+    })
+}
 
-#[derive(Clone)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum RoomType {
     Normal,
     Obelisk,
     Secret,
+    Vault,   // a dead end full of treasure behind a door
+    Boss,    // a single strong monster guarding its loot
+    Shrine,  // heals whoever prays there, once
+    Library, // scrolls and signs telling the dungeon's story
+    Spawn,   // where the player starts, kept free of features
+}
+
+impl RoomType {
+    // Room types that get their contents from a spawn table
+    pub const POPULATED: [RoomType; 7] = [
+        RoomType::Normal,
+        RoomType::Obelisk,
+        RoomType::Secret,
+        RoomType::Vault,
+        RoomType::Boss,
+        RoomType::Shrine,
+        RoomType::Library,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RoomType::Normal => "normal",
+            RoomType::Obelisk => "obelisk",
+            RoomType::Secret => "secret",
+            RoomType::Vault => "vault",
+            RoomType::Boss => "boss",
+            RoomType::Shrine => "shrine",
+            RoomType::Library => "library",
+            RoomType::Spawn => "spawn",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        [RoomType::Spawn]
+            .into_iter()
+            .chain(RoomType::POPULATED)
+            .find(|room_type| room_type.name() == value)
+            .ok_or_else(|| format!("unknown room type '{}'", value))
+    }
+}
+
+// Where a special room can go and how often it shows up
+struct Placement {
+    room_type: RoomType,
+    dead_end: bool,       // only rooms with a single entrance
+    min_size: Coordinate, // both sides at least this long
+    max_size: Coordinate, // and at most this long
+    min_depth: u32,
+    chance: f64, // for every room that fits
+    per_level: usize,
+}
+
+// Tried in order for every room, the first that fits and passes its roll wins
+const SPECIAL_ROOMS: [Placement; 4] = [
+    // Every level that has a big enough room gets a boss
+    Placement {
+        room_type: RoomType::Boss,
+        dead_end: false,
+        min_size: 12,
+        max_size: Coordinate::MAX,
+        min_depth: 1,
+        chance: 1.0,
+        per_level: 1,
+    },
+    Placement {
+        room_type: RoomType::Vault,
+        dead_end: true,
+        min_size: 5,
+        max_size: 12,
+        min_depth: 1,
+        chance: 0.5,
+        per_level: 2,
+    },
+    Placement {
+        room_type: RoomType::Library,
+        dead_end: false,
+        min_size: 8,
+        max_size: Coordinate::MAX,
+        min_depth: 1,
+        chance: 0.3,
+        per_level: 1,
+    },
+    // The first level is gentle enough without one
+    Placement {
+        room_type: RoomType::Shrine,
+        dead_end: false,
+        min_size: 5,
+        max_size: 14,
+        min_depth: 2,
+        chance: 0.4,
+        per_level: 1,
+    },
+];
+
+// How the room was carved, caves have no straight walls to rely on
+#[derive(Clone)]
+pub enum RoomShape {
+    Rectangle,
+    Cave,
+    // Hand made from a template, its door markers and anchor in map coordinates
+    Prefab {
+        name: String,
+        anchor: Point,
+        doors: Vec<Point>,
+    },
 }
 
 #[derive(Clone)]
@@ -17,19 +151,11 @@ pub struct Room {
     pub width: Coordinate,
     pub height: Coordinate,
     pub room_type: RoomType,
-}
-
-// Possible mob types
-#[derive(Clone)]
-enum MobType {
-    Wither,
-    Bat,
-    Brute,
+    pub shape: RoomShape,
 }
 
 impl Room {
-    const OBELISK_PROB_IN_NORMAL_ROOM: f64 = 0.25; // 25% chance or
-    const SECRET_PROB_IN_NORMAL_ROOM: f64 = 0.5; // 50% chance
+    const MIN_SIZE_FOR_COLUMNS: Coordinate = 5;
 
     pub fn new(location: Point, width: Coordinate, height: Coordinate) -> Self {
         Room {
@@ -37,53 +163,95 @@ impl Room {
             width,
             height,
             room_type: RoomType::Normal,
+            shape: RoomShape::Rectangle,
         }
     }
 
-    pub fn populate(&mut self, tiles: &Arc<RwLock<GameMapTiles>>) {
-        self.fill_with_floor(tiles);
-        self.surround_with_walls(tiles);
-        self.place_columns(tiles);
-        self.determine_room_type(tiles);
+    // A cave region already carved by the layout, the rectangle is just its bounds
+    pub fn new_cave(location: Point, width: Coordinate, height: Coordinate) -> Self {
+        Room {
+            shape: RoomShape::Cave,
+            ..Room::new(location, width, height)
+        }
+    }
 
-        let mut rng = rand::thread_rng();
+    // A prefab stamped with its top left corner at `location`
+    pub fn new_prefab(location: Point, prefab: &Prefab) -> Self {
+        let to_map = |point: Point| Point::new(location.x + point.x, location.y + point.y);
+        Room {
+            shape: RoomShape::Prefab {
+                name: prefab.get_name().to_string(),
+                anchor: to_map(prefab.anchor()),
+                doors: prefab.doors().into_iter().map(to_map).collect(),
+            },
+            ..Room::new(location, prefab.width() - 1, prefab.height() - 1)
+        }
+    }
 
-        match self.room_type {
-            RoomType::Obelisk => {
-                self.place_obelisk(tiles);
-            }
-            RoomType::Secret => {
-                self.place_secret(tiles, true); // `true` indicates it's a secret room
+    // Floor and walls for a rectangular room, caves are carved by their layout
+    pub fn carve(&self, tiles: &mut TileGrid) {
+        if let RoomShape::Rectangle = self.shape {
+            self.fill_with_floor(tiles);
+            self.surround_with_walls(tiles);
+        }
+    }
 
-                // Brutes have a 50% chance of appearing in secret rooms
-                if rng.gen_bool(0.5) {
-                    self.place_mobs(tiles);
-                    self.place_brute(tiles);
-                }
-            }
-            RoomType::Normal => {
-                // Decide whether to place an obelisk
-                if rng.gen_bool(Self::OBELISK_PROB_IN_NORMAL_ROOM) {
-                    self.place_obelisk(tiles);
-                }
-                // Decide whether to place a secret
-                if rng.gen_bool(Self::SECRET_PROB_IN_NORMAL_ROOM) {
-                    self.place_secret(tiles, false); // `false` indicates it's a normal room
-                }
-                // Mobs have a 95% chance of appearing in normal rooms, it's a dungeon after all
-                if rng.gen_bool(0.95) {
-                    self.place_mobs(tiles);
-                }
-            }
+    // Fills the room from the spawn table for its type and `depth`
+    pub fn populate<R: Rng>(&self, tiles: &mut TileGrid, depth: u32, rng: &mut R) -> SpawnReport {
+        // Prefabs come furnished
+        if matches!(self.room_type, RoomType::Spawn)
+            || matches!(self.shape, RoomShape::Prefab { .. })
+        {
+            return SpawnReport::default();
         }
 
-        self.place_doors(tiles);
+        if let RoomShape::Rectangle = self.shape {
+            self.place_columns(tiles, rng);
+        }
+
+        let Some(table) = spawns().for_room(self.room_type, depth) else {
+            return SpawnReport::default();
+        };
+        let rolled = table.roll(rng);
+
+        self.place_spawns(tiles, &rolled, depth, rng)
     }
 
-    fn determine_room_type(&mut self, tiles: &Arc<RwLock<GameMapTiles>>) {
-        let entrances = self.find_room_entrances(tiles);
-        let num_entrances = entrances.len();
-        let mut rng = rand::thread_rng();
+    // Picks a type for the room. Special rooms are tried first, limited by
+    // `placed` which counts the ones already on the level.
+    pub fn determine_room_type<R: Rng>(
+        &mut self,
+        tiles: &TileGrid,
+        depth: u32,
+        placed: &HashMap<RoomType, usize>,
+        rng: &mut R,
+    ) {
+        // Prefabs and the spawn room keep what they are
+        if matches!(self.room_type, RoomType::Spawn)
+            || matches!(self.shape, RoomShape::Prefab { .. })
+        {
+            return;
+        }
+
+        let num_entrances = self.find_room_entrances(tiles).len();
+
+        // Caves have no straight walls for doors and furniture
+        if let RoomShape::Rectangle = self.shape {
+            let size = self.width.min(self.height);
+            let largest_side = self.width.max(self.height);
+            let special = SPECIAL_ROOMS.iter().find(|placement| {
+                (!placement.dead_end || num_entrances == 1)
+                    && size >= placement.min_size
+                    && largest_side <= placement.max_size
+                    && depth >= placement.min_depth
+                    && placed.get(&placement.room_type).copied().unwrap_or(0) < placement.per_level
+                    && rng.gen_bool(placement.chance)
+            });
+            if let Some(placement) = special {
+                self.room_type = placement.room_type;
+                return;
+            }
+        }
 
         // Define probabilities based on the number of entrances
         let (obelisk_chance, secret_chance, normal_chance) = if num_entrances == 1 {
@@ -112,223 +280,206 @@ impl Room {
         }
     }
 
-    fn place_obelisk(&self, tiles: &Arc<RwLock<GameMapTiles>>) {
-        let center = self.center();
-        let mut tiles_write = tiles.write().unwrap();
-        tiles_write[center.y][center.x] = Tile::Obelisk {
-            visible: false,
-            curse: true,
-            fov: 8,
-            damage_hp: 1,
-            reduce_fov_radius: 3,
-        };
-    }
-
-    fn place_secret(&self, tiles: &Arc<RwLock<GameMapTiles>>, is_secret_room: bool) {
-        let mut rng = rand::thread_rng();
-
-        // Find positions adjacent to columns where secrets can be placed
-        let mut potential_positions = Vec::new();
-
-        let x_start = self.location.x + 1;
-        let x_end = self.location.x + self.width - 1;
-        let y_start = self.location.y + 1;
-        let y_end = self.location.y + self.height - 1;
-
-        let tiles_read = tiles.read().unwrap();
-
-        // Scan the room for columns and collect adjacent floor positions
-        for y in y_start..y_end {
-            for x in x_start..x_end {
-                if let Tile::Column { .. } = tiles_read[y][x] {
-                    // Adjacent positions to the column
-                    let adjacent_positions = vec![
-                        (x.wrapping_sub(1), y),
-                        (x + 1, y),
-                        (x, y.wrapping_sub(1)),
-                        (x, y + 1),
-                    ];
-
-                    for &(ax, ay) in &adjacent_positions {
-                        if ax >= x_start && ax < x_end && ay >= y_start && ay < y_end {
-                            if let Tile::Floor { .. } = tiles_read[ay][ax] {
-                                potential_positions.push((ax, ay));
-                            }
-                        }
-                    }
+    // Puts everything a spawn table rolled into the room. Things that find no
+    // space, or no monster for the depth, are counted as skipped.
+    pub fn place_spawns<R: Rng>(
+        &self,
+        tiles: &mut TileGrid,
+        rolled: &[Spawn],
+        depth: u32,
+        rng: &mut R,
+    ) -> SpawnReport {
+        let mut report = SpawnReport::default();
+        report.add_room(self.room_type);
+
+        for &spawn in rolled {
+            let placed = match spawn {
+                Spawn::Monster | Spawn::Guardian | Spawn::Boss | Spawn::Named(_) => {
+                    self.place_monster(tiles, spawn, depth, rng)
                 }
+                Spawn::Secret(rarity) => self.place_secret(tiles, rarity, rng),
+                Spawn::Item(pick) => self.place_item(tiles, pick, rng),
+                Spawn::Obelisk => self
+                    .place_in_middle(
+                        tiles,
+                        Tile::Obelisk {
+                            visible: false,
+                            curse: true,
+                            fov: 8,
+                            damage_hp: 1,
+                            reduce_fov_radius: 3,
+                        },
+                        rng,
+                    )
+                    .map(|_| "obelisk".to_string()),
+                Spawn::Shrine => self
+                    .place_in_middle(
+                        tiles,
+                        Tile::Shrine {
+                            visible: false,
+                            used: false,
+                        },
+                        rng,
+                    )
+                    .map(|_| "shrine".to_string()),
+                Spawn::Sign => self.place_sign(tiles, rng),
+                Spawn::Pit => self.place_pit(tiles, rng),
+            };
+            match placed {
+                Some(name) => report.add_placed(name),
+                None => report.skipped += 1,
             }
         }
 
-        drop(tiles_read); // Release the read lock
+        report
+    }
 
-        // Determine the rarity of the secret
-        let rarity = if is_secret_room {
-            if rng.gen_bool(0.9) {
-                100
-            } else {
-                1000
+    fn place_monster<R: Rng>(
+        &self,
+        tiles: &mut TileGrid,
+        spawn: Spawn,
+        depth: u32,
+        rng: &mut R,
+    ) -> Option<String> {
+        let kind = match spawn {
+            Spawn::Named(kind) => kind,
+            Spawn::Guardian => {
+                monsters().random(depth, rng, |def| def.behaviour == Behaviour::Guard)?
             }
+            Spawn::Boss => {
+                let kind = monsters().random(depth, rng, |def| def.behaviour == Behaviour::Boss)?;
+                self.place_in_middle(tiles, Tile::monster(kind), rng)?;
+                return Some(monsters().get(kind).name.clone());
+            }
+            // Guards and bosses are saved for the rooms that ask for them
+            _ => monsters().random(depth, rng, |def| {
+                !matches!(def.behaviour, Behaviour::Guard | Behaviour::Boss)
+            })?,
+        };
+        let point = self.random_floor(tiles, rng, |_| true)?;
+        self.place_feature(tiles, point, Tile::monster(kind));
+        Some(monsters().get(kind).name.clone())
+    }
+
+    // The middle of the room if it's free, anywhere else otherwise
+    fn place_in_middle<R: Rng>(
+        &self,
+        tiles: &mut TileGrid,
+        tile: Tile,
+        rng: &mut R,
+    ) -> Option<Point> {
+        let center = self.center();
+        let point = if matches!(tiles.get(center), Some(Tile::Floor { .. })) {
+            center
         } else {
-            let rarity_values = [1, 10, 100];
-            *rarity_values.choose(&mut rng).unwrap()
+            self.random_floor(tiles, rng, |_| true)?
         };
+        self.place_feature(tiles, point, tile);
+        Some(point)
+    }
 
-        let mut tiles_write = tiles.write().unwrap();
+    fn place_item<R: Rng>(
+        &self,
+        tiles: &mut TileGrid,
+        pick: ItemPick,
+        rng: &mut R,
+    ) -> Option<String> {
+        let item = match pick {
+            ItemPick::Named(item) => item,
+            ItemPick::Random { category, rarity } => {
+                Item::random_of_category(category, rarity, rng)?
+            }
+        };
+        let point = self.random_floor(tiles, rng, |_| true)?;
+        self.place_feature(
+            tiles,
+            point,
+            Tile::Item {
+                visible: false,
+                item,
+            },
+        );
+        Some(item.name().to_string())
+    }
 
-        if !potential_positions.is_empty() {
-            // Place the secret behind a column
-            let &(x, y) = potential_positions.choose(&mut rng).unwrap();
-            tiles_write[y][x] = Tile::Secret {
+    fn place_sign<R: Rng>(&self, tiles: &mut TileGrid, rng: &mut R) -> Option<String> {
+        let point = self.random_floor(tiles, rng, |_| true)?;
+        let lore = lore().random(rng);
+        self.place_feature(
+            tiles,
+            point,
+            Tile::Sign {
                 visible: false,
-                rarity,
-            };
-        } else {
-            // If no columns exist, place the secret at a random floor position
-            let x = rng.gen_range(x_start..x_end);
-            let y = rng.gen_range(y_start..y_end);
-            tiles_write[y][x] = Tile::Secret {
+                lore,
+            },
+        );
+        Some("sign".to_string())
+    }
+
+    // Secrets like to hide next to columns
+    fn place_secret<R: Rng>(
+        &self,
+        tiles: &mut TileGrid,
+        rarity: u32,
+        rng: &mut R,
+    ) -> Option<String> {
+        let beside_column = |point: Point| {
+            around(point, &CARDINAL_OFFSETS)
+                .any(|neighbour| matches!(tiles.get(neighbour), Some(Tile::Column { .. })))
+        };
+        let point = self
+            .random_floor(tiles, rng, beside_column)
+            .or_else(|| self.random_floor(tiles, rng, |_| true))?;
+        self.place_feature(
+            tiles,
+            point,
+            Tile::Secret {
                 visible: false,
                 rarity,
-            };
-        }
+            },
+        );
+        Some(format!("secret {}", rarity))
     }
 
-    fn place_mobs(&self, tiles: &Arc<RwLock<GameMapTiles>>) {
-        let mut rng = rand::thread_rng();
-
-        // Decide the number of mobs to place, e.g., 1 to 3 mobs per room
-        let num_mobs = rng.gen_range(1..=3);
-
-        let mob_types = [MobType::Wither, MobType::Bat, MobType::Brute];
-
-        // Get the dimensions of the room
-        let x_start = self.location.x + 1;
-        let x_end = self.location.x + self.width - 1;
-        let y_start = self.location.y + 1;
-        let y_end = self.location.y + self.height - 1;
-
-        // Positions where mobs have been placed to avoid overlap
-        let mut occupied_positions = std::collections::HashSet::new();
-
-        // Lock the tiles for writing
-        let mut tiles_write = tiles.write().unwrap();
-
-        for _ in 0..num_mobs {
-            // Randomly select a mob type
-            let mob_type = mob_types[rng.gen_range(0..mob_types.len())].clone();
-
-            // Find a random position within the room that is not occupied
-            let mut attempts = 0;
-            let position = loop {
-                if attempts > 10 {
-                    // Avoid infinite loops in case the room is too full
-                    break None;
-                }
-                let x = rng.gen_range(x_start..x_end);
-                let y = rng.gen_range(y_start..y_end);
-
-                if occupied_positions.contains(&(x, y)) {
-                    attempts += 1;
-                    continue;
-                }
-
-                // Check if the tile is a floor and not occupied by other features
-                match tiles_write[y][x] {
-                    Tile::Floor { .. } => {
-                        occupied_positions.insert((x, y));
-                        break Some((x, y));
-                    }
-                    _ => {
-                        attempts += 1;
-                        continue;
-                    }
-                }
-            };
-
-            if let Some((x, y)) = position {
-                // Create the mob tile based on the selected mob type
-                let mob_tile = match mob_type {
-                    MobType::Wither => Tile::Wither {
-                        visible: false,
-                        hp: 50,     // Example HP
-                        damage: 10, // Example damage
-                        fov: 6,     // Example field of view
-                    },
-                    MobType::Bat => Tile::Bat {
-                        visible: false,
-                        hp: 20,
-                        damage: 5,
-                        fov: 8,
-                    },
-                    MobType::Brute => Tile::Brute {
-                        visible: false,
-                        hp: 80,
-                        damage: 15,
-                        fov: 4,
-                    },
-                };
-
-                // Place the mob on the map
-                tiles_write[y][x] = mob_tile;
-            }
-        }
+    // Pits only go where there's floor all around, so they never cut the room off
+    fn place_pit<R: Rng>(&self, tiles: &mut TileGrid, rng: &mut R) -> Option<String> {
+        let in_the_open = |point: Point| {
+            around(point, &ALL_OFFSETS)
+                .all(|neighbour| matches!(tiles.get(neighbour), Some(Tile::Floor { .. })))
+        };
+        let point = self.random_floor(tiles, rng, in_the_open)?;
+        self.place_feature(tiles, point, Tile::Pit { visible: false });
+        Some("pit".to_string())
     }
 
-    fn place_brute(&self, tiles: &Arc<RwLock<GameMapTiles>>) {
-        let mut rng = rand::thread_rng();
-        // Get the dimensions of the room
-        let x_start = self.location.x + 1;
-        let x_end = self.location.x + self.width - 1;
-        let y_start = self.location.y + 1;
-        let y_end = self.location.y + self.height - 1;
-        // Positions where mobs have been placed to avoid overlap
-        let mut occupied_positions = std::collections::HashSet::new();
-        // Lock the tiles for writing
-        let mut tiles_write = tiles.write().unwrap();
-        // Find a random position within the room that is not occupied
-        let mut attempts = 0;
-        let position = loop {
-            if attempts > 10 {
-                // Avoid infinite loops in case the room is too full
-                break None;
-            }
-            let x = rng.gen_range(x_start..x_end);
-            let y = rng.gen_range(y_start..y_end);
-            if occupied_positions.contains(&(x, y)) {
-                attempts += 1;
-                continue;
-            }
-            // Check if the tile is a floor and not occupied by other features
-            match tiles_write[y][x] {
-                Tile::Floor { .. } => {
-                    occupied_positions.insert((x, y));
-                    break Some((x, y));
-                }
-                _ => {
-                    attempts += 1;
-                    continue;
-                }
-            }
-        };
-        if let Some((x, y)) = position {
-            // Create the mob tile based on the selected mob type
-            let mob_tile = Tile::Brute {
-                visible: false,
-                hp: 20,
-                damage: 10,
-                fov: 4,
-            };
-            // Place the mob on the map
-            tiles_write[y][x] = mob_tile;
-        }
+    // A random free floor tile inside the room that passes `filter`. Stays a
+    // step away from the walls so nothing ends up plugging an entrance.
+    fn random_floor<R, F>(&self, tiles: &TileGrid, rng: &mut R, filter: F) -> Option<Point>
+    where
+        R: Rng,
+        F: Fn(Point) -> bool,
+    {
+        let candidates: Vec<Point> = self
+            .interior_points()
+            .filter(|point| {
+                point.x > self.location.x + 1
+                    && point.y > self.location.y + 1
+                    && point.x + 1 < self.location.x + self.width
+                    && point.y + 1 < self.location.y + self.height
+            })
+            .filter(|&point| matches!(tiles.get(point), Some(Tile::Floor { .. })))
+            .filter(|&point| filter(point))
+            .collect();
+        candidates.choose(rng).copied()
     }
 
-    fn find_room_entrances(&self, tiles: &Arc<RwLock<GameMapTiles>>) -> Vec<Point> {
-        let tiles_read = tiles.read().unwrap();
-        let max_y = tiles_read.len();
-        let max_x = if max_y > 0 { tiles_read[0].len() } else { 0 };
+    fn find_room_entrances(&self, tiles_read: &TileGrid) -> Vec<Point> {
+        let max_y = tiles_read.height();
+        let max_x = tiles_read.width();
         let mut entrances = Vec::new();
+        // Both are false outside the grid
+        let is_floor = |x, y| matches!(tiles_read.get(Point::new(x, y)), Some(Tile::Floor { .. }));
+        let is_wall = |x, y| matches!(tiles_read.get(Point::new(x, y)), Some(Tile::Wall { .. }));
 
         let left = self.location.x;
         let right = self.location.x + self.width;
@@ -338,28 +489,21 @@ impl Room {
         // Check top and bottom walls
         for x in left..=right {
             // Top wall
-            if top < max_y && x < max_x {
-                if let Tile::Floor { .. } = tiles_read[top][x] {
-                    // Check neighbors to the left and right
-                    let left_wall = x == 0 || matches!(tiles_read[top][x - 1], Tile::Wall { .. });
-                    let right_wall =
-                        x + 1 >= max_x || matches!(tiles_read[top][x + 1], Tile::Wall { .. });
-                    if left_wall && right_wall {
-                        entrances.push(Point::new(x, top));
-                    }
+            if is_floor(x, top) {
+                // Check neighbors to the left and right
+                let left_wall = x == 0 || is_wall(x - 1, top);
+                let right_wall = x + 1 >= max_x || is_wall(x + 1, top);
+                if left_wall && right_wall {
+                    entrances.push(Point::new(x, top));
                 }
             }
             // Bottom wall
-            if bottom < max_y && x < max_x {
-                if let Tile::Floor { .. } = tiles_read[bottom][x] {
-                    // Check neighbors to the left and right
-                    let left_wall =
-                        x == 0 || matches!(tiles_read[bottom][x - 1], Tile::Wall { .. });
-                    let right_wall =
-                        x + 1 >= max_x || matches!(tiles_read[bottom][x + 1], Tile::Wall { .. });
-                    if left_wall && right_wall {
-                        entrances.push(Point::new(x, bottom));
-                    }
+            if is_floor(x, bottom) {
+                // Check neighbors to the left and right
+                let left_wall = x == 0 || is_wall(x - 1, bottom);
+                let right_wall = x + 1 >= max_x || is_wall(x + 1, bottom);
+                if left_wall && right_wall {
+                    entrances.push(Point::new(x, bottom));
                 }
             }
         }
@@ -367,27 +511,21 @@ impl Room {
         // Check left and right walls
         for y in top..=bottom {
             // Left wall
-            if y < max_y && left < max_x {
-                if let Tile::Floor { .. } = tiles_read[y][left] {
-                    // Check neighbors above and below
-                    let top_wall = y == 0 || matches!(tiles_read[y - 1][left], Tile::Wall { .. });
-                    let bottom_wall =
-                        y + 1 >= max_y || matches!(tiles_read[y + 1][left], Tile::Wall { .. });
-                    if top_wall && bottom_wall {
-                        entrances.push(Point::new(left, y));
-                    }
+            if is_floor(left, y) {
+                // Check neighbors above and below
+                let top_wall = y == 0 || is_wall(left, y - 1);
+                let bottom_wall = y + 1 >= max_y || is_wall(left, y + 1);
+                if top_wall && bottom_wall {
+                    entrances.push(Point::new(left, y));
                 }
             }
             // Right wall
-            if y < max_y && right < max_x {
-                if let Tile::Floor { .. } = tiles_read[y][right] {
-                    // Check neighbors above and below
-                    let top_wall = y == 0 || matches!(tiles_read[y - 1][right], Tile::Wall { .. });
-                    let bottom_wall =
-                        y + 1 >= max_y || matches!(tiles_read[y + 1][right], Tile::Wall { .. });
-                    if top_wall && bottom_wall {
-                        entrances.push(Point::new(right, y));
-                    }
+            if is_floor(right, y) {
+                // Check neighbors above and below
+                let top_wall = y == 0 || is_wall(right, y - 1);
+                let bottom_wall = y + 1 >= max_y || is_wall(right, y + 1);
+                if top_wall && bottom_wall {
+                    entrances.push(Point::new(right, y));
                 }
             }
         }
@@ -395,79 +533,109 @@ impl Room {
         entrances
     }
 
-    pub fn place_doors(&self, tiles: &Arc<RwLock<GameMapTiles>>) {
-        let entrances = self.find_room_entrances(tiles);
+    pub fn place_doors<R: Rng>(&self, tiles: &mut TileGrid, rng: &mut R) {
+        // Prefab doors were placed with the template
+        if let RoomShape::Prefab { .. } = self.shape {
+            return;
+        }
 
-        let mut tiles_write = tiles.write().unwrap();
-        let mut rng = rand::thread_rng();
+        let entrances = self.find_room_entrances(tiles);
 
         for entrance in entrances {
-            // Randomly decide to place a door at this entrance (e.g., 50% chance)
-            if rng.gen_bool(0.5) {
-                tiles_write[entrance.y][entrance.x] = Tile::Door {
-                    visible: false,
-                    open: false,
-                };
+            // Randomly decide to place a door at this entrance (e.g., 50% chance),
+            // vaults are always shut
+            if self.room_type == RoomType::Vault || rng.gen_bool(0.5) {
+                tiles.set(
+                    entrance,
+                    Tile::Door {
+                        visible: false,
+                        open: false,
+                        lock: None,
+                    },
+                );
             }
         }
     }
 
     pub fn center(&self) -> Point {
+        if let RoomShape::Prefab { anchor, .. } = self.shape {
+            return anchor;
+        }
         Point::new(
             self.location.x + self.width / 2,
             self.location.y + self.height / 2,
         )
     }
 
-    fn fill_with_floor(&self, tiles_arc: &Arc<RwLock<GameMapTiles>>) {
-        let tiles_read = tiles_arc.read().unwrap();
-        let max_y = tiles_read.len();
-        let max_x = tiles_read.first().map_or(0, |row| row.len());
-        drop(tiles_read); // Release the read lock
-
-        let mut tiles_write = tiles_arc.write().unwrap();
-        for y in self.location.y..self.location.y.saturating_add(self.height) {
-            for x in self.location.x..self.location.x.saturating_add(self.width) {
-                if y < max_y && x < max_x {
-                    tiles_write[y][x] = Tile::Floor {
-                        visible: false,
-                        cursed: false,
-                    };
-                }
+    // Every tile inside the room's walls
+    pub fn interior_points(&self) -> impl Iterator<Item = Point> + '_ {
+        (self.location.y + 1..self.location.y + self.height).flat_map(move |y| {
+            (self.location.x + 1..self.location.x + self.width).map(move |x| Point::new(x, y))
+        })
+    }
+
+    // Inside the room's walls
+    pub fn contains_interior(&self, point: Point) -> bool {
+        point.x > self.location.x
+            && point.y > self.location.y
+            && point.x < self.location.x + self.width
+            && point.y < self.location.y + self.height
+    }
+
+    // Features only ever land inside the room's walls, and never off the map
+    fn place_feature(&self, tiles: &mut TileGrid, point: Point, tile: Tile) {
+        if self.contains_interior(point) {
+            tiles.set(point, tile);
+        }
+    }
+
+    // Every tile the room covers, walls included
+    pub fn footprint(&self) -> impl Iterator<Item = Point> + '_ {
+        (self.location.y..=self.location.y + self.height).flat_map(move |y| {
+            (self.location.x..=self.location.x + self.width).map(move |x| Point::new(x, y))
+        })
+    }
+
+    fn fill_with_floor(&self, tiles: &mut TileGrid) {
+        for row in tiles.rows_mut().skip(self.location.y).take(self.height) {
+            for tile in row.iter_mut().skip(self.location.x).take(self.width) {
+                *tile = Tile::Floor {
+                    visible: false,
+                    cursed: false,
+                };
             }
         }
     }
 
-    fn surround_with_walls(&self, tiles_arc: &Arc<RwLock<GameMapTiles>>) {
-        let tiles_read = tiles_arc.read().unwrap();
-        let max_y = tiles_read.len();
-        let max_x = tiles_read.first().map_or(0, |row| row.len());
-        drop(tiles_read); // Release the read lock
-
-        let mut tiles_write = tiles_arc.write().unwrap();
-
-        for y in self.location.y..=self.location.y.saturating_add(self.height) {
-            for x in self.location.x..=self.location.x.saturating_add(self.width) {
-                if y < max_y
-                    && x < max_x
-                    && (y == self.location.y
-                        || y == self.location.y.saturating_add(self.height)
-                        || x == self.location.x
-                        || x == self.location.x.saturating_add(self.width))
-                {
-                    tiles_write[y][x] = Tile::Wall { visible: false };
+    fn surround_with_walls(&self, tiles: &mut TileGrid) {
+        let right = self.location.x.saturating_add(self.width);
+        let bottom = self.location.y.saturating_add(self.height);
+
+        for (y, row) in tiles
+            .rows_mut()
+            .enumerate()
+            .skip(self.location.y)
+            .take(self.height + 1)
+        {
+            for (x, tile) in row
+                .iter_mut()
+                .enumerate()
+                .skip(self.location.x)
+                .take(self.width + 1)
+            {
+                if y == self.location.y || y == bottom || x == self.location.x || x == right {
+                    *tile = Tile::Wall { visible: false };
                 }
             }
         }
     }
 
-    fn place_columns(&self, tiles_arc: &Arc<RwLock<GameMapTiles>>) {
-        let mut rng = rand::thread_rng();
-
-        // 50% chance to place columns in the corners of the room
-        if rng.gen_bool(0.5) {
-            let mut tiles_write = tiles_arc.write().unwrap();
-
+    fn place_columns<R: Rng>(&self, tiles: &mut TileGrid, rng: &mut R) {
+        // 50% chance to place columns in the corners of the room, small rooms have no space for them
+        if self.width >= Self::MIN_SIZE_FOR_COLUMNS
+            && self.height >= Self::MIN_SIZE_FOR_COLUMNS
+            && rng.gen_bool(0.5)
+        {
             let x1 = self.location.x + 2;
             let x2 = self.location.x + self.width - 2;
             let y1 = self.location.y + 2;
@@ -477,27 +645,7 @@ impl Room {
             let positions = vec![(x1, y1), (x2, y1), (x1, y2), (x2, y2)];
 
             for &(x, y) in &positions {
-                tiles_write[y][x] = Tile::Column { visible: false };
-            }
-        }
-    }
-
-    pub fn reset(&self, tiles_arc: &Arc<RwLock<GameMapTiles>>) {
-        // Flood fill the room inside the walls with floor tiles
-        let tiles_read = tiles_arc.read().unwrap();
-        let max_y = tiles_read.len();
-        let max_x = tiles_read.first().map_or(0, |row| row.len());
-        drop(tiles_read); // Release the read lock
-                          // Lock the tiles for writing
-        let mut tiles_write = tiles_arc.write().unwrap();
-        for y in self.location.y + 1..self.location.y + self.height {
-            for x in self.location.x + 1..self.location.x + self.width {
-                if y < max_y && x < max_x {
-                    tiles_write[y][x] = Tile::Floor {
-                        visible: false,
-                        cursed: false,
-                    };
-                }
+                self.place_feature(tiles, Point::new(x, y), Tile::Column { visible: false });
             }
         }
     }
@@ -0,0 +1,230 @@
+use crate::generator::map::MapGenerator;
+use crate::generator::room::RoomType;
+use crate::tile::Tile;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+// What one generated level came out like, to see the effect of tuning the generator
+#[derive(Clone, Debug)]
+pub struct LevelStats {
+    pub seed: u64,
+    pub rooms: usize,
+    pub room_types: BTreeMap<&'static str, usize>,
+    pub empty_leaves: usize, // BSP leaves too small or unlucky to get a room
+    pub floor_coverage: f64, // share of the map that is floor
+    pub corridors: usize,
+    pub corridor_length: usize, // tiles dug for all corridors together
+    pub monsters: usize,
+    pub skipped_spawns: usize, // spawns that were rolled but found no space or no monster
+    pub repaired_rooms: usize,
+    pub attempts: usize,
+    pub connected: bool, // every room can be reached from spawn
+    pub time: Duration,
+}
+
+impl LevelStats {
+    // `time` is how long `generate` took
+    pub fn measure(map_generator: &MapGenerator, time: Duration) -> Self {
+        let dungeon = map_generator.get_dungeon();
+        let structure = map_generator.get_structure();
+        let connectivity = map_generator.get_connectivity_report();
+
+        let mut room_types = BTreeMap::new();
+        for info in &structure.rooms {
+            *room_types.entry(info.room.room_type.name()).or_default() += 1;
+        }
+
+        let cells = dungeon.width() * dungeon.height();
+        let floor = dungeon
+            .points()
+            .filter(|&point| matches!(dungeon.get(point), Some(Tile::Floor { .. })));
+
+        LevelStats {
+            seed: map_generator.get_seed(),
+            rooms: structure.rooms.len(),
+            room_types,
+            empty_leaves: structure.bsp.as_ref().map_or(0, |root| {
+                root.leaves()
+                    .iter()
+                    .filter(|leaf| leaf.room.is_none())
+                    .count()
+            }),
+            floor_coverage: floor.count() as f64 / cells.max(1) as f64,
+            corridors: structure.corridors.len(),
+            corridor_length: structure
+                .corridors
+                .iter()
+                .map(|corridor| corridor.length)
+                .sum(),
+            monsters: dungeon
+                .points()
+                .filter(|&point| dungeon.get(point).is_some_and(|tile| tile.is_monster()))
+                .count(),
+            skipped_spawns: map_generator.get_spawn_report().skipped,
+            repaired_rooms: connectivity.repaired_rooms,
+            attempts: connectivity.attempts,
+            connected: connectivity.is_valid(),
+            time,
+        }
+    }
+}
+
+// Mean, smallest, largest and total of one measure over a batch
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Summary {
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub total: f64,
+}
+
+impl Summary {
+    fn of(values: impl Iterator<Item = f64>) -> Self {
+        let mut count = 0;
+        let mut summary = Summary {
+            mean: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            total: 0.0,
+        };
+        for value in values {
+            count += 1;
+            summary.total += value;
+            summary.min = summary.min.min(value);
+            summary.max = summary.max.max(value);
+        }
+        if count == 0 {
+            return Summary {
+                mean: 0.0,
+                min: 0.0,
+                max: 0.0,
+                total: 0.0,
+            };
+        }
+        summary.mean = summary.total / count as f64;
+        summary
+    }
+}
+
+// Statistics over many generated levels
+#[derive(Clone, Debug, Default)]
+pub struct BatchStats {
+    levels: Vec<LevelStats>,
+}
+
+impl BatchStats {
+    pub fn new() -> Self {
+        BatchStats::default()
+    }
+
+    pub fn add(&mut self, level: LevelStats) {
+        self.levels.push(level);
+    }
+
+    pub fn get_levels(&self) -> &[LevelStats] {
+        &self.levels
+    }
+
+    pub fn connectivity_failures(&self) -> usize {
+        self.levels.iter().filter(|level| !level.connected).count()
+    }
+
+    // Every measure by name, room types as `type <name>` in the order they're declared
+    pub fn summaries(&self) -> Vec<(String, Summary)> {
+        let measure = |get: &dyn Fn(&LevelStats) -> f64| Summary::of(self.levels.iter().map(get));
+
+        let mut summaries = vec![
+            ("rooms".to_string(), measure(&|level| level.rooms as f64)),
+            (
+                "empty BSP leaves".to_string(),
+                measure(&|level| level.empty_leaves as f64),
+            ),
+        ];
+        for room_type in [RoomType::Spawn].into_iter().chain(RoomType::POPULATED) {
+            let name = room_type.name();
+            summaries.push((
+                format!("type {}", name),
+                measure(&|level| level.room_types.get(name).copied().unwrap_or(0) as f64),
+            ));
+        }
+        summaries.extend([
+            (
+                "floor coverage %".to_string(),
+                measure(&|level| level.floor_coverage * 100.0),
+            ),
+            (
+                "corridors".to_string(),
+                measure(&|level| level.corridors as f64),
+            ),
+            (
+                "corridor length".to_string(),
+                measure(&|level| level.corridor_length as f64),
+            ),
+            (
+                "monsters".to_string(),
+                measure(&|level| level.monsters as f64),
+            ),
+            (
+                "skipped spawns".to_string(),
+                measure(&|level| level.skipped_spawns as f64),
+            ),
+            (
+                "repaired rooms".to_string(),
+                measure(&|level| level.repaired_rooms as f64),
+            ),
+            (
+                "attempts".to_string(),
+                measure(&|level| level.attempts as f64),
+            ),
+            (
+                "connectivity failures".to_string(),
+                measure(&|level| if level.connected { 0.0 } else { 1.0 }),
+            ),
+            (
+                "generation ms".to_string(),
+                measure(&|level| level.time.as_secs_f64() * 1000.0),
+            ),
+        ]);
+        summaries
+    }
+
+    // Lined up for reading in a terminal
+    pub fn to_table(&self) -> String {
+        let summaries = self.summaries();
+        let width = summaries
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or(0);
+
+        let mut table = format!(
+            "{} levels, {} failed connectivity\n\n{:<width$} {:>10} {:>10} {:>10} {:>12}\n",
+            self.levels.len(),
+            self.connectivity_failures(),
+            "measure",
+            "mean",
+            "min",
+            "max",
+            "total",
+        );
+        for (name, summary) in summaries {
+            table.push_str(&format!(
+                "{:<width$} {:>10.2} {:>10.2} {:>10.2} {:>12.2}\n",
+                name, summary.mean, summary.min, summary.max, summary.total,
+            ));
+        }
+        table
+    }
+
+    // One line per measure, for spreadsheets and scripts
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("measure,mean,min,max,total\n");
+        for (name, summary) in self.summaries() {
+            csv.push_str(&format!(
+                "{},{:.4},{:.4},{:.4},{:.4}\n",
+                name, summary.mean, summary.min, summary.max, summary.total,
+            ));
+        }
+        csv
+    }
+}
@@ -0,0 +1,213 @@
+use crate::generator::connectivity::{neighbours, reachability_cost};
+use crate::generator::layout::bsp::BSPNode;
+use crate::generator::room::Room;
+use crate::map::grid::TileGrid;
+use crate::map::types::Point;
+use crate::tile::Tile;
+use std::collections::HashMap;
+
+// What a generated level is made of beyond its tiles: the rooms and the ways
+// between them, for anything that needs to reason about the layout
+#[derive(Clone, Default)]
+pub struct LevelStructure {
+    pub rooms: Vec<RoomInfo>,
+    pub spawn_room: Option<usize>,
+    pub exit: Option<Point>,
+    pub bsp: Option<BSPNode>, // only for the layouts built on a BSP tree
+    pub corridors: Vec<Corridor>,
+    pub adjacency: Vec<Vec<usize>>, // room ids you can walk to without crossing another room
+}
+
+#[derive(Clone)]
+pub struct RoomInfo {
+    pub id: usize, // the room's index in `LevelStructure::rooms`
+    pub room: Room,
+    pub entrances: Vec<Point>,        // ways in through the room's walls
+    pub features: Vec<(Point, Tile)>, // anything inside that isn't bare floor
+}
+
+// A dug passage as a polyline, the points where it starts, turns and ends
+#[derive(Clone, Debug, PartialEq)]
+pub struct Corridor {
+    pub points: Vec<Point>,
+    pub length: usize, // tiles dug
+}
+
+impl Corridor {
+    pub fn from_path(path: &[Point]) -> Self {
+        let mut tiles: Vec<Point> = path.to_vec();
+        tiles.dedup();
+
+        let mut points = Vec::new();
+        for (index, &point) in tiles.iter().enumerate() {
+            let is_corner = match (index.checked_sub(1), tiles.get(index + 1)) {
+                (Some(before), Some(&after)) => {
+                    direction(tiles[before], point) != direction(point, after)
+                }
+                _ => true,
+            };
+            if is_corner {
+                points.push(point);
+            }
+        }
+
+        Corridor {
+            points,
+            length: tiles.len(),
+        }
+    }
+}
+
+fn direction(from: Point, to: Point) -> (i64, i64) {
+    (
+        (to.x as i64 - from.x as i64).signum(),
+        (to.y as i64 - from.y as i64).signum(),
+    )
+}
+
+impl LevelStructure {
+    pub fn new(
+        tiles: &TileGrid,
+        rooms: &[Room],
+        spawn_room: Option<usize>,
+        exit: Option<Point>,
+        bsp: Option<BSPNode>,
+        corridor_paths: &[Vec<Point>],
+    ) -> Self {
+        let rooms_info = rooms
+            .iter()
+            .enumerate()
+            .map(|(id, room)| RoomInfo {
+                id,
+                room: room.clone(),
+                entrances: entrances(tiles, room),
+                features: features(tiles, room),
+            })
+            .collect();
+
+        LevelStructure {
+            rooms: rooms_info,
+            spawn_room,
+            exit,
+            bsp,
+            corridors: corridor_paths
+                .iter()
+                .filter(|path| !path.is_empty())
+                .map(|path| Corridor::from_path(path))
+                .collect(),
+            adjacency: adjacency(tiles, rooms),
+        }
+    }
+
+    pub fn get_room(&self, id: usize) -> Option<&RoomInfo> {
+        self.rooms.get(id)
+    }
+
+    pub fn get_spawn_room(&self) -> Option<&RoomInfo> {
+        self.spawn_room.and_then(|id| self.get_room(id))
+    }
+
+    pub fn neighbours(&self, id: usize) -> &[usize] {
+        self.adjacency.get(id).map_or(&[], Vec::as_slice)
+    }
+
+    // The room a point lies in, walls included
+    pub fn room_at(&self, point: Point) -> Option<&RoomInfo> {
+        self.rooms.iter().find(|info| covers(&info.room, point))
+    }
+}
+
+fn is_passable(point: Point, tile: Tile) -> bool {
+    reachability_cost(point, tile).is_some()
+}
+
+fn covers(room: &Room, point: Point) -> bool {
+    (room.location.x..=room.location.x + room.width).contains(&point.x)
+        && (room.location.y..=room.location.y + room.height).contains(&point.y)
+}
+
+// Open cells on the room's edge with open space just outside it
+fn entrances(tiles: &TileGrid, room: &Room) -> Vec<Point> {
+    let (width, height) = (tiles.width(), tiles.height());
+    room.footprint()
+        .filter(|&point| !room.contains_interior(point))
+        .filter(|&point| {
+            tiles
+                .get(point)
+                .is_some_and(|tile| is_passable(point, tile))
+        })
+        .filter(|&point| {
+            neighbours(point, width, height).any(|next| {
+                !covers(room, next) && tiles.get(next).is_some_and(|tile| is_passable(next, tile))
+            })
+        })
+        .collect()
+}
+
+fn features(tiles: &TileGrid, room: &Room) -> Vec<(Point, Tile)> {
+    room.interior_points()
+        .filter_map(|point| tiles.get(point).map(|tile| (point, tile)))
+        .filter(|(_, tile)| !matches!(tile, Tile::Floor { .. } | Tile::Wall { .. } | Tile::Empty))
+        .collect()
+}
+
+// Rooms are adjacent when their open cells touch, or when the same stretch of
+// open cells outside every room reaches both of them
+fn adjacency(tiles: &TileGrid, rooms: &[Room]) -> Vec<Vec<usize>> {
+    let (width, height) = (tiles.width(), tiles.height());
+    let owner = |point: Point| rooms.iter().position(|room| covers(room, point));
+    let open = |point: Point| {
+        tiles
+            .get(point)
+            .is_some_and(|tile| is_passable(point, tile))
+    };
+
+    // Label the open cells outside rooms by the stretch they belong to
+    let mut stretches: HashMap<Point, usize> = HashMap::new();
+    let mut stretch_count = 0;
+    for start in tiles.points() {
+        if !open(start) || owner(start).is_some() || stretches.contains_key(&start) {
+            continue;
+        }
+        stretches.insert(start, stretch_count);
+        let mut stack = vec![start];
+        while let Some(point) = stack.pop() {
+            for next in neighbours(point, width, height) {
+                if open(next) && owner(next).is_none() && !stretches.contains_key(&next) {
+                    stretches.insert(next, stretch_count);
+                    stack.push(next);
+                }
+            }
+        }
+        stretch_count += 1;
+    }
+
+    let mut adjacency = vec![Vec::new(); rooms.len()];
+    let mut touching = vec![Vec::new(); stretch_count];
+    for (id, room) in rooms.iter().enumerate() {
+        for point in room.footprint().filter(|&point| open(point)) {
+            for next in neighbours(point, width, height).filter(|&next| open(next)) {
+                if let Some(&stretch) = stretches.get(&next) {
+                    touching[stretch].push(id);
+                } else if let Some(other) = owner(next).filter(|_| !covers(room, next)) {
+                    adjacency[id].push(other);
+                    adjacency[other].push(id);
+                }
+            }
+        }
+    }
+
+    for mut ids in touching {
+        ids.sort_unstable();
+        ids.dedup();
+        for &a in &ids {
+            adjacency[a].extend(ids.iter().copied().filter(|&b| b != a));
+        }
+    }
+
+    for ids in &mut adjacency {
+        ids.sort_unstable();
+        ids.dedup();
+    }
+    adjacency
+}
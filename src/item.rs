@@ -0,0 +1,152 @@
+use crate::data::item::ItemDef;
+use crate::data::items;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+
+// How many items the player can carry, one per number key
+pub const INVENTORY_SIZE: usize = 10;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ItemCategory {
+    Potion,   // quaffed
+    Scroll,   // read
+    Key,      // opens the lock it belongs to, never hidden in secrets
+    Artefact, // what some levels send the player looking for
+}
+
+// An item from the item definitions, see `data/items.txt`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Item(u8);
+
+impl Item {
+    pub(crate) fn from_index(index: u8) -> Self {
+        Item(index)
+    }
+
+    pub(crate) fn index(&self) -> usize {
+        self.0 as usize
+    }
+
+    pub fn def(&self) -> &'static ItemDef {
+        items().get(*self)
+    }
+
+    pub fn category(&self) -> ItemCategory {
+        self.def().category
+    }
+
+    pub fn name(&self) -> &'static str {
+        &self.def().name
+    }
+
+    // Potions and scrolls turn up in secrets, keys and artefacts are put in
+    // place by the generator
+    pub fn is_loot(&self) -> bool {
+        matches!(self.category(), ItemCategory::Potion | ItemCategory::Scroll)
+    }
+
+    // Something to find in a secret, rarer secrets can hold rarer items
+    pub fn random_for_rarity<R: Rng>(rarity: u32, rng: &mut R) -> Item {
+        let candidates: Vec<Item> = items()
+            .all()
+            .filter(|item| item.is_loot() && item.def().rarity <= rarity)
+            .collect();
+        match candidates.choose(rng) {
+            Some(&item) => item,
+            // Every item book has something of rarity 1
+            None => items().all().find(|item| item.def().rarity == 1).unwrap(),
+        }
+    }
+
+    // Like `random_for_rarity` but only of one category, there might be none
+    pub fn random_of_category<R: Rng>(
+        category: ItemCategory,
+        rarity: u32,
+        rng: &mut R,
+    ) -> Option<Item> {
+        let candidates: Vec<Item> = items()
+            .all()
+            .filter(|item| item.category() == category && item.def().rarity <= rarity)
+            .collect();
+        candidates.choose(rng).copied()
+    }
+}
+
+pub const POTION_APPEARANCES: [&str; 12] = [
+    "murky", "bubbling", "violet", "smoky", "golden", "icy", "crimson", "milky", "fizzy", "oily",
+    "glowing", "inky",
+];
+pub const SCROLL_LABELS: [&str; 12] = [
+    "ZELGO MER",
+    "FOOBIE BLETCH",
+    "XIXAXA XOXAXA",
+    "ELBIB YLOH",
+    "VERR YED HORRE",
+    "THARR",
+    "NR 9",
+    "PRATYAVAYAH",
+    "KERNOD WEL",
+    "ANDOVA BEGARIN",
+    "JUYED AWK YACC",
+    "DAIYEN FOOELS",
+];
+
+// Unidentified items go by an appearance that's shuffled per seed until the
+// player uses one of them
+pub struct ItemNames {
+    appearances: HashMap<Item, String>,
+    identified: HashSet<Item>,
+}
+
+impl ItemNames {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut potions = POTION_APPEARANCES.to_vec();
+        let mut scrolls = SCROLL_LABELS.to_vec();
+        potions.shuffle(&mut rng);
+        scrolls.shuffle(&mut rng);
+
+        let mut potions = potions.into_iter();
+        let mut scrolls = scrolls.into_iter();
+        let appearances = items()
+            .all()
+            .map(|item| {
+                let appearance = match item.category() {
+                    ItemCategory::Potion => format!("{} potion", potions.next().unwrap_or("odd")),
+                    ItemCategory::Scroll => {
+                        format!("scroll labeled {}", scrolls.next().unwrap_or("?"))
+                    }
+                    // Keys and artefacts are plain to see
+                    ItemCategory::Key | ItemCategory::Artefact => item.name().to_string(),
+                };
+                (item, appearance)
+            })
+            .collect();
+
+        ItemNames {
+            appearances,
+            identified: HashSet::new(),
+        }
+    }
+
+    pub fn identify(&mut self, item: Item) {
+        self.identified.insert(item);
+    }
+
+    pub fn is_identified(&self, item: Item) -> bool {
+        self.identified.contains(&item)
+    }
+
+    pub fn display_name(&self, item: Item) -> String {
+        if self.is_identified(item) {
+            item.name().to_string()
+        } else {
+            self.appearances
+                .get(&item)
+                .cloned()
+                .unwrap_or_else(|| item.name().to_string())
+        }
+    }
+}
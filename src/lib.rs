@@ -0,0 +1,13 @@
+pub mod cli;
+pub mod data;
+pub mod effect;
+pub mod game;
+pub mod generator;
+pub mod item;
+pub mod map;
+pub mod objective;
+pub mod player;
+pub mod scores;
+// pub mod sdl;
+pub mod tile;
+pub mod tui;
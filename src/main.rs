@@ -1,47 +1,220 @@
-mod game;
-mod generator;
-mod map;
-mod player;
-// mod sdl;
-mod tile;
-mod tui;
-
-use game::Game;
-use generator::map::MapGenerator;
-// use sdl::SDL;
+use rustyrogue::cli::{self, Command, GenerateOptions, StatsOptions, USAGE};
+use rustyrogue::data::{GameData, DATA_DIRECTORY};
+use rustyrogue::game::{Game, GameSetup, Replay, SAVE_FILE};
+use rustyrogue::generator::layout::LayoutKind;
+use rustyrogue::generator::map::MapGenerator;
+use rustyrogue::generator::prefab::{PrefabLibrary, PREFAB_DIRECTORY};
+use rustyrogue::generator::stats::{BatchStats, LevelStats};
+use rustyrogue::map::hints::write_map;
+use rustyrogue::map::Map;
+use rustyrogue::scores::{HighScores, SCORES_FILE};
+use rustyrogue::tui::menu::{MainMenu, MenuChoice};
+use rustyrogue::tui::widgets::map_view::{MapView, Reseed, ViewedLevel};
+use rustyrogue::tui::Tui;
 use std::env;
+use std::fs;
 use std::io;
-use tui::widgets::map_view::MapView;
-use tui::Tui;
+use std::path::Path;
+use std::process;
+use std::time::Instant;
 
 fn main() -> Result<(), io::Error> {
-    // Parse command-line arguments
-    let args: Vec<String> = env::args().collect();
-    // let use_sdl = args.contains(&"--sdl".to_string());
-    let use_generator = args.contains(&"--generate".to_string());
-
-    // Create game instance
-    let mut game = Game::new()?;
-
-    // if use_sdl {
-    //     // Run the game with SDL renderer
-    //     let mut sdl = SDL::new()?;
-    //     sdl.run(&mut game)?;
-    // } else
-
-    if use_generator {
-        let mut map_generator = MapGenerator::new(300, 120);
-        map_generator.generate(5, 20);
-        let dungeon = map_generator.get_dungeon();
-        // build tooling if you dont have it
-        let mut map_view = MapView::new()?;
-        map_view.run(dungeon)?;
-    // Print
-    } else {
-        // Run the game with Tui renderer (default)
-        let mut tui = Tui::new()?;
-        tui.run(&mut game)?;
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = match cli::parse(&args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    match command {
+        Command::Help => {
+            print!("{}", USAGE);
+            return Ok(());
+        }
+        Command::Scores => {
+            for line in HighScores::load(SCORES_FILE)?.lines() {
+                println!("{}", line);
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    // Monster and item definitions have to be in place before any map is built
+    GameData::load(DATA_DIRECTORY)?.install()?;
+
+    match command {
+        Command::Menu => play(GameSetup::default(), None),
+        Command::Play(setup) => {
+            let game = setup.start()?;
+            play(setup, Some(game))
+        }
+        Command::Load(path) => {
+            let replay = Replay::load(&path)?;
+            let game = replay.resume()?;
+            play(replay.setup, Some(game))
+        }
+        Command::Generate(options) => generate(&options),
+        Command::Stats(options) => stats(&options),
+        Command::View(path) => view_map_file(&path),
+        Command::Replay(path) => Tui::new()?.replay(&Replay::load(&path)?),
+        Command::Help | Command::Scores => Ok(()),
     }
+}
 
+// The title menu, starting with `game` when the command line asked for a run.
+// A run that is left unfinished can be continued until it's over, and is
+// saved when the game is closed.
+fn play(setup: GameSetup, mut game: Option<Game>) -> Result<(), io::Error> {
+    let mut menu = MainMenu::new(setup);
+    let mut tui = Tui::new()?;
+    loop {
+        if let Some(current) = game.as_mut() {
+            tui.run(current)?;
+            if current.is_game_over() {
+                game = None;
+                remove_save()?;
+            }
+        }
+
+        let can_continue = game.is_some() || Path::new(SAVE_FILE).exists();
+        match tui.main_menu(&mut menu, can_continue)? {
+            MenuChoice::Play(setup) => match setup.start() {
+                Ok(new_game) => game = Some(new_game),
+                Err(e) => menu.set_message(format!("Couldn't start the game: {}", e)),
+            },
+            MenuChoice::Continue if game.is_none() => {
+                match Replay::load(SAVE_FILE).and_then(|replay| replay.resume()) {
+                    Ok(saved) => game = Some(saved),
+                    Err(e) => menu.set_message(format!("Couldn't load the saved game: {}", e)),
+                }
+            }
+            MenuChoice::Continue => {}
+            MenuChoice::ViewDungeon => {
+                // The viewer takes over the terminal while it's open
+                drop(tui);
+                // Shows a level as the setup would build it
+                let setup = menu.get_setup();
+                generate(&GenerateOptions {
+                    seed: setup.seed,
+                    layout: setup.layout,
+                    size: setup.size,
+                    depth: 1,
+                    out: None,
+                })?;
+                tui = Tui::new()?;
+            }
+            MenuChoice::Quit => {
+                if let Some(current) = game.as_ref().filter(|game| game.can_replay()) {
+                    current.get_replay().save(SAVE_FILE)?;
+                }
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn remove_save() -> Result<(), io::Error> {
+    match fs::remove_file(SAVE_FILE) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+// Builds a single level and either writes it out or opens it in the viewer
+fn generate(options: &GenerateOptions) -> Result<(), io::Error> {
+    let mut seed = options.seed.unwrap_or_else(rand::random);
+    let map_generator = generate_level(options, seed)?;
+
+    if map_generator.get_connectivity_report().gave_up {
+        eprintln!(
+            "Seed {} gave a level with rooms that can't be reached: {}",
+            seed,
+            map_generator.get_connectivity_report().summary()
+        );
+    }
+
+    if let Some(path) = &options.out {
+        write_map(map_generator.get_dungeon(), path)?;
+        println!("Wrote {} (seed {})", path.display(), seed);
+        return Ok(());
+    }
+
+    let mut map_view = MapView::new()?;
+    map_view.run(ViewedLevel::from_generator(&map_generator), |reseed| {
+        if reseed == Reseed::New {
+            seed = rand::random();
+        }
+        generate_level(options, seed)
+            .map(|map_generator| Some(ViewedLevel::from_generator(&map_generator)))
+    })
+}
+
+// Prefabs are read again every time, so edits show up when regenerating
+fn generate_level(options: &GenerateOptions, seed: u64) -> Result<MapGenerator, io::Error> {
+    let size = options.size;
+    let mut map_generator = MapGenerator::new(size.width, size.height);
+    map_generator
+        .set_prefabs(PrefabLibrary::load_or_builtin(PREFAB_DIRECTORY)?)
+        .set_layout(
+            options
+                .layout
+                .unwrap_or_else(|| LayoutKind::for_depth(options.depth)),
+        )
+        .set_seed(seed)
+        .set_depth(options.depth)
+        .generate(size.min_room_size, size.max_room_size);
+    Ok(map_generator)
+}
+
+// Levels one after another, so each one's generation time is its own
+fn stats(options: &StatsOptions) -> Result<(), io::Error> {
+    let size = options.size;
+    let first_seed = options.seed.unwrap_or_else(rand::random);
+    let prefabs = PrefabLibrary::load_or_builtin(PREFAB_DIRECTORY)?;
+    let layout = options
+        .layout
+        .unwrap_or_else(|| LayoutKind::for_depth(options.depth));
+
+    let mut batch = BatchStats::new();
+    for seed in (0..options.count as u64).map(|offset| first_seed.wrapping_add(offset)) {
+        let mut map_generator = MapGenerator::new(size.width, size.height);
+        map_generator
+            .set_prefabs(prefabs.clone())
+            .set_layout(layout)
+            .set_seed(seed)
+            .set_depth(options.depth);
+        let started = Instant::now();
+        map_generator.generate(size.min_room_size, size.max_room_size);
+        batch.add(LevelStats::measure(&map_generator, started.elapsed()));
+    }
+
+    if options.csv {
+        print!("{}", batch.to_csv());
+    } else {
+        println!(
+            "{} layout, {}x{}, depth {}, seeds {} to {}\n",
+            layout.name(),
+            size.width,
+            size.height,
+            options.depth,
+            first_seed,
+            first_seed.wrapping_add(options.count as u64 - 1)
+        );
+        print!("{}", batch.to_table());
+    }
     Ok(())
 }
+
+// Regenerating reads the file again, to see edits to it
+fn view_map_file(path: &Path) -> Result<(), io::Error> {
+    let load = || -> Result<ViewedLevel, io::Error> {
+        let map = Map::load(&path.to_string_lossy())?;
+        Ok(ViewedLevel::from_map(&map, path.display().to_string()))
+    };
+    let level = load()?;
+    let mut map_view = MapView::new()?;
+    map_view.run(level, |_| load().map(Some))
+}
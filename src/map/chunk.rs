@@ -0,0 +1,215 @@
+use crate::generator::chunk::ChunkGenerator;
+use crate::map::error::MapError;
+use crate::map::grid::TileGrid;
+use crate::map::hints::{read_map, write_map};
+use crate::map::types::{Coordinate, Point};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const CHUNK_SIZE: Coordinate = 64;
+// Not quite infinite, but you'd have to walk for a very long time to find the edge
+pub const WORLD_SIZE_IN_CHUNKS: Coordinate = 1024;
+pub const WORLD_SIZE: Coordinate = CHUNK_SIZE * WORLD_SIZE_IN_CHUNKS;
+
+// Chunks this close to the player (in chunks) are kept in memory,
+// the gap between the two stops chunks from flickering in and out at a border
+const LOAD_RADIUS: Coordinate = 1;
+const UNLOAD_RADIUS: Coordinate = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ChunkCoord {
+    pub x: Coordinate,
+    pub y: Coordinate,
+}
+
+impl ChunkCoord {
+    pub fn new(x: Coordinate, y: Coordinate) -> Self {
+        ChunkCoord { x, y }
+    }
+
+    pub fn containing(point: Point) -> Self {
+        ChunkCoord::new(point.x / CHUNK_SIZE, point.y / CHUNK_SIZE)
+    }
+
+    // Top left corner of the chunk in world coordinates
+    pub fn origin(&self) -> Point {
+        Point::new(self.x * CHUNK_SIZE, self.y * CHUNK_SIZE)
+    }
+
+    pub fn to_world(self, local: Point) -> Point {
+        let origin = self.origin();
+        Point::new(origin.x + local.x, origin.y + local.y)
+    }
+
+    pub fn to_local(self, world: Point) -> Point {
+        let origin = self.origin();
+        Point::new(world.x - origin.x, world.y - origin.y)
+    }
+
+    fn distance(&self, other: ChunkCoord) -> Coordinate {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+}
+
+// The loaded part of an endless world. Chunks are generated the first time
+// the player comes near, written to `directory` when the player leaves them
+// behind and read back from there when they return. The directory belongs to
+// this run only and is removed with the store.
+pub struct ChunkStore {
+    generator: ChunkGenerator,
+    chunks: HashMap<ChunkCoord, TileGrid>,
+    directory: PathBuf,
+}
+
+impl ChunkStore {
+    // Starts a fresh world in a new `<seed>-<run>` directory under `worlds`.
+    // Worlds of other runs, even with the same seed, are left alone.
+    pub fn new(seed: u64, worlds: &Path) -> io::Result<Self> {
+        fs::create_dir_all(worlds)?;
+        let mut run = 0;
+        let directory = loop {
+            let directory = worlds.join(format!("{}-{}", seed, run));
+            match fs::create_dir(&directory) {
+                Ok(()) => break directory,
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => run += 1,
+                Err(e) => return Err(e),
+            }
+        };
+
+        Ok(ChunkStore {
+            generator: ChunkGenerator::new(seed),
+            chunks: HashMap::new(),
+            directory,
+        })
+    }
+
+    pub fn spawn_point(&self) -> Point {
+        self.generator.spawn_point()
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    // The loaded chunk holding `point`, and where `point` is inside it
+    pub fn grid_at(&self, point: Point) -> Option<(&TileGrid, Point)> {
+        let coord = ChunkCoord::containing(point);
+        self.chunks
+            .get(&coord)
+            .map(|tiles| (tiles, coord.to_local(point)))
+    }
+
+    pub fn grid_at_mut(&mut self, point: Point) -> Option<(&mut TileGrid, Point)> {
+        let coord = ChunkCoord::containing(point);
+        self.chunks
+            .get_mut(&coord)
+            .map(|tiles| (tiles, coord.to_local(point)))
+    }
+
+    pub fn grids_mut(&mut self) -> impl Iterator<Item = &mut TileGrid> {
+        self.chunks.values_mut()
+    }
+
+    // Smallest rectangle holding every loaded chunk, as top left and bottom right (exclusive)
+    pub fn loaded_bounds(&self) -> (Point, Point) {
+        let min_x = self.chunks.keys().map(|coord| coord.x).min().unwrap_or(0);
+        let min_y = self.chunks.keys().map(|coord| coord.y).min().unwrap_or(0);
+        let max_x = self
+            .chunks
+            .keys()
+            .map(|coord| coord.x + 1)
+            .max()
+            .unwrap_or(0);
+        let max_y = self
+            .chunks
+            .keys()
+            .map(|coord| coord.y + 1)
+            .max()
+            .unwrap_or(0);
+        (
+            ChunkCoord::new(min_x, min_y).origin(),
+            ChunkCoord::new(max_x, max_y).origin(),
+        )
+    }
+
+    // Load everything around `point` and put away what's too far from it
+    pub fn stream_around(&mut self, point: Point) -> io::Result<()> {
+        let center = ChunkCoord::containing(point);
+
+        let far_away: Vec<ChunkCoord> = self
+            .chunks
+            .keys()
+            .filter(|coord| coord.distance(center) > UNLOAD_RADIUS)
+            .copied()
+            .collect();
+        for coord in far_away {
+            self.unload(coord)?;
+        }
+
+        let min_x = center.x.saturating_sub(LOAD_RADIUS);
+        let min_y = center.y.saturating_sub(LOAD_RADIUS);
+        let max_x = (center.x + LOAD_RADIUS).min(WORLD_SIZE_IN_CHUNKS - 1);
+        let max_y = (center.y + LOAD_RADIUS).min(WORLD_SIZE_IN_CHUNKS - 1);
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let coord = ChunkCoord::new(x, y);
+                if !self.chunks.contains_key(&coord) {
+                    let tiles = self.load_or_generate(coord)?;
+                    self.chunks.insert(coord, tiles);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Chunks are saved as map files with hint files next to them, like any
+    // other level. Every secret and sign gets a hint, so nothing is rolled
+    // when they're read back. Visibility and curses are worked out again
+    // every turn so they aren't saved.
+    fn load_or_generate(&self, coord: ChunkCoord) -> io::Result<TileGrid> {
+        let path = self.chunk_path(coord);
+        if !path.exists() {
+            return Ok(self.generator.generate(coord).0);
+        }
+
+        let corrupt = |reason: String| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Corrupt chunk {}: {}", path.display(), reason),
+            )
+        };
+        let tiles = read_map(&path, &mut rand::thread_rng()).map_err(|e| match e {
+            MapError::Io(e) => e,
+            e => corrupt(e.to_string()),
+        })?;
+        if tiles.width() != CHUNK_SIZE || tiles.height() != CHUNK_SIZE {
+            return Err(corrupt(format!(
+                "expected {}x{} tiles",
+                CHUNK_SIZE, CHUNK_SIZE
+            )));
+        }
+        Ok(tiles)
+    }
+
+    fn unload(&mut self, coord: ChunkCoord) -> io::Result<()> {
+        if let Some(tiles) = self.chunks.remove(&coord) {
+            write_map(&tiles, self.chunk_path(coord))?;
+        }
+        Ok(())
+    }
+
+    fn chunk_path(&self, coord: ChunkCoord) -> PathBuf {
+        self.directory
+            .join(format!("{}_{}.chunk", coord.x, coord.y))
+    }
+}
+
+impl Drop for ChunkStore {
+    // Chunks on disk are only good for the run that wrote them
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.directory);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::map::types::{Coordinate, Point};
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+// Everything that can go wrong reading or changing a map
+#[derive(Debug)]
+pub enum MapError {
+    OutOfBounds {
+        point: Point,
+        width: Coordinate,
+        height: Coordinate,
+    },
+    NotLoaded(Point), // inside an endless world, but its chunk isn't in memory
+    Empty,
+    RaggedRow {
+        line: usize,
+        length: usize,
+        expected: usize,
+    },
+    BadHints {
+        path: PathBuf,
+        reason: String,
+    },
+    Io(io::Error),
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapError::OutOfBounds {
+                point,
+                width,
+                height,
+            } => write!(
+                f,
+                "({}, {}) is outside the {}x{} map",
+                point.x, point.y, width, height
+            ),
+            MapError::NotLoaded(point) => {
+                write!(
+                    f,
+                    "({}, {}) is in a chunk that isn't loaded",
+                    point.x, point.y
+                )
+            }
+            MapError::Empty => write!(f, "the map has no tiles"),
+            MapError::RaggedRow {
+                line,
+                length,
+                expected,
+            } => write!(
+                f,
+                "line {} is {} tiles wide, expected {}",
+                line, length, expected
+            ),
+            MapError::BadHints { path, reason } => {
+                write!(f, "bad hints in {}: {}", path.display(), reason)
+            }
+            MapError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+impl From<io::Error> for MapError {
+    fn from(e: io::Error) -> Self {
+        MapError::Io(e)
+    }
+}
+
+impl From<MapError> for io::Error {
+    fn from(e: MapError) -> Self {
+        match e {
+            MapError::Io(e) => e,
+            MapError::Empty | MapError::RaggedRow { .. } | MapError::BadHints { .. } => {
+                io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+            }
+            MapError::OutOfBounds { .. } | MapError::NotLoaded(_) => {
+                io::Error::new(io::ErrorKind::InvalidInput, e.to_string())
+            }
+        }
+    }
+}
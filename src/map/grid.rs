@@ -0,0 +1,181 @@
+use crate::map::types::{Coordinate, Point};
+use crate::tile::Tile;
+
+// One bit per tile, for flags that get recomputed every turn
+#[derive(Clone, Debug, Default)]
+pub struct BitGrid {
+    width: Coordinate,
+    height: Coordinate,
+    bits: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: Coordinate, height: Coordinate) -> Self {
+        BitGrid {
+            width,
+            height,
+            bits: vec![0; (width * height).div_ceil(64)],
+        }
+    }
+
+    // Points outside the grid are never set
+    pub fn get(&self, point: Point) -> bool {
+        self.index(point)
+            .is_some_and(|index| self.bits[index / 64] & (1 << (index % 64)) != 0)
+    }
+
+    pub fn set(&mut self, point: Point, value: bool) {
+        let Some(index) = self.index(point) else {
+            return;
+        };
+        if value {
+            self.bits[index / 64] |= 1 << (index % 64);
+        } else {
+            self.bits[index / 64] &= !(1 << (index % 64));
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.fill(0);
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        (point.x < self.width && point.y < self.height).then(|| point.y * self.width + point.x)
+    }
+}
+
+// Tiles stored row by row in one flat vector. Visibility and curses change
+// every turn, so they live in bitsets next to the tiles: `get` fills them
+// into the tile it returns and `set` takes them out of the tile it's given.
+#[derive(Clone, Default)]
+pub struct TileGrid {
+    width: Coordinate,
+    height: Coordinate,
+    tiles: Vec<Tile>,
+    visible: BitGrid,
+    cursed: BitGrid,
+}
+
+impl TileGrid {
+    pub fn new(width: Coordinate, height: Coordinate) -> Self {
+        TileGrid {
+            width,
+            height,
+            tiles: vec![Tile::Empty; width * height],
+            visible: BitGrid::new(width, height),
+            cursed: BitGrid::new(width, height),
+        }
+    }
+
+    // As wide as the longest row, shorter rows are padded with empty space
+    pub fn from_rows(rows: Vec<Vec<Tile>>) -> Self {
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let mut grid = TileGrid::new(width, rows.len());
+        for (y, row) in rows.into_iter().enumerate() {
+            for (x, tile) in row.into_iter().enumerate() {
+                grid.set(Point::new(x, y), tile);
+            }
+        }
+        grid
+    }
+
+    pub fn width(&self) -> Coordinate {
+        self.width
+    }
+
+    pub fn height(&self) -> Coordinate {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    // The tile at `point` with its visibility and curse filled in
+    pub fn get(&self, point: Point) -> Option<Tile> {
+        let tile = *self.tiles.get(self.index(point)?)?;
+        Some(
+            tile.with_visibility(self.visible.get(point))
+                .with_curse(self.cursed.get(point)),
+        )
+    }
+
+    // Returns false when `point` is outside the grid
+    pub fn set(&mut self, point: Point, tile: Tile) -> bool {
+        let Some(index) = self.index(point) else {
+            return false;
+        };
+        if let Some(visible) = tile.visibility() {
+            self.visible.set(point, visible);
+        }
+        if let Some(cursed) = tile.cursed() {
+            self.cursed.set(point, cursed);
+        }
+        self.tiles[index] = tile.with_visibility(false).with_curse(false);
+        true
+    }
+
+    // The stored tile, without its visibility and curse
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut Tile> {
+        let index = self.index(point)?;
+        self.tiles.get_mut(index)
+    }
+
+    pub fn row(&self, y: Coordinate) -> Option<&[Tile]> {
+        (y < self.height).then(|| &self.tiles[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[Tile]> {
+        self.tiles.chunks_exact(self.width.max(1))
+    }
+
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [Tile]> {
+        self.tiles.chunks_exact_mut(self.width.max(1))
+    }
+
+    // One line of glyphs per row, the way map files are written
+    pub fn to_text(&self) -> String {
+        self.rows()
+            .map(|row| row.iter().map(Tile::glyph).collect::<String>() + "\n")
+            .collect()
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width, self.height);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    pub fn fill(&mut self, tile: Tile) {
+        self.tiles.fill(tile);
+        self.visible.clear();
+        self.cursed.clear();
+    }
+
+    pub fn is_visible(&self, point: Point) -> bool {
+        self.visible.get(point)
+    }
+
+    pub fn set_visible(&mut self, point: Point, visible: bool) {
+        self.visible.set(point, visible);
+    }
+
+    pub fn clear_visible(&mut self) {
+        self.visible.clear();
+    }
+
+    pub fn is_cursed(&self, point: Point) -> bool {
+        self.cursed.get(point)
+    }
+
+    pub fn set_cursed(&mut self, point: Point, cursed: bool) {
+        self.cursed.set(point, cursed);
+    }
+
+    pub fn clear_cursed(&mut self) {
+        self.cursed.clear();
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        self.contains(point).then(|| point.y * self.width + point.x)
+    }
+}
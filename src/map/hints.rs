@@ -0,0 +1,369 @@
+use crate::data::{items, monsters};
+use crate::map::error::MapError;
+use crate::map::grid::TileGrid;
+use crate::map::parse_grid;
+use crate::map::types::Point;
+use crate::tile::{Lock, Tile};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// `maps/tutorial.txt` keeps its hints in `maps/tutorial.hint.json`
+pub const HINT_EXTENSION: &str = "hint.json";
+
+pub fn hint_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    path.as_ref().with_extension(HINT_EXTENSION)
+}
+
+// One tile the glyph can't describe on its own. The position counts tiles
+// row by row from the top left, i.e. `y * width + x`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "T: Serialize + Default + PartialEq",
+    deserialize = "T: Deserialize<'de> + Default"
+))]
+pub struct Hint<T> {
+    pub position: usize,
+    // The glyph a hidden tile turns into once it's found, as hand written
+    // hint files have it. Nothing resolves tiles that way yet, so these hints
+    // are kept as written and leave their tile alone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolve_to: Option<String>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub metadata: T,
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+// Secrets have a rarity, those that resolve to a trap say how much it hurts
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SecretHint {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rarity: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub damage: Option<u32>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ObeliskHint {
+    pub curse: bool,
+    pub fov: u32,
+    pub damage: u32,
+    pub reduce_fov_radius: u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SignHint {
+    pub lore: u16,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShrineHint {
+    pub used: bool,
+}
+
+// `"sealed"`, the name of the key that opens it, or null for no lock
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LockHint {
+    pub lock: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ItemHint {
+    pub item: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MonsterHint {
+    pub monster: String,
+    pub hp: u32,
+}
+
+// Everything a map file's glyphs leave out: rolled secrets and signs, obelisk
+// settings, locks, items and wounded monsters. Kept next to the map so the
+// map itself stays plain text.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MapHints {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub secrets: Vec<Hint<SecretHint>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub obelisks: Vec<Hint<ObeliskHint>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub signs: Vec<Hint<SignHint>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub shrines: Vec<Hint<ShrineHint>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub doors: Vec<Hint<LockHint>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub archways: Vec<Hint<LockHint>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<Hint<ItemHint>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub monsters: Vec<Hint<MonsterHint>>,
+}
+
+impl MapHints {
+    // The hints `tiles` needs to come back exactly as it is
+    pub fn from_grid(tiles: &TileGrid) -> Self {
+        let mut hints = MapHints::default();
+        for (position, tile) in tiles.rows().flatten().enumerate() {
+            match *tile {
+                Tile::Secret { rarity, .. } => hints.secrets.push(Hint::new(
+                    position,
+                    SecretHint {
+                        rarity: Some(rarity),
+                        damage: None,
+                    },
+                )),
+                Tile::Obelisk {
+                    curse,
+                    fov,
+                    damage_hp,
+                    reduce_fov_radius,
+                    ..
+                } => hints.obelisks.push(Hint::new(
+                    position,
+                    ObeliskHint {
+                        curse,
+                        fov,
+                        damage: damage_hp,
+                        reduce_fov_radius,
+                    },
+                )),
+                Tile::Sign { lore, .. } => hints.signs.push(Hint::new(position, SignHint { lore })),
+                Tile::Shrine { used: true, .. } => hints
+                    .shrines
+                    .push(Hint::new(position, ShrineHint { used: true })),
+                Tile::Door {
+                    lock: Some(lock), ..
+                } => hints
+                    .doors
+                    .push(Hint::new(position, LockHint::new(Some(lock)))),
+                // A bare archway glyph is sealed
+                Tile::Archway { lock } if lock != Some(Lock::Sealed) => hints
+                    .archways
+                    .push(Hint::new(position, LockHint::new(lock))),
+                Tile::Item { item, .. } => hints.items.push(Hint::new(
+                    position,
+                    ItemHint {
+                        item: item.name().to_string(),
+                    },
+                )),
+                Tile::Monster { kind, hp, .. } if hp != monsters().get(kind).hp => {
+                    hints.monsters.push(Hint::new(
+                        position,
+                        MonsterHint {
+                            monster: monsters().get(kind).name.to_string(),
+                            hp,
+                        },
+                    ))
+                }
+                _ => {}
+            }
+        }
+        hints
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == MapHints::default()
+    }
+
+    pub fn parse(source: &str) -> Result<Self, String> {
+        serde_json::from_str(source).map_err(|e| e.to_string())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("hints are plain data") + "\n"
+    }
+
+    // Fills the hints into a map read from its glyphs. Every hint has to sit
+    // on the kind of tile it describes, except items, whose glyphs mean
+    // nothing to the map reader, and hints that resolve to another glyph.
+    pub fn apply(&self, tiles: &mut TileGrid) -> Result<(), String> {
+        for hint in &self.secrets {
+            let rarity = hint.metadata.rarity;
+            if rarity.is_none() && hint.resolve_to.is_none() {
+                return Err(format!("secret hint at {} has no rarity", hint.position));
+            }
+            hint.update(tiles, "secret", |tile| match tile {
+                Tile::Secret { visible, .. } => Some(Tile::Secret {
+                    visible,
+                    rarity: rarity?,
+                }),
+                _ => None,
+            })?;
+        }
+        for hint in &self.obelisks {
+            let obelisk = &hint.metadata;
+            hint.update(tiles, "obelisk", |tile| match tile {
+                Tile::Obelisk { visible, .. } => Some(Tile::Obelisk {
+                    visible,
+                    curse: obelisk.curse,
+                    fov: obelisk.fov,
+                    damage_hp: obelisk.damage,
+                    reduce_fov_radius: obelisk.reduce_fov_radius,
+                }),
+                _ => None,
+            })?;
+        }
+        for hint in &self.signs {
+            let lore = hint.metadata.lore;
+            hint.update(tiles, "sign", |tile| match tile {
+                Tile::Sign { visible, .. } => Some(Tile::Sign { visible, lore }),
+                _ => None,
+            })?;
+        }
+        for hint in &self.shrines {
+            let used = hint.metadata.used;
+            hint.update(tiles, "shrine", |tile| match tile {
+                Tile::Shrine { visible, .. } => Some(Tile::Shrine { visible, used }),
+                _ => None,
+            })?;
+        }
+        for hint in &self.doors {
+            let lock = hint.metadata.lock()?;
+            hint.update(tiles, "door", |tile| match tile {
+                // Locked doors are always closed
+                Tile::Door { visible, open, .. } => Some(Tile::Door {
+                    visible,
+                    open: open && lock.is_none(),
+                    lock,
+                }),
+                _ => None,
+            })?;
+        }
+        for hint in &self.archways {
+            let lock = hint.metadata.lock()?;
+            hint.update(tiles, "archway", |tile| match tile {
+                Tile::Archway { .. } => Some(Tile::Archway { lock }),
+                _ => None,
+            })?;
+        }
+        for hint in &self.items {
+            let name = &hint.metadata.item;
+            let item = items()
+                .find_by_name(name)
+                .ok_or_else(|| format!("unknown item '{}'", name))?;
+            hint.update(tiles, "item", |tile| {
+                Some(Tile::Item {
+                    visible: tile.visibility().unwrap_or(false),
+                    item,
+                })
+            })?;
+        }
+        for hint in &self.monsters {
+            let name = &hint.metadata.monster;
+            let kind = monsters()
+                .find_by_name(name)
+                .ok_or_else(|| format!("unknown monster '{}'", name))?;
+            let hp = hint.metadata.hp;
+            hint.update(tiles, "monster", |tile| {
+                tile.is_monster()
+                    .then(|| Tile::monster(kind).with_monster_hp(hp))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Hint<T> {
+    pub fn new(position: usize, metadata: T) -> Self {
+        Hint {
+            position,
+            resolve_to: None,
+            metadata,
+        }
+    }
+
+    // Swaps the tile under the hint for `replace`'s, which gives None when
+    // the tile isn't a `kind`. Hints that resolve to another glyph only have
+    // to be on the map.
+    fn update(
+        &self,
+        tiles: &mut TileGrid,
+        kind: &str,
+        replace: impl FnOnce(Tile) -> Option<Tile>,
+    ) -> Result<(), String> {
+        let width = tiles.width().max(1);
+        let point = Point::new(self.position % width, self.position / width);
+        let tile = tiles
+            .get(point)
+            .ok_or_else(|| format!("{} hint at {} is outside the map", kind, self.position))?;
+        if self.resolve_to.is_some() {
+            return Ok(());
+        }
+        let updated = replace(tile).ok_or_else(|| {
+            format!(
+                "{} hint at {} ({}, {}) is on a '{}'",
+                kind,
+                self.position,
+                point.x,
+                point.y,
+                tile.glyph()
+            )
+        })?;
+        tiles.set(point, updated);
+        Ok(())
+    }
+}
+
+impl LockHint {
+    fn new(lock: Option<Lock>) -> Self {
+        LockHint {
+            lock: lock.map(|lock| match lock {
+                Lock::Sealed => "sealed".to_string(),
+                Lock::Key(item) => item.name().to_string(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> Result<Option<Lock>, String> {
+        match self.lock.as_deref() {
+            None => Ok(None),
+            Some("sealed") => Ok(Some(Lock::Sealed)),
+            Some(name) => items()
+                .find_by_name(name)
+                .map(|item| Some(Lock::Key(item)))
+                .ok_or_else(|| format!("unknown key '{}'", name)),
+        }
+    }
+}
+
+// Reads the map file at `path` and fills in the hint file next to it, if
+// there is one. Secrets and signs without hints are rolled from `rng`.
+pub fn read_map<P: AsRef<Path>, R: Rng>(path: P, rng: &mut R) -> Result<TileGrid, MapError> {
+    let path = path.as_ref();
+    let mut tiles = parse_grid(&fs::read_to_string(path)?, rng)?;
+    let hint_path = hint_path(path);
+    if hint_path.exists() {
+        MapHints::parse(&fs::read_to_string(&hint_path)?)
+            .and_then(|hints| hints.apply(&mut tiles))
+            .map_err(|e| MapError::BadHints {
+                path: hint_path,
+                reason: e,
+            })?;
+    }
+    Ok(tiles)
+}
+
+// Writes `tiles` as a map file at `path`, with a hint file next to it when
+// the glyphs aren't enough. A hint file left over from an earlier map is
+// removed.
+pub fn write_map<P: AsRef<Path>>(tiles: &TileGrid, path: P) -> io::Result<()> {
+    let path = path.as_ref();
+    fs::write(path, tiles.to_text())?;
+    let hints = MapHints::from_grid(tiles);
+    let hint_path = hint_path(path);
+    if !hints.is_empty() {
+        fs::write(hint_path, hints.to_json())
+    } else if hint_path.exists() {
+        fs::remove_file(hint_path)
+    } else {
+        Ok(())
+    }
+}
@@ -1,105 +1,265 @@
-use types::{Coordinate, GameMapTiles, Point};
+use chunk::{ChunkStore, WORLD_SIZE};
+use error::MapError;
+use grid::TileGrid;
+use types::{Coordinate, Point};
 
 use crate::tile::Tile;
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{self, BufRead, BufReader};
-use std::sync::{Arc, RwLock};
-
+use std::io;
+use std::path::Path;
+
+pub mod chunk;
+pub mod error;
+pub mod grid;
+pub mod hints;
+pub mod pathfinding;
 pub mod types;
 
+// Where the tiles live: a single fixed size grid, or chunks of an endless world
+enum TileStorage {
+    Grid(TileGrid),
+    Chunks(ChunkStore),
+}
+
 pub struct Map {
-    tiles: Arc<RwLock<GameMapTiles>>,
+    tiles: TileStorage,
     visible_tiles: HashSet<Point>,
+    explored_tiles: HashSet<Point>,
+    newly_explored_tiles: Vec<Point>,
+    revealed_tiles: HashSet<Point>, // shown by magic, known without having been seen
 }
 
 impl Map {
-    #[allow(dead_code)]
-    pub fn load(filename: &str) -> io::Result<Self> {
-        let file = File::open(filename)?;
-        let reader = BufReader::new(file);
-        let mut tiles = Vec::new();
-
-        for line in reader.lines() {
-            let line = line?;
-            tiles.push(line.chars().map(Tile::from_char).collect());
-        }
+    pub fn load(filename: &str) -> Result<Self, MapError> {
+        Map::load_with(filename, &mut rand::thread_rng())
+    }
 
-        Ok(Map {
-            tiles: Arc::new(RwLock::new(tiles)),
-            visible_tiles: HashSet::new(),
-        })
+    // Like `load`, rolling from `rng`. The hint file next to the map fills in
+    // what its glyphs leave out, and those tiles aren't rolled at all.
+    pub fn load_with<P: AsRef<Path>, R: rand::Rng>(path: P, rng: &mut R) -> Result<Self, MapError> {
+        hints::read_map(path, rng).map(Map::from_tiles)
+    }
+
+    // One line per row, every row has to be as wide as the first
+    pub fn parse(source: &str) -> Result<Self, MapError> {
+        Map::parse_with(source, &mut rand::thread_rng())
+    }
+
+    // Like `parse`, rolling the contents of secrets and signs from `rng`
+    pub fn parse_with<R: rand::Rng>(source: &str, rng: &mut R) -> Result<Self, MapError> {
+        parse_grid(source, rng).map(Map::from_tiles)
+    }
+
+    // The whole map when it's a fixed size grid, endless worlds have none
+    pub fn get_grid(&self) -> Option<&TileGrid> {
+        match &self.tiles {
+            TileStorage::Grid(tiles) => Some(tiles),
+            TileStorage::Chunks(_) => None,
+        }
     }
 
     pub fn width(&self) -> usize {
-        let tiles = self.tiles.read().unwrap();
-        tiles[0].len()
+        match &self.tiles {
+            TileStorage::Grid(tiles) => tiles.width(),
+            TileStorage::Chunks(_) => WORLD_SIZE,
+        }
     }
 
     pub fn height(&self) -> usize {
-        let tiles = self.tiles.read().unwrap();
-        tiles.len()
+        match &self.tiles {
+            TileStorage::Grid(tiles) => tiles.height(),
+            TileStorage::Chunks(_) => WORLD_SIZE,
+        }
+    }
+
+    pub fn from_tiles(tiles: TileGrid) -> Self {
+        Map::with_storage(TileStorage::Grid(tiles))
     }
 
-    pub fn from_tiles(tiles: Arc<RwLock<GameMapTiles>>) -> Self {
+    // An endless map, nothing is loaded until `stream_around` is called
+    pub fn from_chunks(chunks: ChunkStore) -> Self {
+        Map::with_storage(TileStorage::Chunks(chunks))
+    }
+
+    fn with_storage(tiles: TileStorage) -> Self {
         Map {
             tiles,
             visible_tiles: HashSet::new(),
+            explored_tiles: HashSet::new(),
+            newly_explored_tiles: Vec::new(),
+            revealed_tiles: HashSet::new(),
         }
     }
 
-    pub fn get_tiles(&self) -> &Arc<RwLock<GameMapTiles>> {
-        &self.tiles
+    // Make sure the chunks around `point` are loaded, fixed size maps are always loaded
+    pub fn stream_around(&mut self, point: Point) -> io::Result<()> {
+        match &mut self.tiles {
+            TileStorage::Grid(_) => Ok(()),
+            TileStorage::Chunks(chunks) => chunks.stream_around(point),
+        }
     }
 
+    // The part of the map that is in memory, as top left and bottom right (exclusive)
+    pub fn loaded_bounds(&self) -> (Point, Point) {
+        match &self.tiles {
+            TileStorage::Grid(_) => (Point::new(0, 0), Point::new(self.width(), self.height())),
+            TileStorage::Chunks(chunks) => chunks.loaded_bounds(),
+        }
+    }
+
+    // Every point inside `loaded_bounds`
+    fn loaded_points(&self) -> impl Iterator<Item = Point> {
+        let (min, max) = self.loaded_bounds();
+        (min.y..max.y).flat_map(move |y| (min.x..max.x).map(move |x| Point::new(x, y)))
+    }
+
+    // Nothing outside the map can be walked on
     pub fn is_walkable(&self, position: Point) -> bool {
-        let tiles = self.tiles.read().unwrap();
-        let tile = tiles[position.y][position.x];
-        tile.is_walkable()
+        self.try_get(position).is_ok_and(|tile| tile.is_walkable())
     }
 
+    // Writes outside the map or to chunks that aren't loaded are dropped
     pub fn set_tile(&mut self, position: Point, tile: Tile) {
-        let mut tiles = self.tiles.write().unwrap();
-        tiles[position.y][position.x] = tile;
+        self.try_set(position, tile).ok();
     }
 
+    // Tiles outside the map or in chunks that aren't loaded read as empty space
     pub fn get_tile(&self, position: Point) -> Tile {
-        let tiles = self.tiles.read().unwrap();
-        tiles[position.y][position.x]
+        self.try_get(position).unwrap_or(Tile::Empty)
+    }
+
+    pub fn try_get(&self, position: Point) -> Result<Tile, MapError> {
+        let (tiles, local) = self.grid_at(position)?;
+        tiles.get(local).ok_or_else(|| self.out_of_bounds(position))
+    }
+
+    pub fn try_set(&mut self, position: Point, tile: Tile) -> Result<(), MapError> {
+        let (tiles, local) = self.grid_at_mut(position)?;
+        if tiles.set(local, tile) {
+            Ok(())
+        } else {
+            Err(self.out_of_bounds(position))
+        }
+    }
+
+    // The grid holding `position`, and where `position` is inside it
+    fn grid_at(&self, position: Point) -> Result<(&TileGrid, Point), MapError> {
+        if !self.contains(position) {
+            return Err(self.out_of_bounds(position));
+        }
+        match &self.tiles {
+            TileStorage::Grid(tiles) => Ok((tiles, position)),
+            TileStorage::Chunks(chunks) => chunks
+                .grid_at(position)
+                .ok_or(MapError::NotLoaded(position)),
+        }
+    }
+
+    fn grid_at_mut(&mut self, position: Point) -> Result<(&mut TileGrid, Point), MapError> {
+        if !self.contains(position) {
+            return Err(self.out_of_bounds(position));
+        }
+        match &mut self.tiles {
+            TileStorage::Grid(tiles) => Ok((tiles, position)),
+            TileStorage::Chunks(chunks) => chunks
+                .grid_at_mut(position)
+                .ok_or(MapError::NotLoaded(position)),
+        }
+    }
+
+    pub fn contains(&self, position: Point) -> bool {
+        position.x < self.width() && position.y < self.height()
+    }
+
+    fn out_of_bounds(&self, point: Point) -> MapError {
+        MapError::OutOfBounds {
+            point,
+            width: self.width(),
+            height: self.height(),
+        }
     }
 
     pub fn is_deadly(&self, point: Point) -> bool {
         matches!(self.get_tile(point), Tile::Pit { .. })
     }
 
-    #[allow(dead_code)]
+    pub fn is_explored(&self, point: Point) -> bool {
+        self.explored_tiles.contains(&point)
+    }
+
+    pub fn get_explored_tiles(&self) -> &HashSet<Point> {
+        &self.explored_tiles
+    }
+
+    pub fn is_revealed(&self, point: Point) -> bool {
+        self.revealed_tiles.contains(&point)
+    }
+
+    pub fn get_revealed_tiles(&self) -> &HashSet<Point> {
+        &self.revealed_tiles
+    }
+
+    // Seen or revealed, either way the player knows what's there
+    pub fn is_known(&self, point: Point) -> bool {
+        self.is_explored(point) || self.is_revealed(point)
+    }
+
+    // Tiles that were seen for the first time during the last FOV update
+    pub fn get_newly_explored_tiles(&self) -> &Vec<Point> {
+        &self.newly_explored_tiles
+    }
+
+    pub fn is_monster_in_view(&self) -> bool {
+        self.visible_tiles
+            .iter()
+            .any(|&point| self.get_tile(point).is_monster())
+    }
+
     pub fn find_player(&self) -> Option<Point> {
-        let tiles = self.tiles.read().unwrap();
-        for (y, row) in tiles.iter().enumerate() {
-            for (x, &tile) in row.iter().enumerate() {
-                if let Tile::Player { .. } = tile {
-                    return Some(Point { x, y });
-                }
-            }
-        }
-        None
+        self.find_tile(|tile| matches!(tile, Tile::Player { .. }))
+    }
+
+    // The first matching tile, row by row
+    pub fn find_tile<F: Fn(Tile) -> bool>(&self, predicate: F) -> Option<Point> {
+        self.loaded_points()
+            .find(|&point| predicate(self.get_tile(point)))
     }
 
     pub fn is_interactable(&self, point: Point) -> bool {
-        let tiles = self.tiles.read().unwrap();
         matches!(
-            tiles[point.y][point.x],
+            self.get_tile(point),
             Tile::Door { .. } | Tile::Secret { visible: true, .. }
         )
     }
 
     pub fn interact_tile(&mut self, point: Point) {
-        let mut tiles = self.tiles.write().unwrap();
-        if let Tile::Door { open, visible } = tiles[point.y][point.x] {
-            tiles[point.y][point.x] = Tile::Door {
-                open: !open,
+        match self.get_tile(point) {
+            // Locked doors need the game to deal with their lock first
+            Tile::Door {
+                open,
                 visible,
-            };
+                lock: None,
+            } => {
+                self.set_tile(
+                    point,
+                    Tile::Door {
+                        open: !open,
+                        visible,
+                        lock: None,
+                    },
+                );
+            }
+            // Revealed secrets leave plain floor behind
+            Tile::Secret { visible, .. } => {
+                self.set_tile(
+                    point,
+                    Tile::Floor {
+                        visible,
+                        cursed: false,
+                    },
+                );
+            }
+            _ => {}
         }
     }
 
@@ -113,6 +273,7 @@ impl Map {
 
     pub fn update_fov(&mut self, pov: Point, fov_radius: u32) {
         self.clear_visible_tiles();
+        self.newly_explored_tiles.clear();
 
         let is_player_cursed = matches!(
             self.get_tile(pov),
@@ -143,6 +304,9 @@ impl Map {
                     {
                         let point = Point::new(x as Coordinate, y as Coordinate);
                         self.visible_tiles.insert(point);
+                        if self.explored_tiles.insert(point) {
+                            self.newly_explored_tiles.push(point);
+                        }
                         self.update_tile_visibility(point, true);
                     }
                 }
@@ -206,9 +370,7 @@ impl Map {
                     | Tile::Door { open: false, .. }
                     | Tile::Column { .. }
                     | Tile::Secret { .. }
-                    | Tile::Wither { .. }
-                    | Tile::Bat { .. }
-                    | Tile::Brute { .. }
+                    | Tile::Monster { .. }
             )
         } else {
             // you cannot see inside cursed areas
@@ -219,130 +381,21 @@ impl Map {
                     | Tile::Door { open: false, .. }
                     | Tile::Column { .. }
                     | Tile::Secret { .. }
-                    | Tile::Wither { .. }
-                    | Tile::Bat { .. }
-                    | Tile::Brute { .. }
+                    | Tile::Monster { .. }
             )
         }
     }
 
     fn update_tile_visibility(&mut self, point: Point, visible: bool) {
-        let mut tiles_write = self.tiles.write().unwrap();
-        let tile = &mut tiles_write[point.y][point.x];
-        match tile {
-            Tile::Wall { .. } => {
-                *tile = Tile::Wall { visible };
-            }
-            Tile::Pit { .. } => {
-                *tile = Tile::Pit { visible };
-            }
-            Tile::Floor { cursed, .. } => {
-                *tile = Tile::Floor {
-                    cursed: *cursed,
-                    visible,
-                };
-            }
-            Tile::Secret { rarity, .. } => {
-                *tile = Tile::Secret {
-                    rarity: *rarity,
-                    visible,
-                };
-            }
-            Tile::SecretFloor { .. } => {
-                *tile = Tile::SecretFloor { visible };
-            }
-            Tile::Door { open, .. } => {
-                *tile = Tile::Door {
-                    open: *open,
-                    visible,
-                };
-            }
-            Tile::Obelisk {
-                curse,
-                fov,
-                damage_hp,
-                reduce_fov_radius,
-                ..
-            } => {
-                *tile = Tile::Obelisk {
-                    curse: *curse,
-                    fov: *fov,
-                    damage_hp: *damage_hp,
-                    reduce_fov_radius: *reduce_fov_radius,
-                    visible,
-                };
-            }
-            Tile::Wither {
-                hp, damage, fov, ..
-            } => {
-                *tile = Tile::Wither {
-                    hp: *hp,
-                    damage: *damage,
-                    fov: *fov,
-                    visible,
-                };
-            }
-            Tile::Bat {
-                hp, damage, fov, ..
-            } => {
-                *tile = Tile::Bat {
-                    hp: *hp,
-                    damage: *damage,
-                    fov: *fov,
-                    visible,
-                };
-            }
-            Tile::Brute {
-                hp, damage, fov, ..
-            } => {
-                *tile = Tile::Brute {
-                    hp: *hp,
-                    damage: *damage,
-                    fov: *fov,
-                    visible,
-                };
-            }
-            Tile::Player { is_dead, .. } => {
-                *tile = Tile::Player {
-                    is_dead: *is_dead,
-                    is_cursed: false,
-                };
-            }
-            _ => {}
+        if let Ok((tiles, local)) = self.grid_at_mut(point) {
+            tiles.set_visible(local, visible);
         }
     }
 
     fn clear_curse_from_all_tiles(&mut self) {
-        for y in 0..self.height() {
-            for x in 0..self.width() {
-                let point = Point::new(x, y);
-
-                if let Tile::Floor {
-                    cursed: true,
-                    visible,
-                } = self.get_tile(point)
-                {
-                    self.set_tile(
-                        point,
-                        Tile::Floor {
-                            cursed: false,
-                            visible,
-                        },
-                    );
-                } else if let Tile::Player {
-                    is_cursed: true,
-                    is_dead,
-                } = self.get_tile(point)
-                {
-                    self.set_tile(
-                        point,
-                        Tile::Player {
-                            is_cursed: false,
-                            is_dead,
-                        },
-                    );
-                }
-            }
+        match &mut self.tiles {
+            TileStorage::Grid(tiles) => tiles.clear_cursed(),
+            TileStorage::Chunks(chunks) => chunks.grids_mut().for_each(TileGrid::clear_cursed),
         }
     }
 
@@ -351,58 +404,109 @@ impl Map {
         let mut cursed_tiles = HashSet::new();
 
         // First pass: identify all Obelisks and their curse areas
-        for y in 0..self.height() {
-            for x in 0..self.width() {
-                let point = Point::new(x, y);
-
-                if let Tile::Obelisk {
-                    curse: true, fov, ..
-                } = self.get_tile(point)
-                {
-                    self.calculate_curse_area(point, fov, &mut cursed_tiles);
-                }
+        for point in self.loaded_points() {
+            if let Tile::Obelisk {
+                curse: true, fov, ..
+            } = self.get_tile(point)
+            {
+                self.calculate_curse_area(point, fov, &mut cursed_tiles);
             }
         }
 
         // Second pass: apply curses to the identified tiles
         for point in cursed_tiles {
-            if let Tile::Floor { visible, .. } = self.get_tile(point) {
-                self.set_tile(
-                    point,
-                    Tile::Floor {
-                        visible,
-                        cursed: true,
-                    },
-                );
-            } else if let Tile::Player { is_dead, .. } = self.get_tile(point) {
-                self.set_tile(
-                    point,
-                    Tile::Player {
-                        is_dead,
-                        is_cursed: true,
-                    },
-                );
+            // Only floors and the player carry a curse
+            if self.get_tile(point).cursed().is_none() {
+                continue;
+            }
+            if let Ok((tiles, local)) = self.grid_at_mut(point) {
+                tiles.set_cursed(local, true);
             }
         }
     }
 
     pub fn get_obelisk_cursing_tile(&self, pov: Point) -> Option<Tile> {
-        let tiles = self.tiles.read().unwrap();
-        // get nearest obelisk with direct line of sight to the player
-        for (y, row) in tiles.iter().enumerate() {
-            for (x, tile) in row.iter().enumerate() {
-                let point = Point::new(x, y);
-
-                if let Tile::Obelisk { curse: true, .. } = self.get_tile(point) {
-                    if self.has_line_of_sight(point.x, point.y, pov.x, pov.y, true, false) {
-                        return Some(*tile);
-                    }
-                }
-            }
+        self.find_cursing_obelisks(pov)
+            .next()
+            .map(|point| self.get_tile(point))
+    }
+
+    // Obelisks that still curse and have direct line of sight to `pov`
+    fn find_cursing_obelisks(&self, pov: Point) -> impl Iterator<Item = Point> + '_ {
+        self.loaded_points().filter(move |&point| {
+            matches!(self.get_tile(point), Tile::Obelisk { curse: true, .. })
+                && self.has_line_of_sight(point.x, point.y, pov.x, pov.y, true, false)
+        })
+    }
+
+    // Silence the closest cursing obelisk in sight of `pov` for good
+    pub fn banish_nearest_obelisk(&mut self, pov: Point) -> Option<Point> {
+        let distance =
+            |point: Point| point.x.abs_diff(pov.x).pow(2) + point.y.abs_diff(pov.y).pow(2);
+        let nearest = self
+            .find_cursing_obelisks(pov)
+            .min_by_key(|&point| distance(point))?;
+        if let Tile::Obelisk {
+            visible,
+            fov,
+            damage_hp,
+            reduce_fov_radius,
+            ..
+        } = self.get_tile(nearest)
+        {
+            self.set_tile(
+                nearest,
+                Tile::Obelisk {
+                    visible,
+                    curse: false,
+                    fov,
+                    damage_hp,
+                    reduce_fov_radius,
+                },
+            );
         }
+        self.apply_obelisk_curses();
+        Some(nearest)
+    }
 
-        // Return None if no cursing obelisk is found or the player is not on a cursed tile
-        None
+    // Show the player everything within `radius` of `center`, the whole loaded
+    // map without a radius. Revealed tiles are drawn but still count as
+    // unexplored until the player sees them. Returns the points that were new.
+    pub fn reveal(&mut self, center: Point, radius: Option<u32>) -> Vec<Point> {
+        let in_range = |point: Point| match radius {
+            Some(radius) => {
+                point.x.abs_diff(center.x).pow(2) + point.y.abs_diff(center.y).pow(2)
+                    <= (radius as usize).pow(2)
+            }
+            None => true,
+        };
+        let revealed: Vec<Point> = self
+            .loaded_points()
+            .filter(|&point| in_range(point) && self.get_tile(point) != Tile::Empty)
+            .filter(|&point| !self.is_known(point))
+            .collect();
+        self.revealed_tiles.extend(revealed.iter().copied());
+        revealed
+    }
+
+    // How many loaded tiles pass `predicate`
+    pub fn count_tiles<F: Fn(Tile) -> bool>(&self, predicate: F) -> usize {
+        self.loaded_points()
+            .filter(|&point| predicate(self.get_tile(point)))
+            .count()
+    }
+
+    // A floor tile to drop the player on, away from curses
+    pub fn random_safe_floor<R: rand::Rng>(&self, rng: &mut R) -> Option<Point> {
+        let floors: Vec<Point> = self
+            .loaded_points()
+            .filter(|&point| matches!(self.get_tile(point), Tile::Floor { cursed: false, .. }))
+            .collect();
+        if floors.is_empty() {
+            None
+        } else {
+            Some(floors[rng.gen_range(0..floors.len())])
+        }
     }
 
     fn calculate_curse_area(&self, center: Point, radius: u32, cursed_tiles: &mut HashSet<Point>) {
@@ -428,3 +532,33 @@ impl Map {
         }
     }
 }
+
+// The tiles of a map file, rolling secrets and signs from `rng`
+fn parse_grid<R: rand::Rng>(source: &str, rng: &mut R) -> Result<TileGrid, MapError> {
+    let mut rows: Vec<Vec<Tile>> = source
+        .lines()
+        .map(|line| line.chars().map(|c| Tile::from_char_with(c, rng)).collect())
+        .collect();
+    // A trailing blank line isn't a row
+    while rows.last().is_some_and(|row| row.is_empty()) {
+        rows.pop();
+    }
+
+    let expected = rows.first().map_or(0, |row| row.len());
+    if expected == 0 {
+        return Err(MapError::Empty);
+    }
+    if let Some((index, row)) = rows
+        .iter()
+        .enumerate()
+        .find(|(_, row)| row.len() != expected)
+    {
+        return Err(MapError::RaggedRow {
+            line: index + 1,
+            length: row.len(),
+            expected,
+        });
+    }
+
+    Ok(TileGrid::from_rows(rows))
+}
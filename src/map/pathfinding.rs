@@ -0,0 +1,276 @@
+use crate::map::grid::TileGrid;
+use crate::map::types::Point;
+use crate::map::Map;
+use crate::tile::Tile;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+// Paths only move orthogonally, same as the player does
+const NEIGHBOURS: [(i32, i32); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+// Describes how expensive it is to step onto a tile,
+// `None` means the tile can't be entered at all
+#[derive(Clone, Copy)]
+pub struct PathCost {
+    pub avoid_pits: bool,
+    pub avoid_cursed: bool,
+    pub door_cost: Option<u32>, // closed doors are passable with this cost when set
+}
+
+impl PathCost {
+    // Plain walkability as defined by `Tile::is_walkable`
+    pub const WALKABLE: PathCost = PathCost {
+        avoid_pits: false,
+        avoid_cursed: false,
+        door_cost: None,
+    };
+
+    // What a sensible adventurer would do: stay out of pits and curses, open unlocked doors on the way
+    pub const CAUTIOUS: PathCost = PathCost {
+        avoid_pits: true,
+        avoid_cursed: true,
+        door_cost: Some(2),
+    };
+
+    pub fn of(&self, tile: Tile) -> Option<u32> {
+        match tile {
+            Tile::Pit { .. } if self.avoid_pits => None,
+            Tile::Floor { cursed: true, .. } if self.avoid_cursed => None,
+            // Travel never plans through locks, they need the player's attention,
+            // nor through archways, which would take the player to another level
+            Tile::Door { lock: Some(_), .. } | Tile::Archway { .. } => None,
+            Tile::Door { open: false, .. } => self.door_cost,
+            tile if tile.is_walkable() => Some(1),
+            _ => None,
+        }
+    }
+}
+
+// Anything paths can be searched over
+pub trait TileSource {
+    // The searchable area, as top left and bottom right (exclusive)
+    fn bounds(&self) -> (Point, Point);
+    fn tile_at(&self, point: Point) -> Tile;
+}
+
+impl TileSource for Map {
+    fn bounds(&self) -> (Point, Point) {
+        self.loaded_bounds()
+    }
+
+    fn tile_at(&self, point: Point) -> Tile {
+        self.get_tile(point)
+    }
+}
+
+impl TileSource for TileGrid {
+    fn bounds(&self) -> (Point, Point) {
+        (Point::new(0, 0), Point::new(self.width(), self.height()))
+    }
+
+    fn tile_at(&self, point: Point) -> Tile {
+        self.get(point).unwrap_or(Tile::Empty)
+    }
+}
+
+// Neighbours of `point` inside the `(min, max)` bounds, max exclusive
+fn neighbours(bounds: (Point, Point), point: Point) -> impl Iterator<Item = Point> {
+    let (min, max) = bounds;
+
+    NEIGHBOURS.iter().filter_map(move |(dx, dy)| {
+        let x = point.x as i64 + *dx as i64;
+        let y = point.y as i64 + *dy as i64;
+        if (min.x as i64..max.x as i64).contains(&x) && (min.y as i64..max.y as i64).contains(&y) {
+            Some(Point::new(x as usize, y as usize))
+        } else {
+            None
+        }
+    })
+}
+
+fn manhattan_distance(a: Point, b: Point) -> u32 {
+    (a.x.abs_diff(b.x) + a.y.abs_diff(b.y)) as u32
+}
+
+// A* search from `start` to `goal`.
+// Returns the steps to take, excluding `start` and including `goal`.
+pub fn astar<M, F>(map: &M, start: Point, goal: Point, cost: F) -> Option<Vec<Point>>
+where
+    M: TileSource,
+    F: Fn(Point, Tile) -> Option<u32>,
+{
+    if start == goal {
+        return Some(Vec::new());
+    }
+
+    let bounds = map.bounds();
+
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<Point, Point> = HashMap::new();
+    let mut best_cost: HashMap<Point, u32> = HashMap::new();
+
+    best_cost.insert(start, 0);
+    open.push(Reverse((
+        manhattan_distance(start, goal),
+        0,
+        start.y,
+        start.x,
+    )));
+
+    while let Some(Reverse((_, current_cost, y, x))) = open.pop() {
+        let current = Point::new(x, y);
+
+        if current == goal {
+            let mut path = vec![current];
+            let mut step = current;
+            while let Some(&previous) = came_from.get(&step) {
+                if previous == start {
+                    break;
+                }
+                path.push(previous);
+                step = previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        // Skip stale heap entries
+        if current_cost > best_cost[&current] {
+            continue;
+        }
+
+        for next in neighbours(bounds, current) {
+            let Some(step_cost) = cost(next, map.tile_at(next)) else {
+                continue;
+            };
+            let next_cost = current_cost + step_cost;
+
+            if best_cost.get(&next).is_none_or(|&known| next_cost < known) {
+                best_cost.insert(next, next_cost);
+                came_from.insert(next, current);
+                let estimate = next_cost + manhattan_distance(next, goal);
+                open.push(Reverse((estimate, next_cost, next.y, next.x)));
+            }
+        }
+    }
+
+    None
+}
+
+// A Dijkstra map holds the cost of the cheapest route from every tile to the
+// nearest of its sources. Rolling downhill on it leads to the closest source.
+pub struct DijkstraMap {
+    origin: Point, // values only cover the part of the map that was loaded
+    width: usize,
+    height: usize,
+    values: Vec<Option<u32>>,
+}
+
+impl DijkstraMap {
+    pub fn new<M, F>(map: &M, sources: &[Point], cost: F) -> Self
+    where
+        M: TileSource,
+        F: Fn(Point, Tile) -> Option<u32>,
+    {
+        let bounds = map.bounds();
+        let (origin, max) = bounds;
+        let width = max.x - origin.x;
+        let height = max.y - origin.y;
+
+        let mut dijkstra_map = DijkstraMap {
+            origin,
+            width,
+            height,
+            values: vec![None; width * height],
+        };
+        let mut open = BinaryHeap::new();
+
+        for &source in sources {
+            let Some(index) = dijkstra_map.index(source) else {
+                continue;
+            };
+            // A source still costs what it takes to step onto it
+            if let Some(source_cost) = cost(source, map.tile_at(source)) {
+                dijkstra_map.values[index] = Some(source_cost);
+                open.push(Reverse((source_cost, source.y, source.x)));
+            }
+        }
+
+        while let Some(Reverse((current_value, y, x))) = open.pop() {
+            let current = Point::new(x, y);
+            if dijkstra_map.get(current) != Some(current_value) {
+                continue;
+            }
+
+            for next in neighbours(bounds, current) {
+                let Some(step_cost) = cost(next, map.tile_at(next)) else {
+                    continue;
+                };
+                // Every tile's value includes the cost of stepping onto it
+                let next_value = current_value + step_cost;
+                let Some(index) = dijkstra_map.index(next) else {
+                    continue;
+                };
+
+                if dijkstra_map.values[index].is_none_or(|known| next_value < known) {
+                    dijkstra_map.values[index] = Some(next_value);
+                    open.push(Reverse((next_value, next.y, next.x)));
+                }
+            }
+        }
+
+        dijkstra_map
+    }
+
+    // Cost of reaching the nearest source from `point`, `None` if unreachable
+    pub fn get(&self, point: Point) -> Option<u32> {
+        self.index(point).and_then(|index| self.values[index])
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        let x = point.x.checked_sub(self.origin.x)?;
+        let y = point.y.checked_sub(self.origin.y)?;
+        if x < self.width && y < self.height {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    // The neighbouring tile that leads downhill towards the closest source
+    pub fn next_step(&self, from: Point) -> Option<Point> {
+        NEIGHBOURS
+            .iter()
+            .filter_map(|(dx, dy)| {
+                let x = from.x as i32 + dx;
+                let y = from.y as i32 + dy;
+                if x < 0 || y < 0 {
+                    return None;
+                }
+                let point = Point::new(x as usize, y as usize);
+                self.get(point).map(|value| (value, point))
+            })
+            .min_by_key(|&(value, point)| (value, point.y, point.x))
+            .map(|(_, point)| point)
+    }
+
+    // Full downhill path from `from` to the closest source, excluding `from`
+    pub fn path_from(&self, from: Point) -> Option<Vec<Point>> {
+        let mut current_value = self.get(from)?;
+        let mut current = from;
+        let mut path = Vec::new();
+
+        while let Some(next) = self.next_step(current) {
+            let next_value = self.get(next)?;
+            // Nothing is further downhill, we're standing on a source
+            if next_value >= current_value {
+                break;
+            }
+            path.push(next);
+            current = next;
+            current_value = next_value;
+        }
+
+        Some(path)
+    }
+}
@@ -1,8 +1,3 @@
-use crate::tile::Tile;
-
-pub type GameTileRow = Vec<Tile>;
-pub type GameMapTiles = Vec<GameTileRow>;
-
 pub type Coordinate = usize;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -0,0 +1,61 @@
+// What the player has to do on each level before its exit archway opens
+
+use crate::data::item::ItemEffect;
+use crate::data::items;
+use crate::item::{Item, ItemCategory};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+// Stepping through the exit at this depth leads out of the dungeon
+pub const FINAL_DEPTH: u32 = 5;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Objective {
+    ReachExit,              // the exit is open from the start
+    SlayBoss,               // every boss on the level
+    CleanseObelisks(u32),   // banish this many obelisks
+    RetrieveArtefact(Item), // lies somewhere in a secret room
+}
+
+impl Objective {
+    // The last level always ends with a boss, the generator falls back to
+    // `ReachExit` for anything it can't make achievable
+    pub fn random_for_depth<R: Rng>(depth: u32, rng: &mut R) -> Self {
+        if depth >= FINAL_DEPTH {
+            return Objective::SlayBoss;
+        }
+        match rng.gen_range(0..8) {
+            0 => Objective::ReachExit,
+            1..=3 => Objective::SlayBoss,
+            4 | 5 => Objective::CleanseObelisks(rng.gen_range(1..=2)),
+            _ => match artefacts().choose(rng) {
+                Some(&artefact) => Objective::RetrieveArtefact(artefact),
+                None => Objective::SlayBoss,
+            },
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Objective::ReachExit => "Find the exit".to_string(),
+            Objective::SlayBoss => "Slay the guardian of the level".to_string(),
+            Objective::CleanseObelisks(1) => "Banish an obelisk".to_string(),
+            Objective::CleanseObelisks(count) => format!("Banish {} obelisks", count),
+            Objective::RetrieveArtefact(item) => format!("Retrieve the {}", item.name()),
+        }
+    }
+}
+
+pub fn artefacts() -> Vec<Item> {
+    items()
+        .all()
+        .filter(|item| item.category() == ItemCategory::Artefact)
+        .collect()
+}
+
+// What banishes obelisks, if the item book has anything that does
+pub fn banishing_item() -> Option<Item> {
+    items()
+        .all()
+        .find(|item| item.def().effect == ItemEffect::BanishObelisk)
+}
@@ -1,12 +1,34 @@
+pub mod perk;
+
+use crate::effect::StatusEffects;
+use crate::item::Item;
+use crate::player::perk::{LevelUpChoice, Perk};
+
+// Every level up heals and toughens the player a little on top of their choice
+const LEVEL_UP_HP: u32 = 2;
+const STAT_CHOICE_BONUS: u32 = 2;
+const VITALITY_CHOICE_HP: u32 = 10;
+const KEEN_EYES_FOV_BONUS: u32 = 3;
+
 pub struct Player {
     pub level: u32,
-    pub exp: u32,
+    pub exp: u32, // experience towards the next level
     // stats that can be changed by leveling up or through curses
     pub max_hp: u32,
     pub current_hp: u32,
     pub strength: u32,
     pub defense: u32,
     pub fov_radius: u32,
+    pub perks: Vec<Perk>,
+    pub effects: StatusEffects,
+    pub inventory: Vec<Item>,
+    pub pending_level_ups: u32, // level ups the player hasn't picked a choice for yet
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Player::new()
+    }
 }
 
 impl Player {
@@ -19,6 +41,10 @@ impl Player {
             strength: 5,
             defense: 2,
             fov_radius: 10, // 10 seems to be a good default and provide a cool effect
+            perks: Vec::new(),
+            effects: StatusEffects::new(),
+            inventory: Vec::new(),
+            pending_level_ups: 0,
         }
     }
 
@@ -26,26 +52,78 @@ impl Player {
         self.level * 100
     }
 
-    pub fn gain_exp(&mut self, amount: u32) {
+    // Returns how many levels were gained, leftover experience carries over
+    pub fn gain_exp(&mut self, amount: u32) -> u32 {
         self.exp += amount;
-        if self.exp >= self.xp_for_next_level() {
+        let mut levels_gained = 0;
+        while self.exp >= self.xp_for_next_level() {
+            self.exp -= self.xp_for_next_level();
             self.level_up();
+            levels_gained += 1;
         }
+        levels_gained
     }
 
     pub fn level_up(&mut self) {
         self.level += 1;
-        self.max_hp += 5;
+        self.max_hp += LEVEL_UP_HP;
         self.current_hp = self.max_hp;
-        self.strength += 1;
-        self.defense += 1;
-        self.exp = 0;
+        self.pending_level_ups += 1;
+    }
+
+    pub fn has_perk(&self, perk: Perk) -> bool {
+        self.perks.contains(&perk)
+    }
+
+    // Stats can always be picked, perks only once
+    pub fn get_level_up_choices(&self) -> Vec<LevelUpChoice> {
+        let mut choices = vec![
+            LevelUpChoice::Strength,
+            LevelUpChoice::Defense,
+            LevelUpChoice::Vitality,
+        ];
+        choices.extend(
+            Perk::ALL
+                .iter()
+                .filter(|&&perk| !self.has_perk(perk))
+                .map(|&perk| LevelUpChoice::Perk(perk)),
+        );
+        choices
+    }
+
+    // Spends a pending level up, returns false if there was nothing to spend or
+    // the choice isn't available
+    pub fn choose_level_up(&mut self, choice: LevelUpChoice) -> bool {
+        if self.pending_level_ups == 0 || !self.get_level_up_choices().contains(&choice) {
+            return false;
+        }
+
+        match choice {
+            LevelUpChoice::Strength => self.strength += STAT_CHOICE_BONUS,
+            LevelUpChoice::Defense => self.defense += STAT_CHOICE_BONUS,
+            LevelUpChoice::Vitality => {
+                self.max_hp += VITALITY_CHOICE_HP;
+                self.current_hp += VITALITY_CHOICE_HP;
+            }
+            LevelUpChoice::Perk(perk) => {
+                if perk == Perk::KeenEyes {
+                    self.fov_radius += KEEN_EYES_FOV_BONUS;
+                }
+                self.perks.push(perk);
+            }
+        }
+        self.pending_level_ups -= 1;
+        true
     }
 
     pub fn take_damage(&mut self, amount: u32) {
         self.current_hp = self.current_hp.saturating_sub(amount);
     }
 
+    pub fn heal(&mut self, amount: u32) {
+        self.current_hp = (self.current_hp + amount).min(self.max_hp);
+    }
+
     pub fn is_dead(&self) -> bool {
         self.current_hp == 0
     }
@@ -0,0 +1,68 @@
+// Perks are picked once each on level up and change how existing mechanics treat the player
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Perk {
+    KeenEyes,       // see further
+    CurseWard,      // obelisk curses don't shrink your sight
+    IronWill,       // obelisk curses hurt half as much
+    TreasureHunter, // secrets are worth twice the experience
+    Venomous,       // monsters that survive a hit are poisoned
+}
+
+impl Perk {
+    pub const ALL: [Perk; 5] = [
+        Perk::KeenEyes,
+        Perk::CurseWard,
+        Perk::IronWill,
+        Perk::TreasureHunter,
+        Perk::Venomous,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Perk::KeenEyes => "Keen Eyes",
+            Perk::CurseWard => "Curse Ward",
+            Perk::IronWill => "Iron Will",
+            Perk::TreasureHunter => "Treasure Hunter",
+            Perk::Venomous => "Venomous",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Perk::KeenEyes => "+3 sight radius",
+            Perk::CurseWard => "Obelisk curses no longer shrink your sight",
+            Perk::IronWill => "Obelisk curses deal half damage",
+            Perk::TreasureHunter => "Secrets give double XP",
+            Perk::Venomous => "Your hits poison monsters",
+        }
+    }
+}
+
+// What the player can pick from on the level up screen
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LevelUpChoice {
+    Strength,
+    Defense,
+    Vitality,
+    Perk(Perk),
+}
+
+impl LevelUpChoice {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LevelUpChoice::Strength => "Strength",
+            LevelUpChoice::Defense => "Defense",
+            LevelUpChoice::Vitality => "Vitality",
+            LevelUpChoice::Perk(perk) => perk.name(),
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            LevelUpChoice::Strength => "+2 STR",
+            LevelUpChoice::Defense => "+2 DEF",
+            LevelUpChoice::Vitality => "+10 max HP",
+            LevelUpChoice::Perk(perk) => perk.description(),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Kept in the working directory next to the maps, like the endless worlds
+pub const SCORES_FILE: &str = "scores.txt";
+pub const MORGUE_DIRECTORY: &str = "morgue";
+
+// Only the best runs are worth remembering
+pub const MAX_SCORES: usize = 10;
+
+// One finished run, stored as a tab separated line
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScoreEntry {
+    pub score: u32,
+    pub depth: u32,
+    pub level: u32,
+    pub turns: u32,
+    pub seed: u64,
+    pub outcome: String, // "escaped" or how the player died
+}
+
+impl ScoreEntry {
+    fn parse(line: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [score, depth, level, turns, seed, outcome] = fields[..] else {
+            return Err(format!("expected 6 fields, found {}", fields.len()));
+        };
+        let number = |name: &str, value: &str| {
+            value
+                .parse::<u32>()
+                .map_err(|e| format!("invalid {} '{}': {}", name, value, e))
+        };
+
+        Ok(ScoreEntry {
+            score: number("score", score)?,
+            depth: number("depth", depth)?,
+            level: number("level", level)?,
+            turns: number("turns", turns)?,
+            seed: seed
+                .parse()
+                .map_err(|e| format!("invalid seed '{}': {}", seed, e))?,
+            outcome: outcome.to_string(),
+        })
+    }
+
+    fn encode(&self) -> String {
+        // Tabs and newlines would break the line format
+        let outcome = self.outcome.replace(['\t', '\n'], " ");
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            self.score, self.depth, self.level, self.turns, self.seed, outcome
+        )
+    }
+}
+
+// The best runs on this machine, highest score first
+#[derive(Clone, Debug, Default)]
+pub struct HighScores {
+    entries: Vec<ScoreEntry>,
+}
+
+impl HighScores {
+    // A missing file just means nobody has finished a run yet
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(HighScores::default()),
+            Err(e) => return Err(e),
+        };
+        HighScores::parse(&source).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid scores {}: {}", path.display(), e),
+            )
+        })
+    }
+
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut scores = HighScores::default();
+        for (number, line) in source.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry =
+                ScoreEntry::parse(line).map_err(|e| format!("line {}: {}", number + 1, e))?;
+            scores.add(entry);
+        }
+        Ok(scores)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut contents: String = self
+            .entries
+            .iter()
+            .map(|entry| entry.encode() + "\n")
+            .collect();
+        if contents.is_empty() {
+            contents.push('\n');
+        }
+        fs::write(path, contents)
+    }
+
+    // Returns the entry's place in the table, counting from 0, or `None` if
+    // it didn't make the cut. Ties go to the older run.
+    pub fn add(&mut self, entry: ScoreEntry) -> Option<usize> {
+        let rank = self
+            .entries
+            .iter()
+            .position(|other| other.score < entry.score)
+            .unwrap_or(self.entries.len());
+        if rank >= MAX_SCORES {
+            return None;
+        }
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_SCORES);
+        Some(rank)
+    }
+
+    pub fn entries(&self) -> &[ScoreEntry] {
+        &self.entries
+    }
+
+    // The table as text, for the game over screen and `--scores`
+    pub fn lines(&self) -> Vec<String> {
+        if self.entries.is_empty() {
+            return vec!["No scores yet".to_string()];
+        }
+        self.entries
+            .iter()
+            .enumerate()
+            .map(|(rank, entry)| {
+                format!(
+                    "{:>2}. {:>6}  depth {} level {} in {} turns, {}",
+                    rank + 1,
+                    entry.score,
+                    entry.depth,
+                    entry.level,
+                    entry.turns,
+                    entry.outcome
+                )
+            })
+            .collect()
+    }
+}
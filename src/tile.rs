@@ -1,7 +1,13 @@
+use crate::data::monster::{MonsterDef, MonsterKind};
+use crate::data::{lore, monsters};
+use crate::item::Item;
 use rand::Rng;
 use ratatui::style::Color as RatatuiColor;
 use sdl2::pixels::Color as SDLColor;
 
+// Characters the map, chunk and prefab formats already use, monster glyphs can't be one of them
+pub const TERRAIN_GLYPHS: &str = "∩<>#o.@+/V?_|·-:D*&¶";
+
 const VISIBLE_WALL_COLOR: RatatuiColor = RatatuiColor::Indexed(250);
 const INVISIBLE_WALL_COLOR: RatatuiColor = RatatuiColor::Indexed(245);
 
@@ -9,11 +15,18 @@ const VISIBLE_CURSED_FLOOR_COLOR: RatatuiColor = RatatuiColor::Magenta;
 const VISIBLE_FLOOR_COLOR: RatatuiColor = RatatuiColor::Indexed(255);
 const INVISIBLE_FLOOR_COLOR: RatatuiColor = RatatuiColor::Indexed(240);
 
+// What keeps a door or archway shut
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Lock {
+    Key(Item), // opened by this key, doors can be picked or bashed too
+    Sealed,    // no key fits, something else has to open it
+}
+
 #[derive(Clone, Copy, PartialEq)]
 pub enum Tile {
-    // archway is the plot device that starts the game
+    // archways lead between levels, the player wakes up beside one
     Archway {
-        locked: bool,
+        lock: Option<Lock>,
     },
     // normal map tiles
     Stairs {
@@ -39,6 +52,7 @@ pub enum Tile {
     Door {
         visible: bool,
         open: bool,
+        lock: Option<Lock>, // locked doors are always closed
     },
     Secret {
         visible: bool,
@@ -56,28 +70,28 @@ pub enum Tile {
         damage_hp: u32,
         reduce_fov_radius: u32,
     }, // obelisks curse players and should be avoided
-    // deadly tiles
-    Pit {
+    // helpful tiles
+    Shrine {
         visible: bool,
-    }, // falling into a pit kills the player
-    // enemies (wither, bat, brute)
-    Wither {
+        used: bool,
+    }, // shrines answer a single prayer
+    Sign {
         visible: bool,
-        hp: u32,
-        damage: u32,
-        fov: u32,
+        lore: u16, // which entry of the lore book it tells
     },
-    Bat {
+    Item {
         visible: bool,
-        hp: u32,
-        damage: u32,
-        fov: u32,
-    },
-    Brute {
+        item: Item,
+    }, // picked up by walking over it
+    // deadly tiles
+    Pit {
         visible: bool,
+    }, // falling into a pit kills the player
+    // enemies, everything but their health comes from the monster definitions
+    Monster {
+        visible: bool,
+        kind: MonsterKind,
         hp: u32,
-        damage: u32,
-        fov: u32,
     },
     // empty
     Empty,
@@ -86,7 +100,7 @@ pub enum Tile {
 impl Tile {
     pub fn as_char(&self) -> char {
         match self {
-            Tile::Archway { .. } => '∩', // figure out use for this later
+            Tile::Archway { .. } => '∩', // leads to another level once it's open
             Tile::Stairs { up: false, .. } => '>',
             Tile::Stairs { up: true, .. } => '<',
             Tile::Wall { .. } => '#',
@@ -98,24 +112,98 @@ impl Tile {
             Tile::Secret { visible: true, .. } => '?',
             Tile::Pit { visible: true } => 'V',
             Tile::Obelisk { visible: true, .. } => '|',
-            Tile::Wither { visible: true, .. } => 'W',
-            Tile::Bat { visible: true, .. } => 'B',
-            Tile::Brute { visible: true, .. } => 'U',
+            Tile::Shrine { visible: true, .. } => '&',
+            Tile::Sign { visible: true, .. } => '¶',
+            Tile::Item {
+                visible: true,
+                item,
+            } => item.def().glyph,
+            Tile::Monster {
+                visible: true,
+                kind,
+                ..
+            } => monsters().get(*kind).glyph,
             // FOV tiles + enemies
             Tile::Floor { .. }
             | Tile::SecretFloor { visible: false }
             | Tile::Secret { visible: false, .. }
             | Tile::Pit { visible: false }
-            | Tile::Wither { visible: false, .. }
-            | Tile::Bat { visible: false, .. }
-            | Tile::Brute { visible: false, .. }
-            | Tile::Obelisk { visible: false, .. } => '·',
+            | Tile::Monster { visible: false, .. }
+            | Tile::Obelisk { visible: false, .. }
+            | Tile::Shrine { visible: false, .. }
+            | Tile::Sign { visible: false, .. }
+            | Tile::Item { visible: false, .. } => '·',
             Tile::Empty => ' ',
         }
     }
 
+    // Out of sight but known to the player, what's there is still drawn.
+    // Monsters wander off, so they're left out.
+    pub fn remembered_char(&self) -> char {
+        match self {
+            Tile::Monster { .. } => '·',
+            tile => tile.with_visibility(true).as_char(),
+        }
+    }
+
+    // The character a map file keeps the tile as, whether it's been seen or not
+    pub fn glyph(&self) -> char {
+        match self {
+            Tile::Floor { .. } => '.',
+            tile => tile.with_visibility(true).as_char(),
+        }
+    }
+
+    // What the tile is, for inspecting maps
+    pub fn describe(&self) -> String {
+        let lock = |lock: &Option<Lock>| match lock {
+            None => String::new(),
+            Some(Lock::Sealed) => " (sealed)".to_string(),
+            Some(Lock::Key(item)) => format!(" (locked, {})", item.name()),
+        };
+        match self {
+            Tile::Archway { lock: archway } => format!("archway{}", lock(archway)),
+            Tile::Stairs { up: true, .. } => "stairs up".to_string(),
+            Tile::Stairs { up: false, .. } => "stairs down".to_string(),
+            Tile::Wall { .. } => "wall".to_string(),
+            Tile::Column { .. } => "column".to_string(),
+            Tile::Floor { cursed: true, .. } => "cursed floor".to_string(),
+            Tile::Floor { .. } => "floor".to_string(),
+            Tile::Player { is_dead: true, .. } => "player (dead)".to_string(),
+            Tile::Player { .. } => "player".to_string(),
+            Tile::Door { open: true, .. } => "open door".to_string(),
+            Tile::Door { lock: door, .. } => format!("closed door{}", lock(door)),
+            Tile::Secret { rarity, .. } => format!("secret (1 in {})", rarity),
+            Tile::SecretFloor { .. } => "secret floor".to_string(),
+            Tile::Obelisk {
+                curse,
+                fov,
+                damage_hp,
+                reduce_fov_radius,
+                ..
+            } => format!(
+                "obelisk ({}, reach {}, {} damage, -{} sight)",
+                if *curse { "cursing" } else { "banished" },
+                fov,
+                damage_hp,
+                reduce_fov_radius
+            ),
+            Tile::Shrine { used: true, .. } => "shrine (used)".to_string(),
+            Tile::Shrine { .. } => "shrine".to_string(),
+            Tile::Sign { lore, .. } => format!("sign (lore {})", lore),
+            Tile::Item { item, .. } => item.name().to_string(),
+            Tile::Pit { .. } => "pit".to_string(),
+            Tile::Monster { kind, hp, .. } => {
+                let def = monsters().get(*kind);
+                format!("{} ({}/{} hp)", def.name, hp, def.hp)
+            }
+            Tile::Empty => "nothing".to_string(),
+        }
+    }
+
     pub fn term_fg(&self) -> RatatuiColor {
         match self {
+            Tile::Archway { lock: None } => RatatuiColor::LightGreen,
             Tile::Archway { .. } => RatatuiColor::LightCyan,
             // dark gray/gray when visible tiles
             Tile::Stairs { visible, .. }
@@ -151,15 +239,40 @@ impl Tile {
             }
             Tile::Obelisk { visible: false, .. } => INVISIBLE_FLOOR_COLOR,
             Tile::Secret { visible: false, .. } => INVISIBLE_FLOOR_COLOR,
-            Tile::Wither { visible: false, .. } => INVISIBLE_FLOOR_COLOR,
-            Tile::Bat { visible: false, .. } => INVISIBLE_FLOOR_COLOR,
-            Tile::Brute { visible: false, .. } => INVISIBLE_FLOOR_COLOR,
+            Tile::Monster { visible: false, .. }
+            | Tile::Shrine { visible: false, .. }
+            | Tile::Sign { visible: false, .. }
+            | Tile::Item { visible: false, .. } => INVISIBLE_FLOOR_COLOR,
+            Tile::Door {
+                visible: true,
+                lock: Some(_),
+                ..
+            } => RatatuiColor::LightRed,
             Tile::Door { visible: true, .. } => RatatuiColor::Yellow,
+            // a shrine that was prayed at is just stone
+            Tile::Shrine {
+                visible: true,
+                used: true,
+            } => VISIBLE_WALL_COLOR,
+            Tile::Shrine { visible: true, .. } => RatatuiColor::LightGreen,
+            Tile::Sign { visible: true, .. } => RatatuiColor::Indexed(180),
+            Tile::Item {
+                visible: true,
+                item,
+            } => item.def().colour,
             Tile::Secret { visible: true, .. } => RatatuiColor::LightYellow,
+            // banished obelisks are just stone
+            Tile::Obelisk {
+                visible: true,
+                curse: false,
+                ..
+            } => VISIBLE_WALL_COLOR,
             Tile::Obelisk { visible: true, .. } => RatatuiColor::Magenta,
-            Tile::Wither { visible: true, .. }
-            | Tile::Bat { visible: true, .. }
-            | Tile::Brute { visible: true, .. } => RatatuiColor::Red,
+            Tile::Monster {
+                visible: true,
+                kind,
+                ..
+            } => monsters().get(*kind).colour,
             _ => RatatuiColor::Reset,
         }
     }
@@ -192,13 +305,19 @@ impl Tile {
             Tile::Floor { .. } => SDLColor::RGB(50, 50, 50),
             Tile::Player { .. } => SDLColor::RGB(255, 255, 0),
             Tile::Door { open: true, .. } => SDLColor::RGB(150, 75, 0),
+            Tile::Door {
+                open: false,
+                lock: Some(_),
+                ..
+            } => SDLColor::RGB(200, 50, 0),
             Tile::Door { open: false, .. } => SDLColor::RGB(150, 75, 0),
             Tile::Pit { .. } => SDLColor::RGB(50, 50, 50),
             Tile::Secret { .. } => SDLColor::RGB(255, 255, 0),
             Tile::Obelisk { .. } => SDLColor::RGB(255, 0, 255),
-            Tile::Wither { .. } => SDLColor::RGB(255, 0, 0),
-            Tile::Bat { .. } => SDLColor::RGB(0, 255, 0),
-            Tile::Brute { .. } => SDLColor::RGB(0, 0, 255),
+            Tile::Shrine { .. } => SDLColor::RGB(0, 255, 128),
+            Tile::Sign { .. } => SDLColor::RGB(200, 150, 100),
+            Tile::Item { .. } => SDLColor::RGB(255, 255, 255),
+            Tile::Monster { kind, .. } => monsters().get(*kind).sdl_colour,
             Tile::Empty => SDLColor::RGB(0, 0, 0),
         }
     }
@@ -208,19 +327,129 @@ impl Tile {
             Tile::Wall { .. }
             | Tile::Secret { .. }
             | Tile::Obelisk { .. }
+            | Tile::Shrine { .. }
+            | Tile::Sign { .. }
             | Tile::Column { .. }
-            | Tile::Wither { .. }
-            | Tile::Bat { .. }
-            | Tile::Brute { .. } => false,
-            Tile::Archway { locked } => !locked,
+            | Tile::Monster { .. } => false,
+            Tile::Archway { lock } => lock.is_none(),
             Tile::Door { open, .. } => *open,
             _ => true,
         }
     }
 
+    // The lock keeping a door or archway shut, if there is one
+    pub fn lock(&self) -> Option<Lock> {
+        match *self {
+            Tile::Door { lock, .. } | Tile::Archway { lock } => lock,
+            _ => None,
+        }
+    }
+
+    pub fn is_monster(&self) -> bool {
+        matches!(self, Tile::Monster { .. })
+    }
+
+    pub fn monster_def(&self) -> Option<&'static MonsterDef> {
+        match self {
+            Tile::Monster { kind, .. } => Some(monsters().get(*kind)),
+            _ => None,
+        }
+    }
+
+    // A monster at full health
+    pub fn monster(kind: MonsterKind) -> Self {
+        Tile::Monster {
+            visible: false,
+            kind,
+            hp: monsters().get(kind).hp,
+        }
+    }
+
+    pub fn monster_hp(&self) -> Option<u32> {
+        match *self {
+            Tile::Monster { hp, .. } => Some(hp),
+            _ => None,
+        }
+    }
+
+    pub fn with_monster_hp(mut self, hp: u32) -> Self {
+        if let Tile::Monster { hp: current, .. } = &mut self {
+            *current = hp;
+        }
+        self
+    }
+
+    // The visibility flag, `None` for tiles that are never drawn dimmed
+    pub fn visibility(&self) -> Option<bool> {
+        match *self {
+            Tile::Stairs { visible, .. }
+            | Tile::Wall { visible }
+            | Tile::Column { visible }
+            | Tile::Floor { visible, .. }
+            | Tile::Door { visible, .. }
+            | Tile::Secret { visible, .. }
+            | Tile::SecretFloor { visible }
+            | Tile::Obelisk { visible, .. }
+            | Tile::Pit { visible }
+            | Tile::Shrine { visible, .. }
+            | Tile::Sign { visible, .. }
+            | Tile::Item { visible, .. }
+            | Tile::Monster { visible, .. } => Some(visible),
+            _ => None,
+        }
+    }
+
+    pub fn with_visibility(mut self, visible: bool) -> Self {
+        match &mut self {
+            Tile::Stairs { visible: flag, .. }
+            | Tile::Wall { visible: flag }
+            | Tile::Column { visible: flag }
+            | Tile::Floor { visible: flag, .. }
+            | Tile::Door { visible: flag, .. }
+            | Tile::Secret { visible: flag, .. }
+            | Tile::SecretFloor { visible: flag }
+            | Tile::Obelisk { visible: flag, .. }
+            | Tile::Pit { visible: flag }
+            | Tile::Shrine { visible: flag, .. }
+            | Tile::Sign { visible: flag, .. }
+            | Tile::Item { visible: flag, .. }
+            | Tile::Monster { visible: flag, .. } => *flag = visible,
+            _ => {}
+        }
+        self
+    }
+
+    // Whether an obelisk's curse lies on the tile, `None` for tiles that can't be cursed
+    pub fn cursed(&self) -> Option<bool> {
+        match *self {
+            Tile::Floor { cursed, .. } => Some(cursed),
+            Tile::Player { is_cursed, .. } => Some(is_cursed),
+            _ => None,
+        }
+    }
+
+    pub fn with_curse(mut self, cursed: bool) -> Self {
+        match &mut self {
+            Tile::Floor { cursed: flag, .. }
+            | Tile::Player {
+                is_cursed: flag, ..
+            } => *flag = cursed,
+            _ => {}
+        }
+        self
+    }
+
     pub fn from_char(c: char) -> Self {
+        Tile::from_char_with(c, &mut rand::thread_rng())
+    }
+
+    // Secrets and signs roll their contents from `rng`, so seeded maps come
+    // out the same every time
+    pub fn from_char_with<R: Rng>(c: char, rng: &mut R) -> Self {
         match c {
-            '∩' => Tile::Archway { locked: true },
+            '∩' => Tile::Archway {
+                lock: Some(Lock::Sealed),
+            },
             '>' => Tile::Stairs {
                 up: false,
                 visible: false,
@@ -242,17 +471,19 @@ impl Tile {
             '+' => Tile::Door {
                 open: true,
                 visible: false,
+                lock: None,
             },
             '/' => Tile::Door {
                 open: false,
                 visible: false,
+                lock: None,
             },
             // pits are deadly if you step into them
             'V' => Tile::Pit { visible: false },
             '?' => Tile::Secret {
                 visible: false,
                 // set rarity to random number of magnitude i.e. 1, 10, 100, 1000
-                rarity: 10_u32.pow(rand::thread_rng().gen_range(0..=3)),
+                rarity: 10_u32.pow(rng.gen_range(0..=3)),
             },
             '_' => Tile::SecretFloor { visible: false },
             '|' => Tile::Obelisk {
@@ -262,25 +493,18 @@ impl Tile {
                 damage_hp: 1,
                 reduce_fov_radius: 3,
             },
-            'W' => Tile::Wither {
-                visible: false,
-                hp: 3,
-                damage: 2,
-                fov: 8,
-            },
-            'B' => Tile::Bat {
+            '&' => Tile::Shrine {
                 visible: false,
-                hp: 1,
-                damage: 1,
-                fov: 6,
+                used: false,
             },
-            'U' => Tile::Brute {
+            '¶' => Tile::Sign {
                 visible: false,
-                hp: 20,
-                damage: 10,
-                fov: 4,
+                lore: lore().random(rng),
             },
-            _ => Tile::Empty,
+            // anything else might be a monster
+            c => monsters()
+                .find_by_glyph(c)
+                .map_or(Tile::Empty, Tile::monster),
         }
     }
 }
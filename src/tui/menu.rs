@@ -0,0 +1,199 @@
+use crate::game::{custom_maps, Difficulty, GameSetup, MAP_DIRECTORY};
+use crate::scores::{HighScores, SCORES_FILE};
+use crossterm::event::KeyCode;
+use std::path::PathBuf;
+
+// What the player picked on the title menu
+pub enum MenuChoice {
+    Play(GameSetup),
+    Continue, // back to the run that was left for the menu
+    ViewDungeon,
+    Quit,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MenuEntry {
+    NewGame,
+    Continue,
+    CustomMap,
+    Seed,
+    Difficulty,
+    World,
+    HighScores,
+    Viewer,
+    Quit,
+}
+
+// The title menu and the setup for the next run
+pub struct MainMenu {
+    setup: GameSetup,
+    maps: Vec<PathBuf>, // hand made maps that can be played
+    map_index: usize,
+    seed_input: String, // digits typed so far, a random seed when empty
+    selected: usize,
+    scores: Option<Vec<String>>, // Some while the high score table is shown
+    message: Option<String>,     // e.g. why the last game couldn't start
+}
+
+impl MainMenu {
+    // Starts from whatever the command line asked for
+    pub fn new(setup: GameSetup) -> Self {
+        let seed_input = setup.seed.map(|seed| seed.to_string()).unwrap_or_default();
+        MainMenu {
+            setup,
+            maps: custom_maps(MAP_DIRECTORY).unwrap_or_default(),
+            map_index: 0,
+            seed_input,
+            selected: 0,
+            scores: None,
+            message: None,
+        }
+    }
+
+    // Continue only shows up while there's a run to go back to
+    pub fn entries(&self, can_continue: bool) -> Vec<MenuEntry> {
+        let mut entries = vec![MenuEntry::NewGame];
+        if can_continue {
+            entries.push(MenuEntry::Continue);
+        }
+        entries.extend([
+            MenuEntry::CustomMap,
+            MenuEntry::Seed,
+            MenuEntry::Difficulty,
+            MenuEntry::World,
+            MenuEntry::HighScores,
+            MenuEntry::Viewer,
+            MenuEntry::Quit,
+        ]);
+        entries
+    }
+
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn get_setup(&self) -> &GameSetup {
+        &self.setup
+    }
+
+    pub fn get_scores(&self) -> Option<&Vec<String>> {
+        self.scores.as_ref()
+    }
+
+    pub fn get_message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    pub fn set_message(&mut self, message: String) {
+        self.message = Some(message);
+    }
+
+    pub fn label(&self, entry: MenuEntry) -> String {
+        match entry {
+            MenuEntry::NewGame => "New game".to_string(),
+            MenuEntry::Continue => "Continue".to_string(),
+            MenuEntry::CustomMap => match self.maps.get(self.map_index) {
+                Some(path) => format!(
+                    "Play map: < {} >",
+                    path.file_stem().unwrap_or_default().to_string_lossy()
+                ),
+                None => format!("Play map: no maps in {}/", MAP_DIRECTORY),
+            },
+            MenuEntry::Seed if self.seed_input.is_empty() => "Seed: random".to_string(),
+            MenuEntry::Seed => format!("Seed: {}", self.seed_input),
+            MenuEntry::Difficulty => format!("Difficulty: < {} >", self.setup.difficulty),
+            MenuEntry::World if self.setup.endless => "World: < endless >".to_string(),
+            MenuEntry::World => "World: < dungeon >".to_string(),
+            MenuEntry::HighScores => "High scores".to_string(),
+            MenuEntry::Viewer => "Dungeon viewer".to_string(),
+            MenuEntry::Quit => "Quit".to_string(),
+        }
+    }
+
+    // Arrows move and change values, digits type the seed, enter picks
+    pub fn handle_key(&mut self, code: KeyCode, can_continue: bool) -> Option<MenuChoice> {
+        if self.scores.is_some() {
+            self.scores = None;
+            return None;
+        }
+
+        let entries = self.entries(can_continue);
+        self.selected = self.selected.min(entries.len() - 1);
+        let entry = entries[self.selected];
+
+        match code {
+            KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('w') => {
+                self.selected = (self.selected + entries.len() - 1) % entries.len();
+            }
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('s') => {
+                self.selected = (self.selected + 1) % entries.len();
+            }
+            KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('a') => self.cycle(entry, -1),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('d') => self.cycle(entry, 1),
+            KeyCode::Char(c) if entry == MenuEntry::Seed && c.is_ascii_digit() => {
+                let typed = format!("{}{}", self.seed_input, c);
+                // Anything past u64 is dropped rather than wrapped
+                if let Ok(seed) = typed.parse::<u64>() {
+                    self.seed_input = typed;
+                    self.setup.seed = Some(seed);
+                }
+            }
+            KeyCode::Backspace if entry == MenuEntry::Seed => {
+                self.seed_input.pop();
+                self.setup.seed = self.seed_input.parse().ok();
+            }
+            KeyCode::Enter => return self.choose(entry),
+            KeyCode::Esc | KeyCode::Char('q') => return Some(MenuChoice::Quit),
+            _ => {}
+        }
+        None
+    }
+
+    fn cycle(&mut self, entry: MenuEntry, step: isize) {
+        let wrap =
+            |index: usize, len: usize| (index as isize + step).rem_euclid(len as isize) as usize;
+        match entry {
+            MenuEntry::CustomMap if !self.maps.is_empty() => {
+                self.map_index = wrap(self.map_index, self.maps.len());
+            }
+            MenuEntry::Difficulty => {
+                let all = Difficulty::ALL;
+                let index = all
+                    .iter()
+                    .position(|&difficulty| difficulty == self.setup.difficulty)
+                    .unwrap_or_default();
+                self.setup.difficulty = all[wrap(index, all.len())];
+            }
+            MenuEntry::World => self.setup.endless = !self.setup.endless,
+            _ => {}
+        }
+    }
+
+    fn choose(&mut self, entry: MenuEntry) -> Option<MenuChoice> {
+        self.message = None;
+        match entry {
+            MenuEntry::NewGame => Some(MenuChoice::Play(GameSetup {
+                map: None,
+                ..self.setup.clone()
+            })),
+            MenuEntry::Continue => Some(MenuChoice::Continue),
+            MenuEntry::CustomMap => {
+                let path = self.maps.get(self.map_index)?;
+                Some(MenuChoice::Play(GameSetup {
+                    map: Some(path.clone()),
+                    ..self.setup.clone()
+                }))
+            }
+            MenuEntry::HighScores => {
+                self.scores = Some(match HighScores::load(SCORES_FILE) {
+                    Ok(scores) => scores.lines(),
+                    Err(e) => vec![format!("Couldn't read the high scores: {}", e)],
+                });
+                None
+            }
+            MenuEntry::Viewer => Some(MenuChoice::ViewDungeon),
+            MenuEntry::Quit => Some(MenuChoice::Quit),
+            MenuEntry::Seed | MenuEntry::Difficulty | MenuEntry::World => None,
+        }
+    }
+}
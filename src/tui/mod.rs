@@ -1,8 +1,15 @@
+pub mod menu;
 pub mod widgets;
 
-use crate::game::{Game, MessageType};
-use crate::tile::Tile;
+use crate::game::{Action, Game, MessageType, Replay, RunRecord};
+use crate::map::{types::Point, Map};
+use crate::scores::{MORGUE_DIRECTORY, SCORES_FILE};
+use crate::tui::menu::{MainMenu, MenuChoice};
+use crate::tui::widgets::game_over::GameOverWidget;
 use crate::tui::widgets::inventory::InventoryWidget;
+use crate::tui::widgets::item_menu::ItemMenuWidget;
+use crate::tui::widgets::level_up::LevelUpWidget;
+use crate::tui::widgets::main_menu::MainMenuWidget;
 use crossterm::{
     event::{self, KeyCode, KeyEventKind},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
@@ -11,12 +18,13 @@ use crossterm::{
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    style::{Color, Style},
     text::{Line, Span},
     widgets::{Block, BorderType, Borders, Paragraph},
     Terminal,
 };
 use std::io;
+use std::path::Path;
 
 const QUIT_KEY: KeyCode = KeyCode::Char('q');
 const LEFT_MOVEMENT_KEYS: [KeyCode; 3] = [KeyCode::Left, KeyCode::Char('h'), KeyCode::Char('a')];
@@ -25,14 +33,36 @@ const UP_MOVEMENT_KEYS: [KeyCode; 3] = [KeyCode::Up, KeyCode::Char('k'), KeyCode
 const RIGHT_MOVEMENT_KEYS: [KeyCode; 3] = [KeyCode::Right, KeyCode::Char('l'), KeyCode::Char('d')];
 const INTERACT_KEYS: [KeyCode; 2] = [KeyCode::Char(' '), KeyCode::Char('e')];
 const HINT_KEYS: [KeyCode; 2] = [KeyCode::Char('?'), KeyCode::Tab];
+const AUTO_EXPLORE_KEYS: [KeyCode; 2] = [KeyCode::Char('x'), KeyCode::Char('o')];
+const USE_ITEM_KEYS: [KeyCode; 2] = [KeyCode::Char('u'), KeyCode::Char('i')];
+const TRAVEL_KEYS: [KeyCode; 1] = [KeyCode::Char('t')];
+const PICK_LOCK_KEYS: [KeyCode; 1] = [KeyCode::Char('p')];
+const BASH_DOOR_KEYS: [KeyCode; 1] = [KeyCode::Char('b')];
+const TRAVEL_TO_STAIRS_KEYS: [KeyCode; 2] = [KeyCode::Char('>'), KeyCode::Char('<')];
+const CONFIRM_KEYS: [KeyCode; 2] = [KeyCode::Enter, KeyCode::Char('.')];
+const CANCEL_KEYS: [KeyCode; 2] = [KeyCode::Esc, KeyCode::Char('q')];
+const REPLAY_PAUSE_KEYS: [KeyCode; 1] = [KeyCode::Char(' ')];
+const REPLAY_FASTER_KEYS: [KeyCode; 2] = [KeyCode::Char('+'), KeyCode::Char('=')];
+const REPLAY_SLOWER_KEYS: [KeyCode; 1] = [KeyCode::Char('-')];
+
+// How long each action of a replay stays on screen, in milliseconds
+const REPLAY_DELAY: u64 = 100;
+const MIN_REPLAY_DELAY: u64 = 10;
+const MAX_REPLAY_DELAY: u64 = 1600;
 
 // Declare constant for the game log height
 const GAME_LOG_HEIGHT: u16 = 7;
 const INVENTORY_WIDTH: u16 = 25;
 
+const TRAVEL_CURSOR_COLOR: Color = Color::LightBlue;
+
 pub struct Tui {
     terminal: Terminal<CrosstermBackend<std::io::Stdout>>,
     map_area_size: (usize, usize),
+    travel_cursor: Option<Point>, // Some while the player is picking a travel destination
+    item_menu_open: bool,
+    run_record: Option<Result<RunRecord, String>>, // written once the run is over
+    banner: Option<String>, // shown above the map, e.g. how far along a replay is
 }
 
 impl Tui {
@@ -46,15 +76,51 @@ impl Tui {
         Ok(Tui {
             terminal,
             map_area_size: (9999, 9999), // default to something big to avoid flashing on first draw
+            travel_cursor: None,
+            item_menu_open: false,
+            run_record: None,
+            banner: None,
         })
     }
 
+    // Shows the title menu until the player picks something to do
+    pub fn main_menu(
+        &mut self,
+        menu: &mut MainMenu,
+        can_continue: bool,
+    ) -> Result<MenuChoice, io::Error> {
+        loop {
+            self.terminal
+                .draw(|f| f.render_widget(MainMenuWidget::new(menu, can_continue), f.area()))?;
+
+            if event::poll(std::time::Duration::from_millis(100))? {
+                if let event::Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        if let Some(choice) = menu.handle_key(key.code, can_continue) {
+                            return Ok(choice);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Plays until the player quits to the menu, the game is left as it was
+    // so it can be continued unless it's over
     pub fn run(&mut self, game: &mut Game) -> Result<(), io::Error> {
+        self.run_record = None;
+        self.travel_cursor = None;
+        self.item_menu_open = false;
         loop {
             self.draw(game)?;
 
             if game.is_game_over() {
-                // Display the final game state and wait for the player to quit
+                // Leave a morgue file and a high score behind, then display the
+                // final game state and wait for the player to head back to the menu
+                self.run_record = Some(
+                    game.record_run(Path::new(MORGUE_DIRECTORY), Path::new(SCORES_FILE))
+                        .map_err(|e| e.to_string()),
+                );
                 self.draw(game)?;
                 loop {
                     if event::poll(std::time::Duration::from_millis(100))? {
@@ -67,15 +133,48 @@ impl Tui {
                 }
             } else if event::poll(std::time::Duration::from_millis(100))? {
                 if let event::Event::Key(key) = event::read()? {
-                    if key.kind == KeyEventKind::Press {
+                    if key.kind == KeyEventKind::Press && game.has_pending_level_up() {
+                        if key.code == QUIT_KEY {
+                            return Ok(());
+                        }
+                        Self::handle_level_up_key(game, key.code);
+                    } else if key.kind == KeyEventKind::Press && self.item_menu_open {
+                        self.handle_item_menu_key(game, key.code);
+                    } else if key.kind == KeyEventKind::Press && self.travel_cursor.is_some() {
+                        self.handle_travel_cursor_key(game, key.code);
+                    } else if key.kind == KeyEventKind::Press {
                         match key.code {
                             code if code == QUIT_KEY => return Ok(()),
-                            code if LEFT_MOVEMENT_KEYS.contains(&code) => game.move_player(-1, 0),
-                            code if RIGHT_MOVEMENT_KEYS.contains(&code) => game.move_player(1, 0),
-                            code if UP_MOVEMENT_KEYS.contains(&code) => game.move_player(0, -1),
-                            code if DOWN_MOVEMENT_KEYS.contains(&code) => game.move_player(0, 1),
-                            code if INTERACT_KEYS.contains(&code) => game.interact(),
+                            code if LEFT_MOVEMENT_KEYS.contains(&code) => {
+                                game.perform(Action::Move(-1, 0))
+                            }
+                            code if RIGHT_MOVEMENT_KEYS.contains(&code) => {
+                                game.perform(Action::Move(1, 0))
+                            }
+                            code if UP_MOVEMENT_KEYS.contains(&code) => {
+                                game.perform(Action::Move(0, -1))
+                            }
+                            code if DOWN_MOVEMENT_KEYS.contains(&code) => {
+                                game.perform(Action::Move(0, 1))
+                            }
+                            code if INTERACT_KEYS.contains(&code) => game.perform(Action::Interact),
                             code if HINT_KEYS.contains(&code) => game.show_hint(),
+                            code if AUTO_EXPLORE_KEYS.contains(&code) => {
+                                game.perform(Action::AutoExplore)
+                            }
+                            code if TRAVEL_TO_STAIRS_KEYS.contains(&code) => {
+                                game.perform(Action::TravelToStairs)
+                            }
+                            code if USE_ITEM_KEYS.contains(&code) => self.item_menu_open = true,
+                            code if PICK_LOCK_KEYS.contains(&code) => {
+                                game.perform(Action::PickLock)
+                            }
+                            code if BASH_DOOR_KEYS.contains(&code) => {
+                                game.perform(Action::BashDoor)
+                            }
+                            code if TRAVEL_KEYS.contains(&code) => {
+                                self.travel_cursor = Some(game.get_player_position());
+                            }
                             _ => (),
                         }
                     }
@@ -84,8 +183,122 @@ impl Tui {
         }
     }
 
+    fn handle_travel_cursor_key(&mut self, game: &mut Game, code: KeyCode) {
+        let Some(cursor) = self.travel_cursor else {
+            return;
+        };
+        let (map_width, map_height) = game.get_map_size();
+
+        let (dx, dy) = match code {
+            code if LEFT_MOVEMENT_KEYS.contains(&code) => (-1, 0),
+            code if RIGHT_MOVEMENT_KEYS.contains(&code) => (1, 0),
+            code if UP_MOVEMENT_KEYS.contains(&code) => (0, -1),
+            code if DOWN_MOVEMENT_KEYS.contains(&code) => (0, 1),
+            code if CONFIRM_KEYS.contains(&code) => {
+                self.travel_cursor = None;
+                game.perform(Action::TravelTo(cursor));
+                return;
+            }
+            code if CANCEL_KEYS.contains(&code) => {
+                self.travel_cursor = None;
+                return;
+            }
+            _ => return,
+        };
+
+        self.travel_cursor = Some(Point::new(
+            (cursor.x as i32 + dx).clamp(0, map_width as i32 - 1) as usize,
+            (cursor.y as i32 + dy).clamp(0, map_height as i32 - 1) as usize,
+        ));
+    }
+
+    // Plays the recorded actions back one at a time, the run isn't recorded
+    // again when it ends
+    pub fn replay(&mut self, replay: &Replay) -> Result<(), io::Error> {
+        let mut game = replay.setup.start()?;
+        let mut delay = REPLAY_DELAY;
+        let mut paused = false;
+        let mut next = 0;
+        self.run_record = None;
+
+        loop {
+            let state = if next == replay.actions.len() {
+                " finished"
+            } else if paused {
+                " paused"
+            } else {
+                ""
+            };
+            self.banner = Some(format!(
+                "Replay {}/{}{} | space pauses, +/- change speed, q quits",
+                next,
+                replay.actions.len(),
+                state
+            ));
+            self.draw(&game)?;
+
+            if event::poll(std::time::Duration::from_millis(delay))? {
+                if let event::Event::Key(key) = event::read()? {
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    match key.code {
+                        code if code == QUIT_KEY => {
+                            self.banner = None;
+                            return Ok(());
+                        }
+                        code if REPLAY_PAUSE_KEYS.contains(&code) => paused = !paused,
+                        code if REPLAY_FASTER_KEYS.contains(&code) => {
+                            delay = (delay / 2).max(MIN_REPLAY_DELAY);
+                        }
+                        code if REPLAY_SLOWER_KEYS.contains(&code) => {
+                            delay = (delay * 2).min(MAX_REPLAY_DELAY);
+                        }
+                        _ => {}
+                    }
+                }
+            } else if !paused && next < replay.actions.len() {
+                game.perform(replay.actions[next]);
+                next += 1;
+            }
+        }
+    }
+
+    // Number keys pick the matching choice on the level up screen
+    fn handle_level_up_key(game: &mut Game, code: KeyCode) {
+        let KeyCode::Char(c) = code else {
+            return;
+        };
+        let Some(index) = c.to_digit(10).and_then(|digit| digit.checked_sub(1)) else {
+            return;
+        };
+        if (index as usize) < game.get_level_up_choices().len() {
+            game.perform(Action::LevelUp(index as usize));
+        }
+    }
+
+    // Number keys use the item in that slot, 0 is the tenth
+    fn handle_item_menu_key(&mut self, game: &mut Game, code: KeyCode) {
+        if CANCEL_KEYS.contains(&code) {
+            self.item_menu_open = false;
+            return;
+        }
+        let KeyCode::Char(c) = code else {
+            return;
+        };
+        if let Some(digit) = c.to_digit(10) {
+            self.item_menu_open = false;
+            game.perform(Action::UseItem((digit as usize + 9) % 10));
+        }
+    }
+
     fn draw(&mut self, game: &Game) -> Result<(), io::Error> {
-        let map_widget = Self::prepare_map_widget(game, self.map_area_size);
+        let map_widget = Self::prepare_map_widget(
+            game,
+            self.map_area_size,
+            self.travel_cursor,
+            self.banner.clone(),
+        );
         let info_widget = Self::prepare_inventory_widget(game);
         let game_log_widget = Self::prepare_game_log_widget(game);
 
@@ -119,20 +332,32 @@ impl Tui {
             f.render_widget(info_widget, top_chunks[1]);
             f.render_widget(game_log_widget, main_chunks[1]);
 
+            if self.item_menu_open {
+                f.render_widget(ItemMenuWidget::new(game), top_chunks[0]);
+            }
+
+            if game.has_pending_level_up() && !game.is_game_over() {
+                f.render_widget(LevelUpWidget::new(game), top_chunks[0]);
+            }
+
             if game.is_game_over() {
-                let game_over_message = Paragraph::new("Game Over! Press 'q' to quit.")
-                    .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
-                    .alignment(ratatui::layout::Alignment::Center);
-                f.render_widget(game_over_message, top_chunks[0]);
+                f.render_widget(
+                    GameOverWidget::new(game, self.run_record.as_ref()),
+                    top_chunks[0],
+                );
             }
         })?;
 
         Ok(())
     }
 
-    fn prepare_map_widget(game: &Game, map_size: (usize, usize)) -> Paragraph<'static> {
-        let map_arc = game.get_map();
-        let map = map_arc.read().unwrap();
+    fn prepare_map_widget(
+        game: &Game,
+        map_size: (usize, usize),
+        travel_cursor: Option<Point>,
+        banner: Option<String>,
+    ) -> Paragraph<'static> {
+        let map = game.get_map();
         let player_pos = game.get_player_position();
 
         let visible_width = map_size.0;
@@ -141,18 +366,20 @@ impl Tui {
         let start_x = player_pos.x.saturating_sub(visible_width / 2);
         let start_y = player_pos.y.saturating_sub(visible_height / 2);
 
-        let end_x = (start_x + visible_width).min(map[0].len());
-        let end_y = (start_y + visible_height).min(map.len());
+        let end_x = (start_x + visible_width).min(map.width());
+        let end_y = (start_y + visible_height).min(map.height());
 
-        let map_string: Vec<Line> = map[start_y..end_y]
-            .iter()
-            .map(|row| {
+        let map_string: Vec<Line> = (start_y..end_y)
+            .map(|y| {
                 Line::from(
-                    row[start_x..end_x]
-                        .iter()
-                        .map(|&tile: &Tile| {
-                            let style = Style::default().fg(tile.term_fg()).bg(tile.term_bg());
-                            Span::styled(tile.as_char().to_string(), style)
+                    (start_x..end_x)
+                        .map(|x| {
+                            let point = Point::new(x, y);
+                            let mut span = Self::map_tile_span(map, point);
+                            if travel_cursor == Some(point) {
+                                span.style = span.style.bg(TRAVEL_CURSOR_COLOR);
+                            }
+                            span
                         })
                         .collect::<Vec<Span>>(),
                 )
@@ -163,12 +390,28 @@ impl Tui {
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded),
+                    .border_type(BorderType::Rounded)
+                    .title(banner.unwrap_or_default()),
             )
             .style(Style::default())
     }
 
-    fn prepare_inventory_widget(game: &Game) -> InventoryWidget {
+    // Tiles in view are drawn as they are, ones the player has seen before or
+    // had revealed as they remember them
+    pub fn map_tile_span(map: &Map, point: Point) -> Span<'static> {
+        let tile = map.get_tile(point);
+        let glyph = if tile.visibility() == Some(false) && map.is_known(point) {
+            tile.remembered_char()
+        } else {
+            tile.as_char()
+        };
+        Span::styled(
+            glyph.to_string(),
+            Style::default().fg(tile.term_fg()).bg(tile.term_bg()),
+        )
+    }
+
+    fn prepare_inventory_widget(game: &Game) -> InventoryWidget<'_> {
         InventoryWidget::new(game)
     }
 
@@ -0,0 +1,92 @@
+use crate::game::{Game, RunRecord};
+use crate::tui::widgets::centered_rect;
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+const GAME_OVER_WIDTH: u16 = 72;
+
+const VICTORY_COLOR: Color = Color::LightGreen;
+const DEATH_COLOR: Color = Color::LightRed;
+const RANK_COLOR: Color = Color::Yellow;
+
+// Popup with the run summary and the high scores once the run is over,
+// whether the player got out or died trying
+pub struct GameOverWidget<'a> {
+    game: &'a Game,
+    record: Option<&'a Result<RunRecord, String>>, // `None` until the run has been recorded
+}
+
+impl<'a> GameOverWidget<'a> {
+    pub fn new(game: &'a Game, record: Option<&'a Result<RunRecord, String>>) -> Self {
+        Self { game, record }
+    }
+}
+
+impl Widget for GameOverWidget<'_> {
+    fn render(self, area: Rect, buf: &mut ratatui::buffer::Buffer) {
+        let summary = self.game.get_run_summary();
+        let (headline, title, color) = if self.game.has_escaped() {
+            (
+                "You escaped the dungeon!".to_string(),
+                "Victory! Press 'q' for the menu",
+                VICTORY_COLOR,
+            )
+        } else {
+            (
+                format!("You {}.", summary.outcome()),
+                "Game Over! Press 'q' for the menu",
+                DEATH_COLOR,
+            )
+        };
+
+        let mut lines = vec![
+            Line::styled(headline, Style::default().add_modifier(Modifier::BOLD)),
+            Line::from(""),
+        ];
+        lines.extend(summary.lines().into_iter().map(Line::from));
+        lines.push(Line::from(""));
+
+        match self.record {
+            Some(Ok(record)) => {
+                lines.push(Line::styled(
+                    "High scores",
+                    Style::default().add_modifier(Modifier::BOLD),
+                ));
+                for (rank, line) in record.scores.lines().into_iter().enumerate() {
+                    if Some(rank) == record.rank {
+                        lines.push(Line::styled(line, Style::default().fg(RANK_COLOR)));
+                    } else {
+                        lines.push(Line::from(line));
+                    }
+                }
+                lines.push(Line::from(""));
+                lines.push(Line::from(format!(
+                    "Morgue written to {}",
+                    record.morgue.display()
+                )));
+            }
+            Some(Err(e)) => lines.push(Line::from(format!("Couldn't record the run: {}", e))),
+            None => {}
+        }
+
+        // Kill lists can wrap, leave them some room
+        let popup = centered_rect(area, GAME_OVER_WIDTH, lines.len() as u16 + 4);
+
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(color))
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(title),
+            )
+            .render(popup, buf);
+    }
+}
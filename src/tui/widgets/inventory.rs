@@ -1,10 +1,17 @@
-use crate::Game;
+use crate::effect::Effect;
+use crate::game::Game;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
-    widgets::{Gauge, Paragraph, Widget},
+    text::Line,
+    widgets::{Gauge, Paragraph, Widget, Wrap},
 };
 
+// Room for every status effect there is
+const EFFECTS_HEIGHT: u16 = 6;
+// Long objectives wrap onto a second line
+const OBJECTIVE_HEIGHT: u16 = 2;
+
 pub struct InventoryWidget<'a> {
     game: &'a Game,
 }
@@ -22,31 +29,59 @@ impl Widget for InventoryWidget<'_> {
         let create_layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(1), // Level
-                Constraint::Length(2), // Player Info
-                Constraint::Length(1), // HP
-                Constraint::Length(1), // HP Gauge
-                Constraint::Length(1), // empty space
-                Constraint::Length(1), // XP
-                Constraint::Length(1), // XP Gauge
+                Constraint::Length(1),                // Level
+                Constraint::Length(OBJECTIVE_HEIGHT), // Objective
+                Constraint::Length(2),                // Player Info
+                Constraint::Length(1),                // HP
+                Constraint::Length(1),                // HP Gauge
+                Constraint::Length(1),                // empty space
+                Constraint::Length(1),                // XP
+                Constraint::Length(1),                // XP Gauge
+                Constraint::Length(1),                // empty space
+                Constraint::Length(EFFECTS_HEIGHT),   // Status effects
+                Constraint::Length(1),                // Items
+                Constraint::Min(0),                   // Item list
             ]);
 
         let chunks = create_layout.split(area);
 
-        let level = Paragraph::new(format!("Level: {}", player.level))
-            .alignment(ratatui::layout::Alignment::Center);
+        let level = Paragraph::new(format!(
+            "Depth: {} | Level: {}",
+            self.game.get_depth(),
+            player.level
+        ))
+        .alignment(ratatui::layout::Alignment::Center);
         level.render(chunks[0], buf);
 
+        // What opens the exit, ticked off once it's done
+        if let Some((objective, progress, goal)) = self.game.get_objective() {
+            let (text, color) = if progress >= goal {
+                (format!("{} - done", objective.describe()), Color::Green)
+            } else if goal > 1 {
+                (
+                    format!("{} ({}/{})", objective.describe(), progress, goal),
+                    Color::Yellow,
+                )
+            } else {
+                (objective.describe(), Color::Yellow)
+            };
+            Paragraph::new(text)
+                .style(Style::default().fg(color))
+                .alignment(ratatui::layout::Alignment::Center)
+                .wrap(Wrap { trim: true })
+                .render(chunks[1], buf);
+        }
+
         let player_info = Paragraph::new(format!(
             "STR: {} | DEF: {}",
             player.strength, player.defense
         ))
         .alignment(ratatui::layout::Alignment::Center);
 
-        player_info.render(chunks[1], buf);
+        player_info.render(chunks[2], buf);
 
         let hp = Paragraph::new("HP").alignment(ratatui::layout::Alignment::Center);
-        hp.render(chunks[2], buf);
+        hp.render(chunks[3], buf);
 
         // HP Gauge
         let hp_ratio = player.current_hp as f64 / player.max_hp as f64;
@@ -55,10 +90,10 @@ impl Widget for InventoryWidget<'_> {
             .ratio(hp_ratio)
             .label(format!("{} / {}", player.current_hp, player.max_hp));
 
-        hp_gauge.render(chunks[3], buf);
+        hp_gauge.render(chunks[4], buf);
 
         let xp = Paragraph::new("XP").alignment(ratatui::layout::Alignment::Center);
-        xp.render(chunks[5], buf);
+        xp.render(chunks[6], buf);
 
         // XP Gauge
         let xp_ratio = player.exp as f64 / player.xp_for_next_level() as f64;
@@ -67,6 +102,45 @@ impl Widget for InventoryWidget<'_> {
             .ratio(xp_ratio)
             .label(format!("{} / {}", player.exp, player.xp_for_next_level()));
 
-        xp_gauge.render(chunks[6], buf);
+        xp_gauge.render(chunks[7], buf);
+
+        // Active status effects with the turns they have left
+        let effects: Vec<Line> = player
+            .effects
+            .iter()
+            .map(|active| {
+                let color = match active.effect {
+                    Effect::Regeneration { .. } | Effect::Light { .. } | Effect::Warded => {
+                        Color::Green
+                    }
+                    _ => Color::Magenta,
+                };
+                Line::styled(
+                    format!("{} ({})", active.effect.name(), active.turns),
+                    Style::default().fg(color),
+                )
+            })
+            .collect();
+        Paragraph::new(effects)
+            .alignment(ratatui::layout::Alignment::Center)
+            .render(chunks[9], buf);
+
+        let items = Paragraph::new("Items").alignment(ratatui::layout::Alignment::Center);
+        items.render(chunks[10], buf);
+
+        // Carried items by the name the player knows them by
+        let item_list: Vec<Line> = player
+            .inventory
+            .iter()
+            .enumerate()
+            .map(|(slot, &item)| {
+                Line::from(format!(
+                    "{}) {}",
+                    (slot + 1) % 10,
+                    self.game.get_item_name(item)
+                ))
+            })
+            .collect();
+        Paragraph::new(item_list).render(chunks[11], buf);
     }
 }
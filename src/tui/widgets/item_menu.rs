@@ -0,0 +1,58 @@
+use crate::game::Game;
+use crate::tui::widgets::centered_rect;
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
+};
+
+const ITEM_MENU_WIDTH: u16 = 44;
+
+// Popup listing the inventory, number keys pick the item to use
+pub struct ItemMenuWidget<'a> {
+    game: &'a Game,
+}
+
+impl<'a> ItemMenuWidget<'a> {
+    pub fn new(game: &'a Game) -> Self {
+        Self { game }
+    }
+}
+
+impl Widget for ItemMenuWidget<'_> {
+    fn render(self, area: Rect, buf: &mut ratatui::buffer::Buffer) {
+        let inventory = &self.game.get_player().inventory;
+
+        let lines: Vec<Line> = if inventory.is_empty() {
+            vec![Line::from("You aren't carrying anything.")]
+        } else {
+            inventory
+                .iter()
+                .enumerate()
+                .map(|(slot, &item)| {
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{}) ", (slot + 1) % 10),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ),
+                        Span::raw(self.game.get_item_name(item)),
+                    ])
+                })
+                .collect()
+        };
+
+        let popup = centered_rect(area, ITEM_MENU_WIDTH, lines.len() as u16 + 2);
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .alignment(Alignment::Left)
+            .style(Style::default().fg(Color::Yellow))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title("Use which item? (Esc to cancel)"),
+            )
+            .render(popup, buf);
+    }
+}
@@ -0,0 +1,63 @@
+use crate::game::Game;
+use crate::tui::widgets::centered_rect;
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
+};
+
+const LEVEL_UP_WIDTH: u16 = 56;
+
+// Popup listing the numbered choices for a pending level up
+pub struct LevelUpWidget<'a> {
+    game: &'a Game,
+}
+
+impl<'a> LevelUpWidget<'a> {
+    pub fn new(game: &'a Game) -> Self {
+        Self { game }
+    }
+}
+
+impl Widget for LevelUpWidget<'_> {
+    fn render(self, area: Rect, buf: &mut ratatui::buffer::Buffer) {
+        let player = self.game.get_player();
+        let choices = self.game.get_level_up_choices();
+
+        let mut lines = vec![
+            Line::from(format!("You reached level {}!", player.level)),
+            Line::from(""),
+        ];
+        lines.extend(choices.iter().enumerate().map(|(index, choice)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{}) {}", index + 1, choice.name()),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(format!(" - {}", choice.description())),
+            ])
+        }));
+        if player.pending_level_ups > 1 {
+            lines.push(Line::from(""));
+            lines.push(Line::from(format!(
+                "{} more choices after this one",
+                player.pending_level_ups - 1
+            )));
+        }
+
+        let popup = centered_rect(area, LEVEL_UP_WIDTH, lines.len() as u16 + 2);
+
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .alignment(Alignment::Left)
+            .style(Style::default().fg(Color::Yellow))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title("Level up! Press a number to choose"),
+            )
+            .render(popup, buf);
+    }
+}
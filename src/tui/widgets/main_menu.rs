@@ -0,0 +1,81 @@
+use crate::tui::menu::MainMenu;
+use crate::tui::widgets::centered_rect;
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
+};
+
+const MENU_WIDTH: u16 = 60;
+const SCORES_WIDTH: u16 = 72;
+
+const SELECTED_COLOR: Color = Color::Yellow;
+const MESSAGE_COLOR: Color = Color::LightRed;
+
+// The title menu, with the high score table on top when it's open
+pub struct MainMenuWidget<'a> {
+    menu: &'a MainMenu,
+    can_continue: bool,
+}
+
+impl<'a> MainMenuWidget<'a> {
+    pub fn new(menu: &'a MainMenu, can_continue: bool) -> Self {
+        Self { menu, can_continue }
+    }
+}
+
+impl Widget for MainMenuWidget<'_> {
+    fn render(self, area: Rect, buf: &mut ratatui::buffer::Buffer) {
+        let mut lines = vec![
+            Line::styled("RustyRogue", Style::default().add_modifier(Modifier::BOLD)),
+            Line::from(""),
+        ];
+        for (index, entry) in self.menu.entries(self.can_continue).into_iter().enumerate() {
+            let label = self.menu.label(entry);
+            if index == self.menu.get_selected() {
+                lines.push(Line::styled(
+                    format!("> {} <", label),
+                    Style::default()
+                        .fg(SELECTED_COLOR)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            } else {
+                lines.push(Line::from(label));
+            }
+        }
+        if let Some(message) = self.menu.get_message() {
+            lines.push(Line::from(""));
+            lines.push(Line::styled(
+                message.to_string(),
+                Style::default().fg(MESSAGE_COLOR),
+            ));
+        }
+
+        let popup = centered_rect(area, MENU_WIDTH, lines.len() as u16 + 2);
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title("Enter to pick, arrows to change, digits for the seed"),
+            )
+            .render(popup, buf);
+
+        if let Some(scores) = self.menu.get_scores() {
+            let lines: Vec<Line> = scores.iter().map(|line| Line::from(line.clone())).collect();
+            let popup = centered_rect(area, SCORES_WIDTH, lines.len() as u16 + 2);
+            Clear.render(popup, buf);
+            Paragraph::new(lines)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title("High scores, press any key"),
+                )
+                .render(popup, buf);
+        }
+    }
+}
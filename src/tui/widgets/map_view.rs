@@ -1,4 +1,9 @@
-use crate::map::types::{GameMapTiles, Point};
+use crate::generator::connectivity;
+use crate::generator::map::MapGenerator;
+use crate::generator::room::{Room, RoomShape, RoomType};
+use crate::map::grid::TileGrid;
+use crate::map::types::{Coordinate, Point};
+use crate::map::Map;
 use crate::tile::Tile;
 use crossterm::{
     event::{self, KeyCode, KeyEventKind},
@@ -7,17 +12,351 @@ use crossterm::{
 };
 use ratatui::{
     backend::CrosstermBackend,
-    style::Style,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
     text::{Line, Span},
     widgets::{Block, BorderType, Borders, Paragraph},
     Terminal,
 };
+use std::collections::HashSet;
 use std::io;
-use std::sync::{Arc, RwLock};
+
+const CURSOR_COLOR: Color = Color::Yellow;
+const CURSED_COLOR: Color = Color::Indexed(53);
+const UNREACHABLE_COLOR: Color = Color::Indexed(88);
+// Neighbouring partitions get different colours
+const PARTITION_COLORS: [Color; 6] = [
+    Color::Indexed(19),
+    Color::Indexed(22),
+    Color::Indexed(52),
+    Color::Indexed(54),
+    Color::Indexed(23),
+    Color::Indexed(58),
+];
+
+const INFO_HEIGHT: u16 = 4; // the inspector below the map, borders included
+
+// What can be drawn over the map while tuning the generator
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Overlay {
+    Tiles, // just the map
+    Partitions,
+    Rooms,
+    Curses,
+    Unreachable,
+}
+
+impl Overlay {
+    pub const ALL: [Overlay; 5] = [
+        Overlay::Tiles,
+        Overlay::Partitions,
+        Overlay::Rooms,
+        Overlay::Curses,
+        Overlay::Unreachable,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Overlay::Tiles => "tiles",
+            Overlay::Partitions => "bsp partitions",
+            Overlay::Rooms => "rooms",
+            Overlay::Curses => "curse areas",
+            Overlay::Unreachable => "unreachable",
+        }
+    }
+
+    pub fn next(&self) -> Overlay {
+        let index = Overlay::ALL.iter().position(|overlay| overlay == self);
+        Overlay::ALL[index.map_or(0, |index| (index + 1) % Overlay::ALL.len())]
+    }
+}
+
+// How the viewer asks for another level
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Reseed {
+    New,  // a different level
+    Same, // the same seed again, e.g. after editing prefabs or data files
+}
+
+// A level and what's known about how it was made
+pub struct ViewedLevel {
+    tiles: TileGrid,
+    caption: String, // shown in the title, e.g. the seed and generator stats
+    partitions: Vec<Rect>,
+    rooms: Vec<Room>,
+    cursed: HashSet<Point>,
+    unreachable: HashSet<Point>,
+}
+
+impl ViewedLevel {
+    pub fn from_generator(generator: &MapGenerator) -> Self {
+        let partitions = generator.get_bsp_tree().map_or(Vec::new(), |root| {
+            root.leaves()
+                .into_iter()
+                .map(|leaf| rect(leaf.x, leaf.y, leaf.width, leaf.height))
+                .collect()
+        });
+        let caption = format!(
+            "seed {} | {} | {}",
+            generator.get_seed(),
+            generator.get_connectivity_report().summary(),
+            generator.get_spawn_report().summary()
+        );
+        let start = generator.get_spawn_room().map(Room::center);
+        ViewedLevel::new(
+            generator.get_dungeon().clone(),
+            caption,
+            partitions,
+            generator.get_rooms().clone(),
+            start,
+        )
+    }
+
+    // A map without a generator behind it, reachable from its '@' or first floor
+    pub fn from_map(map: &Map, caption: String) -> Self {
+        let start = map
+            .find_player()
+            .or_else(|| map.find_tile(|tile| matches!(tile, Tile::Floor { .. })));
+        let tiles = map.get_grid().cloned().unwrap_or_default();
+        ViewedLevel::new(tiles, caption, Vec::new(), Vec::new(), start)
+    }
+
+    fn new(
+        tiles: TileGrid,
+        caption: String,
+        partitions: Vec<Rect>,
+        rooms: Vec<Room>,
+        start: Option<Point>,
+    ) -> Self {
+        // The same curse areas the game works out when the level is played
+        let mut map = Map::from_tiles(tiles.clone());
+        map.apply_obelisk_curses();
+        let cursed = map.get_grid().map_or(HashSet::new(), |grid| {
+            grid.points()
+                .filter(|&point| grid.is_cursed(point))
+                .collect()
+        });
+        let unreachable = start.map_or(HashSet::new(), |start| {
+            connectivity::unreachable_points(&tiles, start)
+                .into_iter()
+                .collect()
+        });
+        ViewedLevel {
+            tiles,
+            caption,
+            partitions,
+            rooms,
+            cursed,
+            unreachable,
+        }
+    }
+
+    pub fn get_tiles(&self) -> &TileGrid {
+        &self.tiles
+    }
+
+    pub fn get_partitions(&self) -> &[Rect] {
+        &self.partitions
+    }
+
+    pub fn get_cursed(&self) -> &HashSet<Point> {
+        &self.cursed
+    }
+
+    pub fn get_unreachable(&self) -> &HashSet<Point> {
+        &self.unreachable
+    }
+
+    fn partition_at(&self, point: Point) -> Option<usize> {
+        self.partitions
+            .iter()
+            .position(|partition| contains(partition, point))
+    }
+
+    fn room_at(&self, point: Point) -> Option<usize> {
+        self.rooms.iter().position(|room| {
+            point.x >= room.location.x
+                && point.y >= room.location.y
+                && point.x <= room.location.x + room.width
+                && point.y <= room.location.y + room.height
+        })
+    }
+
+    // The colour the overlay paints under `point`, if any
+    fn overlay_color(&self, overlay: Overlay, point: Point) -> Option<Color> {
+        match overlay {
+            Overlay::Tiles => None,
+            Overlay::Partitions => {
+                let index = self.partition_at(point)?;
+                on_edge(&self.partitions[index], point)
+                    .then(|| PARTITION_COLORS[index % PARTITION_COLORS.len()])
+            }
+            Overlay::Rooms => {
+                let room = &self.rooms[self.room_at(point)?];
+                let walls = rect(
+                    room.location.x,
+                    room.location.y,
+                    room.width + 1,
+                    room.height + 1,
+                );
+                on_edge(&walls, point).then(|| room_color(room.room_type))
+            }
+            Overlay::Curses => self.cursed.contains(&point).then_some(CURSED_COLOR),
+            Overlay::Unreachable => self
+                .unreachable
+                .contains(&point)
+                .then_some(UNREACHABLE_COLOR),
+        }
+    }
+
+    // Everything known about the tile under the cursor
+    pub fn inspect(&self, point: Point) -> String {
+        let Some(tile) = self.tiles.get(point) else {
+            return format!("({}, {}) outside the map", point.x, point.y);
+        };
+        let mut parts = vec![format!(
+            "({}, {}) '{}' {}",
+            point.x,
+            point.y,
+            tile.glyph(),
+            tile.describe()
+        )];
+        if let Some(index) = self.room_at(point) {
+            let room = &self.rooms[index];
+            let shape = match &room.shape {
+                RoomShape::Rectangle => "rectangle".to_string(),
+                RoomShape::Cave => "cave".to_string(),
+                RoomShape::Prefab { name, .. } => format!("prefab '{}'", name),
+            };
+            parts.push(format!(
+                "room {}: {} {} {}x{}",
+                index,
+                room.room_type.name(),
+                shape,
+                room.width + 1,
+                room.height + 1
+            ));
+        }
+        if let Some(index) = self.partition_at(point) {
+            let partition = self.partitions[index];
+            parts.push(format!(
+                "partition {}: {}x{} at ({}, {})",
+                index, partition.width, partition.height, partition.x, partition.y
+            ));
+        }
+        if self.cursed.contains(&point) {
+            parts.push("cursed".to_string());
+        }
+        if self.unreachable.contains(&point) {
+            parts.push("unreachable".to_string());
+        }
+        parts.join(" | ")
+    }
+}
+
+fn rect(x: Coordinate, y: Coordinate, width: Coordinate, height: Coordinate) -> Rect {
+    let clamp = |value: Coordinate| value.min(u16::MAX as Coordinate) as u16;
+    Rect::new(clamp(x), clamp(y), clamp(width), clamp(height))
+}
+
+fn contains(area: &Rect, point: Point) -> bool {
+    let (x, y) = (point.x, point.y);
+    x >= area.left() as Coordinate
+        && y >= area.top() as Coordinate
+        && x < area.right() as Coordinate
+        && y < area.bottom() as Coordinate
+}
+
+fn on_edge(area: &Rect, point: Point) -> bool {
+    contains(area, point)
+        && (point.x == area.left() as Coordinate
+            || point.y == area.top() as Coordinate
+            || point.x + 1 == area.right() as Coordinate
+            || point.y + 1 == area.bottom() as Coordinate)
+}
+
+fn room_color(room_type: RoomType) -> Color {
+    match room_type {
+        RoomType::Normal => Color::Indexed(240),
+        RoomType::Spawn => Color::Indexed(28),
+        RoomType::Boss => Color::Indexed(124),
+        RoomType::Vault => Color::Indexed(136),
+        RoomType::Secret => Color::Indexed(97),
+        RoomType::Obelisk => Color::Indexed(90),
+        RoomType::Shrine => Color::Indexed(30),
+        RoomType::Library => Color::Indexed(25),
+    }
+}
+
+// Where the viewer is looking, kept apart from the terminal so it can be
+// worked out for any screen size
+#[derive(Clone, Debug, PartialEq)]
+pub struct ViewerState {
+    pub camera: Point,
+    pub cursor: Point,
+    pub overlay: Overlay,
+}
+
+impl ViewerState {
+    pub fn new() -> Self {
+        ViewerState {
+            camera: Point::new(0, 0),
+            cursor: Point::new(0, 0),
+            overlay: Overlay::Tiles,
+        }
+    }
+
+    // Moves the cursor, scrolling the camera along once it reaches the edge
+    // of the `viewport`, and never off a `map` sized level
+    pub fn move_cursor(
+        &mut self,
+        dx: isize,
+        dy: isize,
+        map: (usize, usize),
+        viewport: (usize, usize),
+    ) {
+        let step = |value: usize, delta: isize, len: usize| {
+            value
+                .saturating_add_signed(delta)
+                .min(len.saturating_sub(1))
+        };
+        self.cursor = Point::new(
+            step(self.cursor.x, dx, map.0),
+            step(self.cursor.y, dy, map.1),
+        );
+        self.follow_cursor(map, viewport);
+    }
+
+    // Keeps the cursor in view and the camera on the map, e.g. after a resize
+    pub fn follow_cursor(&mut self, map: (usize, usize), viewport: (usize, usize)) {
+        let follow = |camera: usize, cursor: usize, view: usize, len: usize| {
+            let view = view.max(1);
+            let camera = if cursor < camera {
+                cursor
+            } else if cursor >= camera + view {
+                cursor + 1 - view
+            } else {
+                camera
+            };
+            camera.min(len.saturating_sub(view))
+        };
+        self.camera = Point::new(
+            follow(self.camera.x, self.cursor.x, viewport.0, map.0),
+            follow(self.camera.y, self.cursor.y, viewport.1, map.1),
+        );
+    }
+}
+
+impl Default for ViewerState {
+    fn default() -> Self {
+        ViewerState::new()
+    }
+}
 
 pub struct MapView {
     terminal: Terminal<CrosstermBackend<std::io::Stdout>>,
-    camera_position: Point,
+    state: ViewerState,
+    message: Option<String>, // e.g. why the level couldn't be regenerated
 }
 
 impl MapView {
@@ -29,112 +368,164 @@ impl MapView {
         let terminal = Terminal::new(backend)?;
         Ok(MapView {
             terminal,
-            camera_position: Point::new(0, 0),
+            state: ViewerState::new(),
+            message: None,
         })
     }
 
-    pub fn run(&mut self, dungeon: Arc<RwLock<GameMapTiles>>) -> Result<(), io::Error> {
-        let (viewport_width, viewport_height) = {
-            let terminal_size = self.terminal.size()?;
-            (
-                terminal_size.width as usize - 2,
-                terminal_size.height as usize - 2,
-            )
-        };
+    // Shows `level` until 'q' is pressed. `regenerate` hands out the next
+    // level when asked, None when there's no other level to show.
+    pub fn run<F>(&mut self, mut level: ViewedLevel, mut regenerate: F) -> Result<(), io::Error>
+    where
+        F: FnMut(Reseed) -> io::Result<Option<ViewedLevel>>,
+    {
         loop {
-            self.draw(dungeon.clone())?;
-
-            if event::poll(std::time::Duration::from_millis(100))? {
-                if let event::Event::Key(key) = event::read()? {
-                    if key.kind == KeyEventKind::Press {
-                        let dungeon = dungeon.read().unwrap();
-                        let dungeon_height = dungeon.len();
-                        let dungeon_width = dungeon.first().map_or(0, |row| row.len());
-                        drop(dungeon);
-
-                        match key.code {
-                            KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('a') => {
-                                self.camera_position.x = self.camera_position.x.saturating_sub(1);
-                            }
-                            KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('s') => {
-                                self.camera_position.y = (self.camera_position.y + 1)
-                                    .min(dungeon_height.saturating_sub(viewport_height));
-                            }
-                            KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('w') => {
-                                self.camera_position.y = self.camera_position.y.saturating_sub(1);
-                            }
-                            KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('d') => {
-                                self.camera_position.x = (self.camera_position.x + 1)
-                                    .min(dungeon_width.saturating_sub(viewport_width));
-                            }
-                            KeyCode::Char('q') => {
-                                return Ok(());
-                            }
-                            _ => {}
-                        }
+            // The viewport is worked out every frame, so resizing just works
+            let viewport = self.viewport()?;
+            let map = (level.tiles.width(), level.tiles.height());
+            self.state.follow_cursor(map, viewport);
+            self.draw(&level, viewport)?;
 
-                        // Ensure camera position doesn't exceed dungeon boundaries
-                        self.camera_position.x = self
-                            .camera_position
-                            .x
-                            .min(dungeon_width.saturating_sub(viewport_width));
-                        self.camera_position.y = self
-                            .camera_position
-                            .y
-                            .min(dungeon_height.saturating_sub(viewport_height));
-                    }
+            if !event::poll(std::time::Duration::from_millis(100))? {
+                continue;
+            }
+            let event::Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            let (page_x, page_y) = (viewport.0.max(1) as isize, viewport.1.max(1) as isize);
+            let movement = match key.code {
+                KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('a') => Some((-1, 0)),
+                KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('d') => Some((1, 0)),
+                KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('w') => Some((0, -1)),
+                KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('s') => Some((0, 1)),
+                // A screen at a time
+                KeyCode::Home | KeyCode::Char('H') | KeyCode::Char('A') => Some((-page_x, 0)),
+                KeyCode::End | KeyCode::Char('L') | KeyCode::Char('D') => Some((page_x, 0)),
+                KeyCode::PageUp | KeyCode::Char('K') | KeyCode::Char('W') => Some((0, -page_y)),
+                KeyCode::PageDown | KeyCode::Char('J') | KeyCode::Char('S') => Some((0, page_y)),
+                _ => None,
+            };
+            if let Some((dx, dy)) = movement {
+                self.state.move_cursor(dx, dy, map, viewport);
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('o') | KeyCode::Tab => self.state.overlay = self.state.overlay.next(),
+                KeyCode::Char(c @ '1'..='5') => {
+                    self.state.overlay = Overlay::ALL[c as usize - '1' as usize];
+                }
+                KeyCode::Char('r') | KeyCode::Char('R') => {
+                    let reseed = if key.code == KeyCode::Char('r') {
+                        Reseed::New
+                    } else {
+                        Reseed::Same
+                    };
+                    self.message = match regenerate(reseed) {
+                        Ok(Some(next)) => {
+                            level = next;
+                            None
+                        }
+                        Ok(None) => Some("This map can't be regenerated".to_string()),
+                        Err(e) => Some(format!("Couldn't regenerate: {}", e)),
+                    };
                 }
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                _ => {}
             }
         }
     }
-    pub fn draw(&mut self, dungeon: Arc<RwLock<GameMapTiles>>) -> Result<(), io::Error> {
-        let dungeon = dungeon.read().unwrap();
-        let terminal_size = self.terminal.size()?;
-        let (width, height) = (terminal_size.width, terminal_size.height);
-        let viewport_width = width as usize - 2; // Subtract 2 for borders
-        let viewport_height = height as usize - 2; // Subtract 2 for borders
 
-        let dungeon_height = dungeon.len();
-        let dungeon_width = dungeon.first().map_or(0, |row| row.len());
+    // Room for tiles inside the map block's borders
+    fn viewport(&self) -> Result<(usize, usize), io::Error> {
+        let size = self.terminal.size()?;
+        let (map_area, _) = split(Rect::new(0, 0, size.width, size.height));
+        Ok((
+            map_area.width.saturating_sub(2) as usize,
+            map_area.height.saturating_sub(2) as usize,
+        ))
+    }
+
+    fn draw(&mut self, level: &ViewedLevel, viewport: (usize, usize)) -> Result<(), io::Error> {
+        let state = &self.state;
+        let dungeon = &level.tiles;
+        let (viewport_width, viewport_height) = viewport;
 
-        let map_string: Vec<Line> = (0..viewport_height)
+        let lines: Vec<Line> = (state.camera.y..state.camera.y + viewport_height)
             .map(|y| {
-                let row_index = (self.camera_position.y + y).min(dungeon_height.saturating_sub(1));
                 Line::from(
-                    (0..viewport_width)
+                    (state.camera.x..state.camera.x + viewport_width)
                         .map(|x| {
-                            let col_index =
-                                (self.camera_position.x + x).min(dungeon_width.saturating_sub(1));
-                            let tile = dungeon
-                                .get(row_index)
-                                .and_then(|row| row.get(col_index))
-                                .unwrap_or(&Tile::Empty);
-                            let style = Style::default().fg(tile.term_fg()).bg(tile.term_bg());
-                            Span::styled(tile.as_char().to_string(), style)
+                            let point = Point::new(x, y);
+                            let tile = dungeon.get(point).unwrap_or(Tile::Empty);
+                            let background = level
+                                .overlay_color(state.overlay, point)
+                                .unwrap_or(tile.term_bg());
+                            let mut style = Style::default().fg(tile.term_fg()).bg(background);
+                            if point == state.cursor {
+                                style = style.fg(Color::Black).bg(CURSOR_COLOR);
+                            }
+                            Span::styled(tile.glyph().to_string(), style)
                         })
                         .collect::<Vec<Span>>(),
                 )
             })
             .collect();
 
-        let title = format!(
-            "Dungeon {}x{} | View {}x{}",
-            dungeon_width, dungeon_height, viewport_width, viewport_height
+        let mut title = format!(
+            "Dungeon {}x{} | View {}x{} | Overlay: {}",
+            dungeon.width(),
+            dungeon.height(),
+            viewport_width,
+            viewport_height,
+            state.overlay.name()
         );
-        let paragraph = Paragraph::new(map_string).block(
+        if !level.caption.is_empty() {
+            title = format!("{} | {}", title, level.caption);
+        }
+        let map = Paragraph::new(lines).block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Plain)
                 .title(title),
         );
+
+        let info = Paragraph::new(vec![
+            Line::from(level.inspect(state.cursor)),
+            Line::from(self.message.clone().unwrap_or_else(|| {
+                "arrows move, shift/page keys a screen, o or 1-5 overlays, r new seed, R same seed, q quit"
+                    .to_string()
+            })),
+        ])
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title("Inspect"),
+        );
+
         self.terminal.draw(|f| {
-            let size = f.area();
-            f.render_widget(paragraph, size);
+            let (map_area, info_area) = split(f.area());
+            f.render_widget(map, map_area);
+            f.render_widget(info, info_area);
         })?;
         Ok(())
     }
 }
 
+// The map on top, the inspector below it
+fn split(area: Rect) -> (Rect, Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(INFO_HEIGHT)])
+        .split(area);
+    (chunks[0], chunks[1])
+}
+
 impl Drop for MapView {
     fn drop(&mut self) {
         disable_raw_mode().unwrap();
@@ -1,2 +1,20 @@
+pub mod game_over;
 pub mod inventory;
+pub mod item_menu;
+pub mod level_up;
+pub mod main_menu;
 pub mod map_view;
+
+use ratatui::layout::Rect;
+
+// A popup of the given size in the middle of `area`, shrunk if it doesn't fit
+pub fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
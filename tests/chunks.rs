@@ -0,0 +1,167 @@
+mod common;
+
+use rustyrogue::data::{items, monsters};
+use rustyrogue::generator::chunk::ChunkGenerator;
+use rustyrogue::map::chunk::{ChunkCoord, ChunkStore, CHUNK_SIZE};
+use rustyrogue::map::grid::TileGrid;
+use rustyrogue::map::types::Point;
+use rustyrogue::tile::{Lock, Tile};
+use std::path::PathBuf;
+
+use common::same_tiles;
+
+fn worlds(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("rustyrogue-{}-{}", name, std::process::id()))
+}
+
+fn is_open(tiles: &TileGrid, point: Point) -> bool {
+    tiles
+        .get(point)
+        .is_some_and(|tile| !matches!(tile, Tile::Wall { .. } | Tile::Empty))
+}
+
+#[test]
+fn same_seed_gives_the_same_chunks() {
+    let coord = ChunkCoord::new(510, 514);
+    let (a, rooms_a) = ChunkGenerator::new(7).generate(coord);
+    let (b, rooms_b) = ChunkGenerator::new(7).generate(coord);
+    let (other, _) = ChunkGenerator::new(8).generate(coord);
+
+    assert!(same_tiles(&a, &b));
+    assert_eq!(rooms_a.len(), rooms_b.len());
+    assert!(!same_tiles(&a, &other));
+}
+
+#[test]
+fn chunk_monsters_come_from_the_definitions() {
+    let generator = ChunkGenerator::new(3);
+    let mut seen = 0;
+    for x in 500..510 {
+        let (tiles, _) = generator.generate(ChunkCoord::new(x, 512));
+        for tile in tiles.points().filter_map(|point| tiles.get(point)) {
+            if let Some(def) = tile.monster_def() {
+                assert_eq!(tile.monster_hp(), Some(def.hp));
+                seen += 1;
+            }
+        }
+    }
+    assert!(seen > 0);
+}
+
+#[test]
+fn gates_connect_across_chunk_borders() {
+    let generator = ChunkGenerator::new(11);
+    let last = CHUNK_SIZE - 1;
+    for (x, y) in [(511, 511), (512, 512), (300, 700)] {
+        let (tiles, _) = generator.generate(ChunkCoord::new(x, y));
+        let (east, _) = generator.generate(ChunkCoord::new(x + 1, y));
+        let (south, _) = generator.generate(ChunkCoord::new(x, y + 1));
+
+        let east_gates: Vec<_> = (0..CHUNK_SIZE)
+            .filter(|&row| is_open(&tiles, Point::new(last, row)))
+            .collect();
+        let west_gates: Vec<_> = (0..CHUNK_SIZE)
+            .filter(|&row| is_open(&east, Point::new(0, row)))
+            .collect();
+        assert!(!east_gates.is_empty());
+        assert_eq!(east_gates, west_gates);
+
+        let south_gates: Vec<_> = (0..CHUNK_SIZE)
+            .filter(|&column| is_open(&tiles, Point::new(column, last)))
+            .collect();
+        let north_gates: Vec<_> = (0..CHUNK_SIZE)
+            .filter(|&column| is_open(&south, Point::new(column, 0)))
+            .collect();
+        assert!(!south_gates.is_empty());
+        assert_eq!(south_gates, north_gates);
+    }
+}
+
+#[test]
+fn unloaded_chunks_come_back_as_they_were_left() {
+    let worlds = worlds("chunks-round-trip");
+    let mut store = ChunkStore::new(5, &worlds).unwrap();
+    let spawn = store.spawn_point();
+    store.stream_around(spawn).unwrap();
+
+    // Tiles with stats of their own, which all have to survive the disk
+    let bat = monsters().find_by_name("bat").unwrap();
+    let key = items().find_by_name("iron key").unwrap();
+    let edits = [
+        Tile::monster(bat).with_monster_hp(1),
+        Tile::Item {
+            visible: false,
+            item: key,
+        },
+        Tile::Door {
+            visible: false,
+            open: false,
+            lock: Some(Lock::Key(key)),
+        },
+        Tile::Archway {
+            lock: Some(Lock::Sealed),
+        },
+        Tile::Secret {
+            visible: false,
+            rarity: 100,
+        },
+        Tile::Shrine {
+            visible: false,
+            used: true,
+        },
+        Tile::Obelisk {
+            visible: false,
+            curse: false,
+            fov: 4,
+            damage_hp: 2,
+            reduce_fov_radius: 1,
+        },
+        Tile::Sign {
+            visible: false,
+            lore: 3,
+        },
+    ];
+    let (tiles, local) = store.grid_at_mut(spawn).unwrap();
+    for (offset, &tile) in edits.iter().enumerate() {
+        tiles.set(Point::new(local.x + offset, local.y), tile);
+    }
+    let before = tiles.clone();
+
+    // Far enough for the spawn chunk to be written out, then back again
+    let far = Point::new(spawn.x + CHUNK_SIZE * 4, spawn.y);
+    store.stream_around(far).unwrap();
+    assert!(store.grid_at(spawn).is_none());
+    assert!(store
+        .directory()
+        .read_dir()
+        .unwrap()
+        .any(|entry| entry.is_ok()));
+    store.stream_around(spawn).unwrap();
+
+    let (after, _) = store.grid_at(spawn).unwrap();
+    assert!(same_tiles(&before, after));
+
+    drop(store);
+    std::fs::remove_dir_all(&worlds).unwrap();
+}
+
+#[test]
+fn new_worlds_leave_other_runs_alone() {
+    let worlds = worlds("chunks-runs");
+    let first = ChunkStore::new(9, &worlds).unwrap();
+    let marker = first.directory().join("0_0.chunk");
+    std::fs::write(&marker, "kept").unwrap();
+
+    let second = ChunkStore::new(9, &worlds).unwrap();
+    assert_ne!(first.directory(), second.directory());
+    assert!(marker.exists());
+
+    // Each run cleans up after itself
+    let second_directory = second.directory().to_path_buf();
+    drop(second);
+    assert!(!second_directory.exists());
+    assert!(marker.exists());
+
+    drop(first);
+    std::fs::remove_dir_all(&worlds).unwrap();
+}
@@ -0,0 +1,64 @@
+use rustyrogue::cli::{parse, Command};
+use rustyrogue::game::Difficulty;
+use rustyrogue::generator::layout::LayoutKind;
+use rustyrogue::generator::map::DungeonSize;
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(str::to_string).collect()
+}
+
+#[test]
+fn subcommands_parse() {
+    assert!(matches!(parse(&[]), Ok(Command::Menu)));
+    assert!(matches!(parse(&args("--scores")), Ok(Command::Scores)));
+    assert!(matches!(parse(&args("help")), Ok(Command::Help)));
+    assert!(matches!(
+        parse(&args("view maps/tutorial.txt")),
+        Ok(Command::View(_))
+    ));
+
+    let Ok(Command::Play(setup)) = parse(&args(
+        "play --seed 42 --difficulty hard --layout caves --size 120x60 --rooms 5-12",
+    )) else {
+        panic!("play should parse");
+    };
+    assert_eq!(setup.seed, Some(42));
+    assert_eq!(setup.difficulty, Difficulty::Hard);
+    assert_eq!(setup.layout, Some(LayoutKind::Caves));
+    assert_eq!((setup.size.width, setup.size.height), (120, 60));
+    assert_eq!(
+        (setup.size.min_room_size, setup.size.max_room_size),
+        (5, 12)
+    );
+
+    let Ok(Command::Generate(options)) = parse(&args("generate --depth 3 --out level.txt")) else {
+        panic!("generate should parse");
+    };
+    assert_eq!(options.depth, 3);
+    assert_eq!(options.size, DungeonSize::VIEWER);
+    assert!(options.out.is_some());
+}
+
+#[test]
+fn bad_arguments_are_rejected() {
+    for line in [
+        "dance",
+        "play --seed",
+        "play --seed abc",
+        "play --seed 1 --seed 2",
+        "play --colour red",
+        "play --size 10x10",
+        "play --size 20x20 --rooms 5-10",
+        "stats --size 20x20 --rooms 5-10 --layout bsp",
+        "play --rooms 20-10",
+        "play --load save.txt --seed 3",
+        "play --map maps/tutorial.txt --layout bsp",
+        "play --endless --size 100x100",
+        "generate --depth 0",
+        "view",
+        "replay a.txt b.txt",
+        "scores now",
+    ] {
+        assert!(parse(&args(line)).is_err(), "'{}' should be rejected", line);
+    }
+}
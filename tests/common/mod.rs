@@ -0,0 +1,34 @@
+// Each test crate only uses some of these
+#![allow(dead_code)]
+
+use rustyrogue::map::grid::TileGrid;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Same size and the same tiles everywhere, visibility and curses included
+pub fn same_tiles(a: &TileGrid, b: &TileGrid) -> bool {
+    a.width() == b.width()
+        && a.height() == b.height()
+        && a.points().all(|point| a.get(point) == b.get(point))
+}
+
+// A directory of the test's own, gone once the test is over even if it fails
+pub struct TempDirectory(PathBuf);
+
+impl TempDirectory {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("rustyrogue-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&path).unwrap();
+        TempDirectory(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDirectory {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,105 @@
+use rustyrogue::generator::connectivity::reachability_cost;
+use rustyrogue::generator::layout::LayoutKind;
+use rustyrogue::generator::map::MapGenerator;
+use rustyrogue::map::grid::TileGrid;
+use rustyrogue::map::pathfinding::DijkstraMap;
+use rustyrogue::map::types::Point;
+use rustyrogue::tile::Tile;
+
+fn generate(layout: LayoutKind, seed: u64) -> MapGenerator {
+    let mut map_generator = MapGenerator::new(120, 80);
+    map_generator
+        .set_layout(layout)
+        .set_seed(seed)
+        .generate(6, 14);
+    map_generator
+}
+
+fn is_wall(tiles: &TileGrid, x: usize, y: usize) -> bool {
+    matches!(tiles.get(Point::new(x, y)), Some(Tile::Wall { .. }))
+}
+
+#[test]
+fn every_room_is_reachable_from_spawn() {
+    for layout in LayoutKind::ALL {
+        for seed in 0..5 {
+            let map_generator = generate(layout, seed);
+            let report = map_generator.get_connectivity_report();
+            assert_eq!(report.rooms, map_generator.get_rooms().len());
+            assert!((1..=MapGenerator::MAX_GENERATION_ATTEMPTS).contains(&report.attempts));
+            assert!(
+                !report.gave_up,
+                "{layout:?} seed {seed}: {}",
+                report.summary()
+            );
+            assert!(
+                report.is_valid(),
+                "{layout:?} seed {seed}: {}",
+                report.summary()
+            );
+
+            let spawn = map_generator
+                .get_structure()
+                .get_spawn_room()
+                .expect("levels have a spawn room")
+                .room
+                .center();
+            let reachable =
+                DijkstraMap::new(map_generator.get_dungeon(), &[spawn], reachability_cost);
+            for (index, room) in map_generator.get_rooms().iter().enumerate() {
+                assert!(
+                    room.interior_points()
+                        .any(|point| reachable.get(point).is_some()),
+                    "{layout:?} seed {seed} room {index}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn doors_sit_in_wall_gaps() {
+    for layout in LayoutKind::ALL {
+        for seed in 0..3 {
+            let map_generator = generate(layout, seed);
+            let tiles = map_generator.get_dungeon();
+            assert!(map_generator
+                .get_connectivity_report()
+                .invalid_doors
+                .is_empty());
+            for point in tiles.points() {
+                if !matches!(tiles.get(point), Some(Tile::Door { .. })) {
+                    continue;
+                }
+                let (x, y) = (point.x, point.y);
+                assert!(
+                    (is_wall(tiles, x - 1, y) && is_wall(tiles, x + 1, y))
+                        || (is_wall(tiles, x, y - 1) && is_wall(tiles, x, y + 1)),
+                    "{layout:?} seed {seed} door at {x},{y}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn repairs_are_counted() {
+    // Caves often cut rooms off and need corridors dug to them
+    let reports: Vec<_> = (0..20)
+        .map(|seed| {
+            generate(LayoutKind::Caves, seed)
+                .get_connectivity_report()
+                .clone()
+        })
+        .collect();
+    assert!(
+        reports
+            .iter()
+            .map(|report| report.repaired_rooms)
+            .sum::<usize>()
+            > 0
+    );
+    for report in reports.iter().filter(|report| report.repaired_rooms > 0) {
+        assert!(report.is_valid(), "{}", report.summary());
+    }
+}
@@ -0,0 +1,110 @@
+use rustyrogue::data::item::{ItemBook, ItemEffect};
+use rustyrogue::data::monster::{Behaviour, Bestiary};
+use rustyrogue::data::{GameData, DATA_DIRECTORY};
+use rustyrogue::tile::Tile;
+
+const BAT: &str = "
+[bat]
+glyph = B
+colour = red
+sdl_colour = 0, 255, 0
+hp = 4
+damage = 3
+fov = 8
+behaviour = wander
+weight = 40
+exp = 10
+";
+
+const HEALING: &str = "
+[potion of healing]
+category = potion
+glyph = !
+colour = lightred
+effect = heal 15
+rarity = 1
+";
+
+#[test]
+fn shipped_definitions_load() {
+    let data = GameData::load(DATA_DIRECTORY).unwrap();
+
+    let brute = data.monsters.find_by_name("brute").unwrap();
+    assert_eq!(data.monsters.get(brute).behaviour, Behaviour::Guard);
+    let healing = data.items.find_by_name("potion of healing").unwrap();
+    assert_eq!(data.items.get(healing).effect, ItemEffect::Heal(15));
+}
+
+#[test]
+fn monster_tiles_start_at_full_hp() {
+    let tile = Tile::from_char('W');
+    let def = tile.monster_def().unwrap();
+    assert_eq!(def.name, "wither");
+    assert_eq!(tile.monster_hp(), Some(def.hp));
+}
+
+#[test]
+fn speed_and_depth_have_defaults() {
+    let bestiary = Bestiary::parse(BAT).unwrap();
+    let bat = bestiary.get(bestiary.find_by_name("bat").unwrap());
+    assert_eq!(bat.speed, 100);
+    assert!(bat.spawns_at(1));
+    assert!(bat.spawns_at(50));
+}
+
+#[test]
+fn invalid_monsters_are_rejected() {
+    let cases = [
+        (BAT.replace("hp = 4", "hp = 0"), "needs some hp"),
+        (
+            BAT.replace("glyph = B", "glyph = #"),
+            "already used by the map",
+        ),
+        (
+            BAT.replace("colour = red\n", "colour = reddish\n"),
+            "unknown colour",
+        ),
+        (BAT.replace("wander", "flee"), "unknown behaviour"),
+        (
+            BAT.replace("exp = 10", "exp = 10\nsmell = 3"),
+            "unknown field",
+        ),
+        (BAT.replace("weight = 40\n", ""), "missing 'weight'"),
+        (
+            format!("{}{}", BAT, BAT.replace("[bat]", "[vampire]")),
+            "share the glyph",
+        ),
+        (format!("{}{}", BAT, BAT), "defined twice"),
+    ];
+    for (source, error) in cases {
+        let result = Bestiary::parse(&source);
+        assert!(
+            result.as_ref().is_err_and(|e| e.contains(error)),
+            "expected '{}', got {:?}",
+            error,
+            result.map(|_| ())
+        );
+    }
+}
+
+#[test]
+fn invalid_items_are_rejected() {
+    let cases = [
+        (HEALING.replace("rarity = 1", "rarity = 5"), "rarity"),
+        (HEALING.replace("rarity = 1", "rarity = 10"), "rarity 1"),
+        (HEALING.replace("heal 15", "heal"), "number(s) after it"),
+        (HEALING.replace("potion\n", "wand\n"), "unknown category"),
+        (HEALING.replace("potion\n", "key\n"), "keys can only unlock"),
+        (HEALING.replace("heal 15", "unlock"), "has to be a key"),
+        (HEALING.replace("heal 15", "none"), "has to be an artefact"),
+    ];
+    for (source, error) in cases {
+        let result = ItemBook::parse(&source);
+        assert!(
+            result.as_ref().is_err_and(|e| e.contains(error)),
+            "expected '{}', got {:?}",
+            error,
+            result.map(|_| ())
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use rustyrogue::data::monsters;
+use rustyrogue::effect::Effect;
+use rustyrogue::game::Game;
+use rustyrogue::map::types::Point;
+use rustyrogue::map::Map;
+use rustyrogue::player::perk::{LevelUpChoice, Perk};
+use rustyrogue::tile::Tile;
+
+const BRUTE: Point = Point { x: 1, y: 1 };
+const WITHER: Point = Point { x: 7, y: 1 };
+
+// A brute and a wither at the ends of a corridor, with two secrets next to
+// the player that are worth a level between them
+fn game_with_monsters() -> Game {
+    let mut tiles = Map::parse("#########\n#...@...#\n#########\n")
+        .unwrap()
+        .get_grid()
+        .unwrap()
+        .clone();
+    tiles.set(
+        BRUTE,
+        Tile::monster(monsters().find_by_name("brute").unwrap()),
+    );
+    tiles.set(
+        WITHER,
+        Tile::monster(monsters().find_by_name("wither").unwrap()),
+    );
+    for x in [3, 5] {
+        tiles.set(
+            Point::new(x, 1),
+            Tile::Secret {
+                visible: false,
+                rarity: 1000,
+            },
+        );
+    }
+
+    Game::from_tiles(tiles, 3).unwrap()
+}
+
+fn monster_hp(game: &Game, point: Point) -> Option<u32> {
+    game.get_map().get_tile(point).monster_hp()
+}
+
+fn is_poisoned(game: &Game, point: Point) -> bool {
+    game.get_monster_effects(point)
+        .is_some_and(|effects| effects.any(|effect| matches!(effect, Effect::Poison { .. })))
+}
+
+#[test]
+fn venomous_hits_poison_monsters_until_it_wears_off() {
+    let mut game = game_with_monsters();
+    game.interact();
+    game.interact();
+    assert!(game.has_pending_level_up());
+    game.choose_level_up(LevelUpChoice::Perk(Perk::Venomous));
+    assert!(game.get_player().has_perk(Perk::Venomous));
+
+    // 8 hp, 5 from the hit and 2 from the poison on the same turn
+    game.move_player(1, 0);
+    game.move_player(1, 0);
+    game.move_player(1, 0);
+    assert_eq!(monster_hp(&game, WITHER), Some(1));
+    assert!(is_poisoned(&game, WITHER));
+
+    // The next turn's poison finishes it off
+    let exp = game.get_player().exp;
+    game.move_player(-1, 0);
+    assert_eq!(monster_hp(&game, WITHER), None);
+    assert!(game.get_monster_effects(WITHER).is_none());
+    assert!(game
+        .get_game_log_messages()
+        .iter()
+        .any(|message| message.message == "The wither succumbs! (+25 XP)"));
+    assert!(game.get_player().exp >= exp + 25);
+
+    // 30 hp, the brute shrugs off three turns of poison
+    for _ in 0..4 {
+        game.move_player(-1, 0);
+    }
+    assert_eq!(game.get_player_position(), Point::new(2, 1));
+    assert_eq!(monster_hp(&game, BRUTE), Some(23));
+    assert!(is_poisoned(&game, BRUTE));
+
+    game.move_player(1, 0);
+    assert_eq!(monster_hp(&game, BRUTE), Some(21));
+    game.move_player(1, 0);
+    assert_eq!(monster_hp(&game, BRUTE), Some(19));
+    assert!(!is_poisoned(&game, BRUTE));
+
+    // Worn off, no more damage
+    game.move_player(1, 0);
+    assert_eq!(monster_hp(&game, BRUTE), Some(19));
+    assert!(game.get_monster_effects(BRUTE).is_none());
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rustyrogue::data::{items, monsters};
+use rustyrogue::generator::layout::LayoutKind;
+use rustyrogue::generator::map::MapGenerator;
+use rustyrogue::generator::prefab::{PrefabLibrary, PREFAB_DIRECTORY};
+use rustyrogue::map::hints::{hint_path, write_map, MapHints};
+use rustyrogue::map::types::Point;
+use rustyrogue::map::Map;
+use rustyrogue::tile::{Lock, Tile};
+use std::fs;
+
+use common::{same_tiles, TempDirectory};
+
+#[test]
+fn generated_levels_round_trip() {
+    let directory = TempDirectory::new("hints-round-trip");
+
+    for (seed, layout) in LayoutKind::ALL.into_iter().enumerate() {
+        let mut map_generator = MapGenerator::new(160, 80);
+        map_generator
+            .set_prefabs(PrefabLibrary::load(PREFAB_DIRECTORY).unwrap())
+            .set_layout(layout)
+            .set_seed(seed as u64)
+            .set_depth(3)
+            .generate(6, 16);
+        let mut tiles = map_generator.get_dungeon().clone();
+
+        // A few tiles no generator makes on its own
+        let key = items().find_by_name("iron key").unwrap();
+        let potion = items().find_by_name("potion of healing").unwrap();
+        let bat = monsters().find_by_name("bat").unwrap();
+        let floor: Vec<Point> = tiles
+            .points()
+            .filter(|&point| matches!(tiles.get(point), Some(Tile::Floor { .. })))
+            .take(4)
+            .collect();
+        tiles.set(
+            floor[0],
+            Tile::Door {
+                visible: false,
+                open: false,
+                lock: Some(Lock::Key(key)),
+            },
+        );
+        tiles.set(floor[1], Tile::Archway { lock: None });
+        tiles.set(floor[2], Tile::monster(bat).with_monster_hp(1));
+        tiles.set(
+            floor[3],
+            Tile::Item {
+                visible: false,
+                item: potion,
+            },
+        );
+
+        let path = directory.path().join(format!("{}.txt", layout));
+        write_map(&tiles, &path).unwrap();
+        let loaded = Map::load(&path.to_string_lossy()).unwrap();
+        assert!(
+            same_tiles(loaded.get_grid().unwrap(), &tiles),
+            "{} level changed on the way back",
+            layout
+        );
+    }
+}
+
+#[test]
+fn hints_must_match_their_tiles() {
+    let mut tiles = Map::parse("#?#\n#.#\n")
+        .unwrap()
+        .get_grid()
+        .unwrap()
+        .clone();
+    let hints =
+        MapHints::parse(r#"{"secrets": [{"position": 1, "metadata": {"rarity": 1000}}]}"#).unwrap();
+    hints.apply(&mut tiles).unwrap();
+    assert!(matches!(
+        tiles.get(Point::new(1, 0)),
+        Some(Tile::Secret { rarity: 1000, .. })
+    ));
+
+    for bad in [
+        r#"{"secrets": [{"position": 0, "metadata": {"rarity": 10}}]}"#,
+        r#"{"secrets": [{"position": 60, "metadata": {"rarity": 10}}]}"#,
+        r#"{"secrets": [{"position": 1}]}"#,
+        r#"{"doors": [{"position": 1, "metadata": {"lock": "sealed"}}]}"#,
+        r#"{"items": [{"position": 4, "metadata": {"item": "no such thing"}}]}"#,
+    ] {
+        let hints = MapHints::parse(bad).unwrap();
+        assert!(hints.apply(&mut tiles.clone()).is_err(), "{} applied", bad);
+    }
+    assert!(MapHints::parse("{\"secrets\": 3}").is_err());
+    assert_eq!(
+        hint_path("maps/tutorial.txt").to_str(),
+        Some("maps/tutorial.hint.json")
+    );
+}
+
+#[test]
+fn hand_written_hints_are_kept_as_written() {
+    let source = fs::read_to_string("maps/tutorial.hint.json").unwrap();
+    let hints = MapHints::parse(&source).unwrap();
+    assert_eq!(hints.to_json(), source);
+    assert_eq!(hints.secrets[0].resolve_to.as_deref(), Some("^"));
+    assert_eq!(hints.secrets[0].metadata.damage, Some(5));
+    assert_eq!(hints.doors[0].resolve_to.as_deref(), Some("V"));
+
+    // Nothing resolves tiles yet, so the tutorial loads as it's drawn
+    let drawn = Map::parse_with(
+        &fs::read_to_string("maps/tutorial.txt").unwrap(),
+        &mut StdRng::seed_from_u64(4),
+    )
+    .unwrap();
+    let loaded = Map::load_with("maps/tutorial.txt", &mut StdRng::seed_from_u64(4)).unwrap();
+    assert!(same_tiles(
+        loaded.get_grid().unwrap(),
+        drawn.get_grid().unwrap()
+    ));
+}
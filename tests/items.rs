@@ -0,0 +1,54 @@
+use rustyrogue::data::items;
+use rustyrogue::game::Game;
+use rustyrogue::map::types::Point;
+use rustyrogue::map::Map;
+use rustyrogue::tile::Tile;
+
+// A corridor with a banishing scroll and a potion to pick up on the way
+fn game_with_items() -> Game {
+    let mut tiles = Map::parse("######\n#@...#\n######\n")
+        .unwrap()
+        .get_grid()
+        .unwrap()
+        .clone();
+    for (x, name) in [(2, "scroll of banish obelisk"), (3, "potion of healing")] {
+        let item = items().find_by_name(name).unwrap();
+        tiles.set(
+            Point::new(x, 1),
+            Tile::Item {
+                visible: false,
+                item,
+            },
+        );
+    }
+
+    Game::from_tiles(tiles, 7).unwrap()
+}
+
+#[test]
+fn unused_banishing_scrolls_keep_their_slot() {
+    let mut game = game_with_items();
+    game.move_player(1, 0);
+    game.move_player(1, 0);
+    let inventory = game.get_player().inventory.clone();
+    assert_eq!(inventory.len(), 2);
+
+    // Every message about the finds reads right, whatever the unknown names are
+    for item in &inventory {
+        let name = game.get_item_name(*item);
+        let article = if "aeiou".contains(&name[..1]) {
+            "an"
+        } else {
+            "a"
+        };
+        let expected = format!("You find {} {}.", article, name);
+        assert!(game
+            .get_game_log_messages()
+            .iter()
+            .any(|message| message.message == expected));
+    }
+
+    // No obelisk anywhere, so the scroll stays where it was
+    game.use_item(0);
+    assert_eq!(game.get_player().inventory, inventory);
+}
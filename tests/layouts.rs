@@ -0,0 +1,38 @@
+use rustyrogue::generator::layout::LayoutKind;
+use rustyrogue::generator::map::MapGenerator;
+use rustyrogue::generator::room::RoomType;
+
+#[test]
+fn every_layout_makes_a_playable_level() {
+    for layout in LayoutKind::ALL {
+        for seed in [1, 42, 1234] {
+            let mut map_generator = MapGenerator::new(120, 80);
+            map_generator
+                .set_layout(layout)
+                .set_seed(seed)
+                .generate(6, 14);
+
+            let rooms = map_generator.get_rooms();
+            assert!(!rooms.is_empty(), "{layout:?} seed {seed} has no rooms");
+
+            let spawn = map_generator
+                .get_spawn_room()
+                .unwrap_or_else(|| panic!("{layout:?} seed {seed} has no spawn room"));
+            assert!(matches!(spawn.room_type, RoomType::Spawn));
+            assert_eq!(
+                rooms
+                    .iter()
+                    .filter(|room| matches!(room.room_type, RoomType::Spawn))
+                    .count(),
+                1
+            );
+
+            let report = map_generator.get_connectivity_report();
+            assert!(
+                report.is_valid(),
+                "{layout:?} seed {seed}: {}",
+                report.summary()
+            );
+        }
+    }
+}
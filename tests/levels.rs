@@ -0,0 +1,142 @@
+use rustyrogue::data::monster::Behaviour;
+use rustyrogue::game::GameSetup;
+use rustyrogue::generator::layout::LayoutKind;
+use rustyrogue::generator::map::DungeonSize;
+use rustyrogue::generator::map::MapGenerator;
+use rustyrogue::generator::prefab::PrefabLibrary;
+use rustyrogue::item::Item;
+use rustyrogue::map::grid::TileGrid;
+use rustyrogue::map::pathfinding::DijkstraMap;
+use rustyrogue::map::types::Point;
+use rustyrogue::objective::{artefacts, banishing_item, Objective};
+use rustyrogue::tile::{Lock, Tile};
+
+#[test]
+fn exit_is_locked_and_out_of_the_way() {
+    for (seed, depth) in [(0, 1), (1, 1), (2, 5), (3, 5)] {
+        let mut map_generator = MapGenerator::new(200, 120);
+        map_generator
+            .set_prefabs(PrefabLibrary::default())
+            .set_seed(seed)
+            .set_depth(depth)
+            .generate(8, 20);
+        let dungeon = map_generator.get_dungeon();
+        let exit = map_generator.get_exit().expect("every level has a way out");
+        // Sealed until the objective is done, or locked with a key when the
+        // exit only has to be reached
+        let lock = dungeon.get(exit).and_then(|tile| tile.lock());
+        match map_generator.get_objective() {
+            Objective::ReachExit => {
+                assert!(matches!(lock, Some(Lock::Key(_))), "seed {seed}: {lock:?}")
+            }
+            _ => assert_eq!(lock, Some(Lock::Sealed), "seed {seed}"),
+        }
+
+        // Every neighbour is open floor the player can get to
+        let spawn = map_generator.get_spawn_room().unwrap().center();
+        let reachable = DijkstraMap::new(dungeon, &[spawn], |_, tile| {
+            (tile.is_walkable() || matches!(tile, Tile::Door { lock: None, .. })).then_some(1)
+        });
+        for dy in 0..3 {
+            for dx in 0..3 {
+                let point = Point::new(exit.x + dx - 1, exit.y + dy - 1);
+                if point != exit {
+                    assert!(
+                        matches!(dungeon.get(point), Some(Tile::Floor { .. })),
+                        "seed {seed}: {point:?} beside the exit isn't floor"
+                    );
+                    assert!(
+                        reachable.get(point).is_some(),
+                        "seed {seed}: {point:?} beside the exit can't be reached"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn entrance_opens_onto_the_spawn_point() {
+    for (seed, layout) in LayoutKind::ALL.into_iter().enumerate() {
+        let setup = GameSetup {
+            seed: Some(seed as u64),
+            layout: Some(layout),
+            size: DungeonSize {
+                width: 120,
+                height: 60,
+                min_room_size: 6,
+                max_room_size: 14,
+            },
+            ..Default::default()
+        };
+        let game = setup.start().unwrap();
+        let map = game.get_map();
+        let player = game.get_player_position();
+        let beside_player = (0..3)
+            .flat_map(|dy| (0..3).map(move |dx| (dx, dy)))
+            .any(|(dx, dy)| {
+                let point = Point::new(player.x + dx - 1, player.y + dy - 1);
+                matches!(map.get_tile(point), Tile::Archway { .. })
+            });
+        assert!(beside_player, "{layout} seed {seed}");
+    }
+}
+
+fn count(dungeon: &TileGrid, wanted: impl Fn(&Tile) -> bool) -> usize {
+    dungeon.rows().flatten().filter(|tile| wanted(tile)).count()
+}
+
+#[test]
+fn objectives_are_always_achievable() {
+    let artefact = artefacts()[0];
+    let asked = [
+        Objective::SlayBoss,
+        Objective::CleanseObelisks(2),
+        Objective::RetrieveArtefact(artefact),
+    ];
+    for (seed, objective) in asked.into_iter().enumerate() {
+        let seed = seed as u64;
+        let mut map_generator = MapGenerator::new(200, 120);
+        map_generator
+            .set_prefabs(PrefabLibrary::default())
+            .set_objective(objective)
+            .set_seed(seed)
+            .generate(8, 20);
+        let dungeon = map_generator.get_dungeon();
+        let items = |wanted: Item| {
+            count(
+                dungeon,
+                |tile| matches!(tile, Tile::Item { item, .. } if *item == wanted),
+            )
+        };
+
+        // Whatever it settled for has what it takes on the map
+        match map_generator.get_objective() {
+            Objective::ReachExit => {}
+            Objective::SlayBoss => {
+                let bosses = count(dungeon, |tile| {
+                    tile.monster_def()
+                        .is_some_and(|def| def.behaviour == Behaviour::Boss)
+                });
+                assert!(bosses > 0, "seed {seed}: no boss");
+            }
+            Objective::CleanseObelisks(needed) => {
+                let obelisks = count(dungeon, |tile| {
+                    matches!(tile, Tile::Obelisk { curse: true, .. })
+                });
+                assert!(
+                    items(banishing_item().unwrap()) >= needed as usize,
+                    "seed {seed}: not enough banishing scrolls"
+                );
+                assert!(
+                    obelisks >= needed as usize,
+                    "seed {seed}: not enough obelisks"
+                );
+            }
+            Objective::RetrieveArtefact(item) => {
+                assert_eq!(item, artefact, "seed {seed}");
+                assert_eq!(items(artefact), 1, "seed {seed}");
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use rustyrogue::generator::map::MapGenerator;
+use rustyrogue::generator::prefab::PrefabLibrary;
+use rustyrogue::map::pathfinding::DijkstraMap;
+use rustyrogue::map::types::Point;
+use rustyrogue::objective::Objective;
+use rustyrogue::tile::{Lock, Tile};
+
+// Everything the player can get to without opening a lock
+fn without_locks(_: Point, tile: Tile) -> Option<u32> {
+    match tile {
+        Tile::Pit { .. } | Tile::Empty => None,
+        tile if tile.lock().is_some() => None,
+        Tile::Door { .. } => Some(1),
+        tile if tile.is_monster() || tile.is_walkable() => Some(1),
+        _ => None,
+    }
+}
+
+#[test]
+fn every_lock_has_a_reachable_key() {
+    let mut locked_doors = 0;
+    for seed in 0..10 {
+        let mut map_generator = MapGenerator::new(200, 120);
+        map_generator
+            .set_prefabs(PrefabLibrary::default())
+            .set_seed(seed)
+            .generate(8, 20);
+        let spawn = map_generator.get_spawn_room().unwrap().center();
+        let dungeon = map_generator.get_dungeon();
+        let reachable = DijkstraMap::new(dungeon, &[spawn], without_locks);
+
+        let mut keys = Vec::new();
+        let mut locks = Vec::new();
+        for (y, row) in dungeon.rows().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                match *tile {
+                    Tile::Item { item, .. } if reachable.get(Point::new(x, y)).is_some() => {
+                        keys.push(item)
+                    }
+                    Tile::Door {
+                        open,
+                        lock: Some(Lock::Key(key)),
+                        ..
+                    } => {
+                        assert!(!open, "seed {seed}: locked doors are shut");
+                        locks.push(key);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        assert_eq!(
+            map_generator.get_spawn_report().count("locked door"),
+            locks.len(),
+            "seed {seed}"
+        );
+        locked_doors += locks.len();
+        for key in locks {
+            assert!(
+                keys.contains(&key),
+                "seed {seed}: no {} this side of the lock",
+                key.name()
+            );
+        }
+    }
+    assert!(locked_doors > 0, "seeds 0 to 9 should lock some vault");
+}
+
+#[test]
+fn locked_exit_has_its_key_on_the_near_side() {
+    for seed in 0..6 {
+        let mut map_generator = MapGenerator::new(160, 100);
+        map_generator
+            .set_prefabs(PrefabLibrary::default())
+            .set_objective(Objective::ReachExit)
+            .set_seed(seed)
+            .generate(8, 20);
+        let dungeon = map_generator.get_dungeon();
+        let exit = map_generator.get_exit().expect("every level has a way out");
+        let Some(Tile::Archway {
+            lock: Some(Lock::Key(key)),
+        }) = dungeon.get(exit)
+        else {
+            panic!("seed {seed}: the exit should take a key");
+        };
+        assert_eq!(map_generator.get_spawn_report().count("locked archway"), 1);
+
+        let spawn = map_generator.get_spawn_room().unwrap().center();
+        let reachable = DijkstraMap::new(dungeon, &[spawn], without_locks);
+        assert!(
+            dungeon.points().any(|point| reachable.get(point).is_some()
+                && dungeon.get(point)
+                    == Some(Tile::Item {
+                        visible: false,
+                        item: key
+                    })),
+            "seed {seed}: no {} this side of the exit",
+            key.name()
+        );
+    }
+}
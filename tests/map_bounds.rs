@@ -0,0 +1,114 @@
+use proptest::prelude::*;
+use rustyrogue::generator::layout::LayoutKind;
+use rustyrogue::generator::map::MapGenerator;
+use rustyrogue::generator::prefab::{PrefabLibrary, PREFAB_DIRECTORY};
+use rustyrogue::map::error::MapError;
+use rustyrogue::map::grid::TileGrid;
+use rustyrogue::map::types::Point;
+use rustyrogue::map::Map;
+use rustyrogue::tile::Tile;
+
+fn layouts() -> impl Strategy<Value = LayoutKind> {
+    prop::sample::select(LayoutKind::ALL.to_vec())
+}
+
+fn floor_map(width: usize, height: usize) -> Map {
+    let mut tiles = TileGrid::new(width, height);
+    tiles.fill(Tile::Floor {
+        visible: false,
+        cursed: false,
+    });
+    Map::from_tiles(tiles)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn generated_rooms_stay_inside_the_map(
+        width in 0usize..90,
+        height in 0usize..60,
+        min_room_size in 0usize..12,
+        extra_room_size in 0usize..20,
+        layout in layouts(),
+    ) {
+        let mut map_generator = MapGenerator::new(width, height);
+        map_generator
+            .set_prefabs(PrefabLibrary::load(PREFAB_DIRECTORY).unwrap())
+            .set_layout(layout)
+            .generate(min_room_size, min_room_size + extra_room_size);
+
+        let tiles = map_generator.get_dungeon();
+        prop_assert_eq!((tiles.width(), tiles.height()), (width, height));
+        for room in map_generator.get_rooms() {
+            prop_assert!(room.footprint().all(|point| tiles.contains(point)));
+        }
+        if let Some(spawn_room) = map_generator.get_spawn_room() {
+            prop_assert!(tiles.contains(spawn_room.center()));
+        }
+    }
+
+    #[test]
+    fn map_queries_are_bounds_checked(
+        width in 0usize..20,
+        height in 0usize..20,
+        x in 0usize..30,
+        y in 0usize..30,
+    ) {
+        let mut map = floor_map(width, height);
+        let point = Point::new(x, y);
+        let inside = x < width && y < height;
+
+        prop_assert_eq!(map.try_set(point, Tile::Wall { visible: false }).is_ok(), inside);
+        match map.try_get(point) {
+            Ok(tile) => {
+                let is_wall = matches!(tile, Tile::Wall { .. });
+                prop_assert!(inside && is_wall);
+            }
+            Err(MapError::OutOfBounds { .. }) => prop_assert!(!inside),
+            Err(e) => prop_assert!(false, "unexpected error: {}", e),
+        }
+
+        // The lenient versions never panic either
+        map.set_tile(point, Tile::Player { is_dead: false, is_cursed: false });
+        prop_assert_eq!(map.is_walkable(point), inside);
+        map.update_fov(point, 5);
+        map.apply_obelisk_curses();
+    }
+
+    #[test]
+    fn ragged_rows_are_rejected(widths in prop::collection::vec(1usize..10, 1..10)) {
+        let source = widths
+            .iter()
+            .map(|&width| ".".repeat(width))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        match widths.iter().position(|&width| width != widths[0]) {
+            Some(index) => match Map::parse(&source).err() {
+                Some(MapError::RaggedRow { line, length, expected }) => {
+                    prop_assert_eq!(line, index + 1);
+                    prop_assert_eq!(length, widths[index]);
+                    prop_assert_eq!(expected, widths[0]);
+                }
+                other => prop_assert!(false, "expected a ragged row error, got {:?}", other),
+            },
+            None => {
+                let map = Map::parse(&source).unwrap();
+                prop_assert_eq!((map.width(), map.height()), (widths[0], widths.len()));
+            }
+        }
+    }
+}
+
+#[test]
+fn empty_maps_are_rejected() {
+    assert!(matches!(Map::parse("").err(), Some(MapError::Empty)));
+    assert!(matches!(Map::parse("\n\n").err(), Some(MapError::Empty)));
+}
+
+#[test]
+fn hand_made_maps_load() {
+    let map = Map::load("maps/tutorial.txt").unwrap();
+    assert_eq!((map.width(), map.height()), (78, 34));
+}
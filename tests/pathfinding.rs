@@ -0,0 +1,129 @@
+use rustyrogue::map::grid::TileGrid;
+use rustyrogue::map::pathfinding::{astar, DijkstraMap, PathCost};
+use rustyrogue::map::types::Point;
+use rustyrogue::tile::Tile;
+
+fn grid(rows: &[&str]) -> TileGrid {
+    TileGrid::from_rows(
+        rows.iter()
+            .map(|row| row.chars().map(Tile::from_char).collect())
+            .collect(),
+    )
+}
+
+fn walkable(_: Point, tile: Tile) -> Option<u32> {
+    PathCost::WALKABLE.of(tile)
+}
+
+#[test]
+fn astar_finds_the_shortest_way_around_walls() {
+    let tiles = grid(&[
+        "#######", //
+        "#.....#", //
+        "#.###.#", //
+        "#.#...#", //
+        "#######",
+    ]);
+    let path = astar(&tiles, Point::new(1, 3), Point::new(3, 3), walkable).unwrap();
+    assert_eq!(path.len(), 10);
+    assert_eq!(path.last(), Some(&Point::new(3, 3)));
+    assert!(!path.contains(&Point::new(1, 3)));
+    assert_eq!(
+        astar(&tiles, Point::new(1, 3), Point::new(1, 3), walkable),
+        Some(Vec::new())
+    );
+    for pair in path.windows(2) {
+        assert_eq!(
+            pair[0].x.abs_diff(pair[1].x) + pair[0].y.abs_diff(pair[1].y),
+            1
+        );
+    }
+}
+
+#[test]
+fn unreachable_goals_have_no_path() {
+    let tiles = grid(&[
+        "#######", //
+        "#..#..#", //
+        "#######",
+    ]);
+    let (start, goal) = (Point::new(1, 1), Point::new(5, 1));
+    assert!(astar(&tiles, start, goal, walkable).is_none());
+
+    let dijkstra_map = DijkstraMap::new(&tiles, &[goal], walkable);
+    assert_eq!(dijkstra_map.get(start), None);
+    assert_eq!(dijkstra_map.next_step(start), None);
+    assert_eq!(dijkstra_map.path_from(start), None);
+}
+
+#[test]
+fn dijkstra_maps_lead_to_the_nearest_of_several_sources() {
+    let tiles = grid(&[
+        "##########", //
+        "#........#", //
+        "##########",
+    ]);
+    let sources = [Point::new(1, 1), Point::new(8, 1)];
+    let dijkstra_map = DijkstraMap::new(&tiles, &sources, walkable);
+
+    assert_eq!(dijkstra_map.get(Point::new(1, 1)), Some(1));
+    assert_eq!(dijkstra_map.get(Point::new(6, 1)), Some(3));
+    assert_eq!(
+        dijkstra_map.next_step(Point::new(6, 1)),
+        Some(Point::new(7, 1))
+    );
+    assert_eq!(
+        dijkstra_map.next_step(Point::new(3, 1)),
+        Some(Point::new(2, 1))
+    );
+    assert_eq!(
+        dijkstra_map.path_from(Point::new(6, 1)),
+        Some(vec![Point::new(7, 1), Point::new(8, 1)])
+    );
+    assert_eq!(dijkstra_map.path_from(Point::new(8, 1)), Some(vec![]));
+}
+
+#[test]
+fn cautious_paths_go_around_curses_and_pits() {
+    let mut tiles = grid(&[
+        "#######", //
+        "#.....#", //
+        "#.#.#.#", //
+        "#..V..#", //
+        "#######",
+    ]);
+    tiles.set(
+        Point::new(3, 1),
+        Tile::Floor {
+            visible: false,
+            cursed: true,
+        },
+    );
+    let (start, goal) = (Point::new(1, 3), Point::new(5, 3));
+    let cautious = |_: Point, tile: Tile| PathCost::CAUTIOUS.of(tile);
+
+    // The pit is on the short way and the curse on the long one
+    assert!(astar(&tiles, start, goal, cautious).is_none());
+    let reckless = astar(&tiles, start, goal, walkable).unwrap();
+    assert!(reckless.contains(&Point::new(3, 3)));
+
+    tiles.set(
+        Point::new(3, 1),
+        Tile::Floor {
+            visible: false,
+            cursed: false,
+        },
+    );
+    let careful = astar(&tiles, start, goal, cautious).unwrap();
+    assert!(careful.contains(&Point::new(3, 1)));
+    assert!(!careful.contains(&Point::new(3, 3)));
+}
+
+#[test]
+fn cautious_paths_open_doors_but_not_locks() {
+    assert_eq!(PathCost::CAUTIOUS.of(Tile::from_char('/')), Some(2));
+    assert_eq!(PathCost::WALKABLE.of(Tile::from_char('/')), None);
+    assert_eq!(PathCost::WALKABLE.of(Tile::from_char('+')), Some(1));
+    assert_eq!(PathCost::CAUTIOUS.of(Tile::from_char('∩')), None);
+    assert_eq!(PathCost::CAUTIOUS.of(Tile::from_char('#')), None);
+}
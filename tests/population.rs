@@ -0,0 +1,38 @@
+mod common;
+
+use rustyrogue::generator::layout::LayoutKind;
+use rustyrogue::generator::map::MapGenerator;
+use rustyrogue::generator::prefab::PrefabLibrary;
+use rustyrogue::map::grid::TileGrid;
+
+use common::same_tiles;
+
+fn generate(layout: LayoutKind, seed: u64, parallel: bool) -> TileGrid {
+    let mut map_generator = MapGenerator::new(160, 100);
+    map_generator
+        .set_prefabs(PrefabLibrary::default())
+        .set_layout(layout)
+        .set_seed(seed)
+        .set_depth(3)
+        .set_parallel(parallel)
+        .generate(6, 16);
+    map_generator.into_dungeon()
+}
+
+#[test]
+fn populating_in_parallel_matches_one_room_at_a_time() {
+    for layout in LayoutKind::ALL {
+        for seed in [3, 17] {
+            let sequential = generate(layout, seed, false);
+            for _ in 0..3 {
+                let parallel = generate(layout, seed, true);
+                assert!(
+                    same_tiles(&sequential, &parallel),
+                    "{} seed {}",
+                    layout.name(),
+                    seed
+                );
+            }
+        }
+    }
+}
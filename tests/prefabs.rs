@@ -0,0 +1,35 @@
+mod common;
+
+use rustyrogue::generator::prefab::{PrefabLibrary, PREFAB_DIRECTORY};
+use std::fs;
+use std::io;
+
+use common::TempDirectory;
+
+#[test]
+fn shipped_prefabs_are_built_in() {
+    assert!(!PrefabLibrary::builtin().is_empty());
+    assert!(!PrefabLibrary::load(PREFAB_DIRECTORY).unwrap().is_empty());
+
+    let missing = std::env::temp_dir().join("rustyrogue-no-prefabs-here");
+    assert!(!PrefabLibrary::load_or_builtin(missing).unwrap().is_empty());
+}
+
+#[test]
+fn broken_prefab_is_rejected() {
+    let temp = TempDirectory::new("prefabs");
+    let directory = temp.path();
+    // The anchor sits in the outer wall
+    fs::write(directory.join("broken.txt"), "#*###\n#...D\n#####\n").unwrap();
+
+    for result in [
+        PrefabLibrary::load(directory),
+        PrefabLibrary::load_or_builtin(directory),
+    ] {
+        let error = result.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        let message = error.to_string();
+        assert!(message.contains("broken.txt"), "{message}");
+        assert!(message.contains("anchor at line 1 column 2"), "{message}");
+    }
+}
@@ -0,0 +1,29 @@
+use rustyrogue::player::perk::LevelUpChoice;
+use rustyrogue::player::Player;
+
+#[test]
+fn big_exp_gain_carries_over_several_levels() {
+    let mut player = Player::new();
+    player.current_hp = 5;
+
+    // 100 for level 2, 200 for level 3 and 300 for level 4, 50 left over
+    assert_eq!(player.gain_exp(650), 3);
+    assert_eq!(player.level, 4);
+    assert_eq!(player.exp, 50);
+    assert_eq!(player.xp_for_next_level(), 400);
+    assert_eq!(player.pending_level_ups, 3);
+    assert_eq!(player.max_hp, 26);
+    assert_eq!(player.current_hp, player.max_hp);
+
+    // Every level gained gets its own choice
+    for _ in 0..3 {
+        assert!(player.choose_level_up(LevelUpChoice::Strength));
+    }
+    assert!(!player.choose_level_up(LevelUpChoice::Strength));
+    assert_eq!(player.strength, 11);
+
+    assert_eq!(player.gain_exp(349), 0);
+    assert_eq!(player.exp, 399);
+    assert_eq!(player.gain_exp(1), 1);
+    assert_eq!((player.level, player.exp), (5, 0));
+}
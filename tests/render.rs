@@ -0,0 +1,55 @@
+use rustyrogue::data::monsters;
+use rustyrogue::map::grid::TileGrid;
+use rustyrogue::map::types::Point;
+use rustyrogue::map::Map;
+use rustyrogue::tile::Tile;
+use rustyrogue::tui::Tui;
+
+fn glyph(map: &Map, point: Point) -> String {
+    Tui::map_tile_span(map, point).content.into_owned()
+}
+
+fn corridor() -> Map {
+    let mut tiles = TileGrid::from_rows(
+        ["############", "#@.........#", "############"]
+            .iter()
+            .map(|row| row.chars().map(Tile::from_char).collect())
+            .collect(),
+    );
+    tiles.set(Point::new(8, 1), Tile::Pit { visible: false });
+    let bat = monsters().find_by_name("bat").unwrap();
+    tiles.set(Point::new(9, 1), Tile::monster(bat));
+    Map::from_tiles(tiles)
+}
+
+#[test]
+fn revealed_tiles_are_drawn() {
+    let mut map = corridor();
+    let (player, pit, bat) = (Point::new(1, 1), Point::new(8, 1), Point::new(9, 1));
+    map.update_fov(player, 2);
+    assert_eq!(glyph(&map, pit), "·");
+
+    let revealed = map.reveal(player, None);
+    assert!(revealed.contains(&pit));
+    assert_eq!(glyph(&map, pit), "V");
+    // Monsters don't stay put, revealing shows the floor under them
+    assert_eq!(glyph(&map, bat), "·");
+
+    // Known, but the player still has to go and look
+    assert!(map.is_known(pit));
+    assert!(!map.is_explored(pit));
+    assert!(map.reveal(player, None).is_empty());
+}
+
+#[test]
+fn seen_tiles_are_remembered() {
+    let mut map = corridor();
+    let pit = Point::new(8, 1);
+    map.update_fov(Point::new(7, 1), 3);
+    assert_eq!(glyph(&map, pit), "V");
+
+    map.update_fov(Point::new(1, 1), 2);
+    assert!(map.is_explored(pit));
+    assert_eq!(map.get_tile(pit).visibility(), Some(false));
+    assert_eq!(glyph(&map, pit), "V");
+}
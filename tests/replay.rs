@@ -0,0 +1,38 @@
+use rustyrogue::game::{Action, GameSetup, Replay};
+use rustyrogue::generator::map::DungeonSize;
+
+#[test]
+fn replays_pick_up_where_the_run_was_left() {
+    let setup = GameSetup {
+        seed: Some(3),
+        size: DungeonSize {
+            width: 120,
+            height: 60,
+            min_room_size: 6,
+            max_room_size: 14,
+        },
+        ..Default::default()
+    };
+    let mut game = setup.start().unwrap();
+    for action in [
+        Action::AutoExplore,
+        Action::Move(1, 0),
+        Action::Interact,
+        Action::AutoExplore,
+        Action::Move(0, -1),
+    ] {
+        game.perform(action);
+    }
+
+    let text = game.get_replay().encode();
+    let resumed = Replay::parse(&text).unwrap().resume().unwrap();
+    assert_eq!(resumed.get_player_position(), game.get_player_position());
+    assert_eq!(
+        resumed.get_player().current_hp,
+        game.get_player().current_hp
+    );
+    assert_eq!(resumed.get_replay().encode(), text);
+
+    assert!(Replay::parse("seed 3\nmove 2 0\n").is_err());
+    assert!(Replay::parse("seed 3\nendless\nactions\n").is_err());
+}
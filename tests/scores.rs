@@ -0,0 +1,56 @@
+mod common;
+
+use rustyrogue::scores::{HighScores, ScoreEntry, MAX_SCORES};
+
+use common::TempDirectory;
+
+fn entry(score: u32, outcome: &str) -> ScoreEntry {
+    ScoreEntry {
+        score,
+        depth: 2,
+        level: 3,
+        turns: 400,
+        seed: 42,
+        outcome: outcome.to_string(),
+    }
+}
+
+#[test]
+fn scores_are_ranked_and_capped() {
+    let mut scores = HighScores::default();
+    assert_eq!(scores.add(entry(300, "fell into a pit")), Some(0));
+    assert_eq!(scores.add(entry(500, "escaped")), Some(0));
+    // Ties go to the older run
+    assert_eq!(scores.add(entry(300, "killed by a bat")), Some(2));
+
+    for _ in 0..MAX_SCORES {
+        scores.add(entry(1000, "escaped"));
+    }
+    assert_eq!(scores.entries().len(), MAX_SCORES);
+    assert_eq!(scores.add(entry(10, "succumbed to poison")), None);
+    assert!(scores.entries().iter().all(|entry| entry.score == 1000));
+}
+
+#[test]
+fn scores_survive_a_round_trip() {
+    let mut scores = HighScores::default();
+    scores.add(entry(120, "withered by an obelisk curse"));
+    scores.add(entry(900, "escaped"));
+
+    let directory = TempDirectory::new("scores");
+    let path = directory.path().join("scores.txt");
+    scores.save(&path).unwrap();
+    let loaded = HighScores::load(&path).unwrap();
+
+    assert_eq!(loaded.entries(), scores.entries());
+}
+
+#[test]
+fn broken_scores_are_rejected() {
+    assert!(HighScores::parse("12\tnope\n").is_err());
+    assert!(HighScores::parse("12\t1\t1\t10\t42\n").is_err());
+    assert!(HighScores::load("no/such/scores.txt")
+        .unwrap()
+        .entries()
+        .is_empty());
+}
@@ -0,0 +1,22 @@
+mod common;
+
+use rustyrogue::generator::layout::LayoutKind;
+use rustyrogue::generator::map::MapGenerator;
+use rustyrogue::map::grid::TileGrid;
+
+use common::same_tiles;
+
+fn generate(seed: u64) -> TileGrid {
+    let mut generator = MapGenerator::new(120, 60);
+    generator
+        .set_layout(LayoutKind::Mixed)
+        .set_seed(seed)
+        .generate(5, 12);
+    generator.into_dungeon()
+}
+
+#[test]
+fn same_seed_generates_the_same_level() {
+    assert!(same_tiles(&generate(9), &generate(9)));
+    assert!(!same_tiles(&generate(9), &generate(10)));
+}
@@ -0,0 +1,60 @@
+use crossterm::event::KeyCode;
+use rustyrogue::game::{Difficulty, GameSetup, MAP_DIRECTORY};
+use rustyrogue::tui::menu::{MainMenu, MenuChoice};
+use std::path::Path;
+
+#[test]
+fn difficulty_scales_damage() {
+    assert_eq!(Difficulty::Easy.scale_damage(5), 3);
+    assert_eq!(Difficulty::Normal.scale_damage(5), 5);
+    assert_eq!(Difficulty::Hard.scale_damage(5), 8);
+    assert_eq!(Difficulty::Easy.scale_damage(1), 1);
+    assert_eq!(Difficulty::Hard.scale_damage(0), 0);
+    assert_eq!("hard".parse::<Difficulty>(), Ok(Difficulty::Hard));
+    assert!("brutal".parse::<Difficulty>().is_err());
+}
+
+#[test]
+fn hand_made_maps_can_be_played() {
+    let setup = GameSetup {
+        seed: Some(7),
+        difficulty: Difficulty::Hard,
+        map: Some(Path::new(MAP_DIRECTORY).join("tutorial.txt")),
+        ..Default::default()
+    };
+    let game = setup.start().unwrap();
+    assert_eq!(game.get_seed(), 7);
+    assert_eq!(game.get_difficulty(), Difficulty::Hard);
+    assert!(game.get_objective().is_none());
+    assert!(!game.is_game_over());
+}
+
+#[test]
+fn menu_builds_the_setup() {
+    let mut menu = MainMenu::new(GameSetup::default());
+
+    // Without a run to continue, the seed is two down from new game
+    for _ in 0..2 {
+        assert!(menu.handle_key(KeyCode::Down, false).is_none());
+    }
+    for digit in "42".chars() {
+        menu.handle_key(KeyCode::Char(digit), false);
+    }
+    menu.handle_key(KeyCode::Down, false);
+    menu.handle_key(KeyCode::Right, false);
+
+    menu.handle_key(KeyCode::Up, false);
+    menu.handle_key(KeyCode::Up, false);
+    menu.handle_key(KeyCode::Up, false);
+    let Some(MenuChoice::Play(setup)) = menu.handle_key(KeyCode::Enter, false) else {
+        panic!("new game should start a run");
+    };
+    assert_eq!(setup.seed, Some(42));
+    assert_eq!(setup.difficulty, Difficulty::Hard);
+    assert!(setup.map.is_none());
+
+    assert!(matches!(
+        menu.handle_key(KeyCode::Char('q'), false),
+        Some(MenuChoice::Quit)
+    ));
+}
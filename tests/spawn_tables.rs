@@ -0,0 +1,192 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rustyrogue::data::item::ItemBook;
+use rustyrogue::data::monster::Bestiary;
+use rustyrogue::data::spawn::{Spawn, SpawnTables};
+use rustyrogue::data::{monsters, DATA_DIRECTORY};
+use rustyrogue::generator::map::MapGenerator;
+use rustyrogue::generator::prefab::PrefabLibrary;
+use rustyrogue::tile::Tile;
+use std::fs;
+use std::path::Path;
+
+const TABLES: &str = "
+[normal]
+room = normal
+rolls = 5
+monster = 1 0-2
+obelisk = 0 1
+
+[obelisk]
+room = obelisk
+rolls = 0
+obelisk = 0 1
+
+[secret]
+room = secret
+rolls = 0
+secret:100 = 0 1
+
+[vault]
+room = vault
+rolls = 0
+potion:100 = 0 1
+
+[boss]
+room = boss
+rolls = 0
+boss = 0 1
+
+[shrine]
+room = shrine
+rolls = 0
+shrine = 0 1
+
+[library]
+room = library
+rolls = 0
+sign = 0 1
+item:scroll of light = 0 1
+";
+
+fn bestiary() -> Bestiary {
+    let source = fs::read_to_string(Path::new(DATA_DIRECTORY).join("monsters.txt")).unwrap();
+    Bestiary::parse(&source).unwrap()
+}
+
+fn item_book() -> ItemBook {
+    let source = fs::read_to_string(Path::new(DATA_DIRECTORY).join("items.txt")).unwrap();
+    ItemBook::parse(&source).unwrap()
+}
+
+#[test]
+fn rolls_respect_min_and_max() {
+    let tables = SpawnTables::parse(TABLES, &bestiary(), &item_book()).unwrap();
+    let table = tables
+        .tables()
+        .find(|table| table.name == "normal")
+        .unwrap();
+    let mut rng = StdRng::seed_from_u64(3);
+
+    for _ in 0..100 {
+        let rolled = table.roll(&mut rng);
+        let count = |spawn| rolled.iter().filter(|&&rolled| rolled == spawn).count();
+        // Monsters stop at their max, the rolls left over have nothing to pick
+        assert_eq!(count(Spawn::Monster), 2);
+        assert_eq!(count(Spawn::Obelisk), 1);
+    }
+}
+
+#[test]
+fn tables_have_to_cover_every_depth() {
+    let cases = [
+        (
+            TABLES.replace("[secret]", "[secret]\nmin_depth = 2"),
+            "no table",
+        ),
+        (
+            format!("{}\n[more]\nroom = secret\nrolls = 1\npit = 1 1", TABLES),
+            "exactly one table",
+        ),
+        (
+            TABLES.replace("room = normal\n", "room = normal\nmax_depth = 4\n"),
+            "no table from depth 5",
+        ),
+        (
+            TABLES.replace("monster =", "monster:dragon ="),
+            "no monster called",
+        ),
+        (
+            TABLES.replace("secret:100", "secret:50"),
+            "has to be 1, 10, 100 or 1000",
+        ),
+        (TABLES.replace("0-2", "2-0"), "max below min"),
+        (
+            TABLES.replace("room = obelisk", "room = spawn"),
+            "kept empty",
+        ),
+        (
+            TABLES.replace("rolls = 0\nobelisk = 0 1", "rolls = 0"),
+            "doesn't spawn",
+        ),
+    ];
+    for (source, error) in cases {
+        let result = SpawnTables::parse(&source, &bestiary(), &item_book());
+        assert!(
+            result.as_ref().is_err_and(|e| e.contains(error)),
+            "expected '{}', got {:?}",
+            error,
+            result.map(|_| ())
+        );
+    }
+}
+
+#[test]
+fn report_matches_the_dungeon() {
+    let mut map_generator = MapGenerator::new(120, 80);
+    map_generator
+        .set_prefabs(PrefabLibrary::default())
+        .set_seed(4)
+        .generate(6, 14);
+    let report = map_generator.get_spawn_report();
+
+    let mut obelisks = 0;
+    let mut monsters_placed = 0;
+    for row in map_generator.get_dungeon().rows() {
+        for tile in row {
+            match tile {
+                Tile::Obelisk { .. } => obelisks += 1,
+                tile if tile.is_monster() => monsters_placed += 1,
+                _ => {}
+            }
+        }
+    }
+
+    assert_eq!(report.count("obelisk"), obelisks);
+    let reported_monsters: usize = monsters()
+        .kinds()
+        .map(|kind| report.count(&monsters().get(kind).name))
+        .sum();
+    assert_eq!(reported_monsters, monsters_placed);
+}
+
+#[test]
+fn special_rooms_stay_within_their_limits() {
+    for (seed, depth) in [(0, 1), (1, 1), (2, 1), (3, 5), (4, 5), (5, 5)] {
+        let mut map_generator = MapGenerator::new(200, 120);
+        map_generator
+            .set_prefabs(PrefabLibrary::default())
+            .set_seed(seed)
+            .set_depth(depth)
+            .generate(8, 20);
+        let report = map_generator.get_spawn_report();
+        let rooms = |room_type| report.rooms.get(room_type).copied().unwrap_or(0);
+
+        assert!(
+            rooms("boss") <= 1,
+            "seed {seed}: {} boss rooms",
+            rooms("boss")
+        );
+        assert!(
+            rooms("library") <= 1,
+            "seed {seed}: {} libraries",
+            rooms("library")
+        );
+        assert!(
+            rooms("shrine") <= 1,
+            "seed {seed}: {} shrines",
+            rooms("shrine")
+        );
+        assert!(
+            rooms("vault") <= 2,
+            "seed {seed}: {} vaults",
+            rooms("vault")
+        );
+        if depth == 1 {
+            assert_eq!(rooms("shrine"), 0, "seed {seed}: shrines start at depth 2");
+        }
+        // Bosses only ever stand in boss rooms
+        let bosses = report.count("ogre") + report.count("lich");
+        assert_eq!(bosses, rooms("boss"), "seed {seed}");
+    }
+}
@@ -0,0 +1,63 @@
+use rustyrogue::cli::{parse, Command};
+use rustyrogue::generator::map::MapGenerator;
+use rustyrogue::generator::stats::{BatchStats, LevelStats};
+use std::time::Duration;
+
+#[test]
+fn batch_statistics_add_up() {
+    let mut batch = BatchStats::new();
+    for seed in 0..3 {
+        let mut map_generator = MapGenerator::new(120, 80);
+        map_generator.set_seed(seed).generate(6, 14);
+        let level = LevelStats::measure(&map_generator, Duration::from_millis(seed + 1));
+        assert_eq!(level.seed, seed);
+        assert_eq!(level.rooms, map_generator.get_rooms().len());
+        assert_eq!(level.room_types.values().sum::<usize>(), level.rooms);
+        assert!(level.floor_coverage > 0.0 && level.floor_coverage < 1.0);
+        batch.add(level);
+    }
+
+    let summaries = batch.summaries();
+    let get = |name: &str| {
+        summaries
+            .iter()
+            .find(|(measure, _)| measure == name)
+            .map(|(_, summary)| *summary)
+            .unwrap()
+    };
+    let rooms = get("rooms");
+    assert!(rooms.min <= rooms.mean && rooms.mean <= rooms.max);
+    assert_eq!(
+        rooms.total as usize,
+        batch
+            .get_levels()
+            .iter()
+            .map(|level| level.rooms)
+            .sum::<usize>()
+    );
+    assert_eq!(get("type spawn").total, 3.0);
+    assert_eq!(get("generation ms").total.round(), 6.0);
+
+    let csv = batch.to_csv();
+    assert!(csv.starts_with("measure,mean,min,max,total\n"));
+    assert_eq!(csv.lines().count(), summaries.len() + 1);
+    assert!(batch.to_table().starts_with("3 levels, "));
+}
+
+#[test]
+fn stats_command_parses() {
+    let args: Vec<String> = "stats --count 20 --seed 7 --layout caves --csv"
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+    let Ok(Command::Stats(options)) = parse(&args) else {
+        panic!("stats should parse");
+    };
+    assert_eq!((options.count, options.seed), (20, Some(7)));
+    assert!(options.csv);
+
+    for line in ["stats --count 0", "stats --count", "stats --out a.txt"] {
+        let args: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+        assert!(parse(&args).is_err(), "'{}' should be rejected", line);
+    }
+}
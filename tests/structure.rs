@@ -0,0 +1,89 @@
+use rustyrogue::generator::layout::LayoutKind;
+use rustyrogue::generator::map::MapGenerator;
+use rustyrogue::generator::structure::Corridor;
+use rustyrogue::map::types::Point;
+
+#[test]
+fn structure_describes_every_room() {
+    for layout in LayoutKind::ALL {
+        let mut map_generator = MapGenerator::new(120, 80);
+        let structure = map_generator
+            .set_layout(layout)
+            .set_seed(11)
+            .generate(6, 14)
+            .clone();
+        let rooms = map_generator.get_rooms();
+        let dungeon = map_generator.get_dungeon();
+
+        assert_eq!(structure.rooms.len(), rooms.len());
+        assert_eq!(structure.adjacency.len(), rooms.len());
+        for (index, info) in structure.rooms.iter().enumerate() {
+            assert_eq!(info.id, index);
+            assert_eq!(info.room.location, rooms[index].location);
+            for &(point, tile) in &info.features {
+                assert!(info.room.contains_interior(point));
+                assert!(dungeon.get(point) == Some(tile));
+            }
+            for &other in structure.neighbours(index) {
+                assert_ne!(other, index);
+                assert!(structure.neighbours(other).contains(&index));
+            }
+        }
+        for corridor in &structure.corridors {
+            assert!(!corridor.points.is_empty());
+            assert!(corridor.length >= corridor.points.len());
+        }
+    }
+}
+
+#[test]
+fn every_room_can_be_walked_to_from_spawn() {
+    for seed in 0..4 {
+        let mut map_generator = MapGenerator::new(160, 100);
+        map_generator.set_seed(seed).generate(8, 20);
+        let report = map_generator.get_connectivity_report();
+        assert!(report.is_valid(), "seed {seed}: {}", report.summary());
+        let structure = map_generator.get_structure();
+        let spawn = structure
+            .get_spawn_room()
+            .expect("levels have a spawn room");
+        assert!(structure.bsp.is_some());
+        assert!(!structure.corridors.is_empty());
+
+        let mut seen = vec![false; structure.rooms.len()];
+        let mut stack = vec![spawn.id];
+        seen[spawn.id] = true;
+        while let Some(id) = stack.pop() {
+            for &next in structure.neighbours(id) {
+                if !seen[next] {
+                    seen[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        assert!(seen.iter().all(|&seen| seen), "seed {seed}");
+        assert!(structure
+            .rooms
+            .iter()
+            .all(|info| !info.entrances.is_empty()));
+    }
+}
+
+#[test]
+fn corridors_keep_only_their_corners() {
+    let path: Vec<Point> = [(1, 1), (2, 1), (3, 1), (3, 1), (3, 2), (3, 3), (4, 3)]
+        .into_iter()
+        .map(|(x, y)| Point::new(x, y))
+        .collect();
+    let corridor = Corridor::from_path(&path);
+    assert_eq!(
+        corridor.points,
+        vec![
+            Point::new(1, 1),
+            Point::new(3, 1),
+            Point::new(3, 3),
+            Point::new(4, 3)
+        ]
+    );
+    assert_eq!(corridor.length, 6);
+}
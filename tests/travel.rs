@@ -0,0 +1,117 @@
+use rustyrogue::data::{items, monsters};
+use rustyrogue::game::Game;
+use rustyrogue::map::grid::TileGrid;
+use rustyrogue::map::types::Point;
+use rustyrogue::map::Map;
+use rustyrogue::tile::Tile;
+
+// Plays `tiles` as a hand made map
+fn game_on(tiles: TileGrid) -> Game {
+    Game::from_tiles(tiles, 1).unwrap()
+}
+
+fn parse(source: &str) -> TileGrid {
+    Map::parse(source).unwrap().get_grid().unwrap().clone()
+}
+
+fn last_message(game: &Game) -> &str {
+    &game.get_game_log_messages().last().unwrap().message
+}
+
+// A winding corridor that can't be seen all at once from the start
+const WINDING: &str = "\
+##########
+#@.......#
+########.#
+#........#
+#.########
+#........#
+##########
+";
+
+#[test]
+fn auto_explore_sees_everything() {
+    let mut game = game_on(parse(WINDING));
+    game.auto_explore();
+    assert_eq!(
+        last_message(&game),
+        "There is nothing left to explore nearby."
+    );
+
+    let map = game.get_map();
+    let grid = map.get_grid().unwrap();
+    for point in grid.points() {
+        if grid.get(point).unwrap().is_walkable() {
+            assert!(map.is_explored(point), "{:?} was never seen", point);
+        }
+    }
+    assert_ne!(game.get_player_position(), Point::new(1, 1));
+}
+
+#[test]
+fn auto_explore_stops_for_monsters() {
+    let mut tiles = parse(WINDING);
+    let bat = monsters().find_by_name("bat").unwrap();
+    tiles.set(Point::new(8, 5), Tile::monster(bat));
+    let mut game = game_on(tiles);
+
+    game.auto_explore();
+    assert_eq!(last_message(&game), "You stop. There is a monster in view!");
+    assert!(game.get_map().is_monster_in_view());
+
+    // And won't start again while it can still be seen
+    let position = game.get_player_position();
+    game.auto_explore();
+    assert_eq!(
+        last_message(&game),
+        "You can't explore with a monster in view."
+    );
+    assert_eq!(game.get_player_position(), position);
+}
+
+#[test]
+fn auto_explore_stops_for_items_on_the_floor() {
+    let mut tiles = parse(WINDING);
+    let potion = items().find_by_name("potion of healing").unwrap();
+    tiles.set(
+        Point::new(1, 5),
+        Tile::Item {
+            visible: false,
+            item: potion,
+        },
+    );
+    let mut game = game_on(tiles);
+
+    game.auto_explore();
+    assert_eq!(
+        last_message(&game),
+        "You stop. You spot something interesting."
+    );
+}
+
+#[test]
+fn travel_needs_a_safe_known_route() {
+    // The far side can be seen across the pits but not walked to
+    let mut game = game_on(parse("#######\n#@.VV.#\n#######\n"));
+    let far_side = Point::new(5, 1);
+    assert!(game.get_map().is_known(far_side));
+
+    game.travel_to(far_side);
+    assert_eq!(last_message(&game), "You don't know how to get there.");
+    assert_eq!(game.get_player_position(), Point::new(1, 1));
+
+    game.travel_to_stairs();
+    assert_eq!(last_message(&game), "You don't know of any stairs.");
+
+    game.travel_to(Point::new(2, 1));
+    assert_eq!(last_message(&game), "You arrive at your destination.");
+    assert_eq!(game.get_player_position(), Point::new(2, 1));
+}
+
+#[test]
+fn travel_to_stairs_walks_to_the_nearest_seen_stairs() {
+    let mut game = game_on(parse("########\n#@....>#\n########\n"));
+    game.travel_to_stairs();
+    assert_eq!(last_message(&game), "You arrive at your destination.");
+    assert_eq!(game.get_player_position(), Point::new(6, 1));
+}
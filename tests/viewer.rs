@@ -0,0 +1,82 @@
+use rustyrogue::generator::layout::LayoutKind;
+use rustyrogue::generator::map::MapGenerator;
+use rustyrogue::map::types::Point;
+use rustyrogue::map::Map;
+use rustyrogue::tui::widgets::map_view::{Overlay, ViewedLevel, ViewerState};
+
+#[test]
+fn overlays_cycle_back_to_the_map() {
+    let mut overlay = Overlay::Tiles;
+    for _ in 0..Overlay::ALL.len() {
+        overlay = overlay.next();
+    }
+    assert_eq!(overlay, Overlay::Tiles);
+}
+
+#[test]
+fn camera_follows_the_cursor() {
+    let (map, viewport) = ((100, 50), (20, 10));
+    let mut state = ViewerState::new();
+
+    state.move_cursor(-1, -1, map, viewport);
+    assert_eq!(state.cursor, Point::new(0, 0));
+
+    // A page to the right drags the camera along
+    state.move_cursor(25, 0, map, viewport);
+    assert_eq!(state.cursor, Point::new(25, 0));
+    assert_eq!(state.camera, Point::new(6, 0));
+
+    // Never past the far edge of the map
+    state.move_cursor(500, 500, map, viewport);
+    assert_eq!(state.cursor, Point::new(99, 49));
+    assert_eq!(state.camera, Point::new(80, 40));
+
+    // A bigger terminal shows more, the camera moves back to fill it
+    state.follow_cursor(map, (60, 30));
+    assert_eq!(state.camera, Point::new(40, 20));
+}
+
+#[test]
+fn bsp_partitions_cover_the_level() {
+    for layout in LayoutKind::ALL {
+        let mut map_generator = MapGenerator::new(120, 60);
+        map_generator.set_layout(layout).set_seed(4).generate(6, 14);
+        let level = ViewedLevel::from_generator(&map_generator);
+        let partitions = level.get_partitions();
+
+        if matches!(layout, LayoutKind::Bsp | LayoutKind::Mixed) {
+            let area: usize = partitions
+                .iter()
+                .map(|partition| partition.width as usize * partition.height as usize)
+                .sum();
+            assert_eq!(
+                area,
+                120 * 60,
+                "{} partitions overlap or leave gaps",
+                layout
+            );
+        } else {
+            assert!(partitions.is_empty(), "{} has no BSP tree", layout);
+        }
+    }
+}
+
+#[test]
+fn curses_and_unreachable_cells_are_found() {
+    let map = Map::parse(
+        "##########\n\
+         #@.......#\n\
+         #...|....#\n\
+         ##########\n\
+         #..#######\n\
+         ##########\n",
+    )
+    .unwrap();
+    let level = ViewedLevel::from_map(&map, "test".to_string());
+
+    assert!(level.get_cursed().contains(&Point::new(5, 2)));
+    assert!(!level.get_cursed().contains(&Point::new(1, 4)));
+    assert_eq!(level.get_unreachable().len(), 2);
+    assert!(level.inspect(Point::new(4, 2)).contains("obelisk"));
+    assert!(level.inspect(Point::new(1, 4)).contains("unreachable"));
+}